@@ -1,44 +1,129 @@
 // BODMAS Calculator
-// This program evaluates mathematical expressions using the `meval` crate,
+// This program evaluates mathematical expressions using the shared `mathrust` library,
 // which respects the BODMAS/PEMDAS order of operations.
+// Each expression is worked through one operation at a time, naming the rule for each step:
+// Brackets, then Orders, then Division and Multiplication, then Addition and Subtraction.
 
-use std::io::{self, Write};
+// A session remembers the last answer as `ans`, along with any values and functions given names,
+// and the line editor keeps the lines entered between runs so the up arrow can recall them.
+
+use std::env;
+use std::path::PathBuf;
+
+use mathrust::bodmas::{bodmas_working, write_answer};
+use mathrust::parser::Expr;
+use mathrust::session::{Answer, Entry, Session};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+// The file in the home directory that the lines entered are saved to.
+const HISTORY_FILE: &str = ".mathrust_history";
 
 fn main() {
     println!("=== BODMAS Calculator ===");
     println!("Enter mathematical expressions like: 20 - 3 * 4 or 30 / (15 - 12)");
-    println!("Supported operations: +, -, *, /, %, ^, ( )");
+    println!("Supported operations: +, -, * or ×, / or ÷, %, ^ or ², √, ( ), and functions such as sqrt(2)");
+    println!("A multiplication sign may be left out, as in 2(3 + 4).");
+    println!("Use 'ans' for the last result, name values with r = 3.5 or area = pi*r^2,");
+    println!("and define functions with f(x) = 2x+1. Type 'vars' to list them.");
+    println!("The up arrow recalls earlier lines, even from before the calculator was last closed.");
+    println!("Type 'exact' for answers as fractions and surds, such as 1/2 or 3√2, and 'decimal' to switch back.");
     println!("Type 'quit' to exit.\n");
 
-    loop {
-        print!("Enter expression: ");
-        // Ensure the prompt is displayed immediately.
-        io::stdout().flush().unwrap();
+    let mut editor = DefaultEditor::new().expect("Failed to start the line editor");
+    let history = env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY_FILE));
+    if let Some(path) = &history {
+        // There is no history to load the first time the calculator is run.
+        let _ = editor.load_history(path);
+    }
 
-        let mut input = String::new();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Failed to read input");
+    // Answers are given as decimals until exact answers are asked for.
+    let mut exact = false;
+    let mut session = Session::new();
 
-        let input = input.trim();
+    loop {
+        let line = match editor.readline("Enter expression: ") {
+            Ok(line) => line,
+            // Ctrl-C, Ctrl-D and the end of piped input all leave the calculator.
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => break,
+            Err(e) => {
+                println!("Error: {}", e);
+                break;
+            }
+        };
+
+        let input = line.trim();
+        if input.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(input);
 
         if input.eq_ignore_ascii_case("quit") {
-            println!("Goodbye!");
             break;
         }
+        if input.eq_ignore_ascii_case("exact") || input.eq_ignore_ascii_case("decimal") {
+            exact = input.eq_ignore_ascii_case("exact");
+            println!("Answers will be given {}.\n", if exact { "exactly where possible" } else { "as decimals" });
+            continue;
+        }
+        if input.eq_ignore_ascii_case("vars") {
+            let definitions = session.definitions();
+            if definitions.is_empty() {
+                println!("Nothing has been defined yet.");
+            }
+            for definition in definitions {
+                println!("  {}", definition);
+            }
+            println!();
+            continue;
+        }
 
-        // Use the `meval` crate to evaluate the expression.
-        // It's a powerful and safe way to handle mathematical expressions.
-        match meval::eval_str(input) {
-            Ok(result) => {
-                // Check if the result is an integer.
-                if result.fract() == 0.0 {
-                    println!("Result: {}\n", result as i64);
-                } else {
-                    println!("Result: {}\n", result);
-                }
+        match session.enter(input) {
+            Ok(Entry::Calculation { expr, answer }) => show_working(&expr, &answer, exact, "Result: "),
+            Ok(Entry::Assignment { name, expr, answer }) => {
+                show_working(&expr, &answer, exact, &format!("{} = ", name))
             }
-            Err(e) => println!("Error: {}\n", e),
+            Ok(Entry::Definition { name }) => println!("Defined {}.\n", name),
+            Err(e) => println!("Error: {}\n", e.report(input)),
+        }
+    }
+
+    if let Some(path) = &history
+        && let Err(e) = editor.save_history(path)
+    {
+        println!("Could not save the history: {}", e);
+    }
+    println!("Goodbye!");
+}
+
+// Shows the working, with the session's names replaced by their values, then the answer after `label`.
+// The answer is the one the working ends with, so an answer that has been rounded is given to 10
+// significant figures after `≈`, as it would be used in place of ans or its name.
+fn show_working(expr: &Expr, answer: &Answer, exact: bool, label: &str) {
+    let working = match bodmas_working(expr) {
+        Ok(working) => working,
+        Err(e) => {
+            println!("Error: {}\n", e);
+            return;
+        }
+    };
+    println!("  {}", expr);
+    for step in &working.steps {
+        println!("{}", step);
+    }
+
+    let result = working.written_answer();
+    match answer.exact.as_ref().filter(|_| exact) {
+        // A whole number is the same either way, so there is no decimal to add.
+        Some(exact_answer) if exact_answer.to_fraction().is_some_and(|f| f.is_integer()) => {
+            println!("{}{}\n", label, exact_answer)
         }
+        // A fraction whose decimal ends is equal to it; anything else is only close to its decimal.
+        Some(exact_answer) => match exact_answer.to_fraction().map(|fraction| fraction.to_decimal()) {
+            Some((decimal, true)) => println!("{}{} (= {})\n", label, exact_answer, decimal),
+            _ => println!("{}{} ({})\n", label, exact_answer, write_answer(&working.answer, false)),
+        },
+        None if exact => println!("{}{} (there is no exact form)\n", label, result),
+        None => println!("{}{}\n", label, result),
     }
 }
@@ -0,0 +1,63 @@
+// Import the necessary libraries from the standard library.
+use std::io; // Used for handling user input.
+
+// The bounds are worked out by the shared `mathrust` library.
+// Each rounded value lies somewhere between its lower and upper bound, so the bounds of a
+// calculation come from trying the bounds of each value and keeping the smallest and largest
+// answers. For example, the upper bound of d / t uses the upper bound of d but the lower bound
+// of t, because dividing by a smaller number gives a larger answer.
+use mathrust::bounds::{BoundsCalculation, Quantity};
+
+// The main function, where the program execution begins.
+fn main() {
+    // Prompt the user to enter the calculation.
+    println!("Enter the calculation, using letters for the rounded values (e.g., d / t or (a + b) * c):");
+
+    // Create a mutable string to store the user's input for the calculation.
+    let mut expression = String::new();
+    // Read the line of input from the user.
+    io::stdin().read_line(&mut expression).expect("Failed to read line");
+
+    // Read each value with its degree of accuracy, one per line, until a blank line.
+    println!("Enter each value and its accuracy, one per line (e.g., d = 120 (nearest 10) or t = 2.5 (1 d.p.)).");
+    println!("Enter a blank line when you have finished:");
+    let mut quantities = Vec::new();
+    loop {
+        let mut quantity_input = String::new();
+        // Stop at a blank line or at the end of the input.
+        if io::stdin().read_line(&mut quantity_input).expect("Failed to read line") == 0
+            || quantity_input.trim().is_empty()
+        {
+            break;
+        }
+        match quantity_input.parse::<Quantity>() {
+            Ok(quantity) => quantities.push(quantity),
+            Err(e) => {
+                println!("Error: {}", e.report(quantity_input.trim_end()));
+                return;
+            }
+        }
+    }
+
+    // Work out the bounds of each operation in turn.
+    let calculation = match BoundsCalculation::calculate(&expression, quantities) {
+        Ok(calculation) => calculation,
+        Err(e) => {
+            println!("Error: {}", e.report(expression.trim_end()));
+            return;
+        }
+    };
+
+    // Print the working, showing which bound of each value was used.
+    println!();
+    for (i, step) in calculation.working().iter().enumerate() {
+        println!("{}. {}", i + 1, step);
+    }
+
+    // Print the final result to the console.
+    println!("\nThe lower bound is: {}", calculation.interval.lower);
+    println!("The upper bound is: {}", calculation.interval.upper);
+    if !calculation.exact {
+        println!("(Divisions that do not end are given to 10 significant figures.)");
+    }
+}
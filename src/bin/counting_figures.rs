@@ -0,0 +1,46 @@
+// Import the necessary libraries from the standard library.
+use std::io; // Used for handling user input.
+
+// Counting the figures is done by the shared `mathrust` library. It works on the digits exactly
+// as they were typed, because 2.50 and 2.5 have the same value but a different number of
+// significant figures.
+// For example, in 0.003040 the first three zeros only hold the place value, the 3, 0 and 4 are
+// significant, and the last 0 is significant because it comes after the decimal point.
+use mathrust::figures::{DigitRole, FigureCount};
+
+// The main function, where the program execution begins.
+fn main() {
+    // Prompt the user to enter a number.
+    println!("Enter a number to count its significant figures and decimal places:");
+
+    // Create a mutable string to store the user's input for the number.
+    let mut number_input = String::new();
+    // Read the line of input from the user.
+    io::stdin().read_line(&mut number_input).expect("Failed to read line");
+
+    // Read the digits of the number, working out the role of each one.
+    let count = match FigureCount::count(&number_input) {
+        Ok(count) => count,
+        Err(e) => {
+            println!("Error: {}", e.report(number_input.trim_end()));
+            return;
+        }
+    };
+
+    // Print the number with the role of each digit marked underneath it.
+    println!("\n  {}", count.number);
+    println!("  {}", count.markers());
+    println!("s = significant, l = leading zero, t = trailing zero, ? = ambiguous zero\n");
+
+    // Print how many of each kind of zero there are.
+    println!("Leading zeros (not significant): {}", count.count_of(DigitRole::LeadingZero));
+    println!("Trailing zeros after the decimal point (significant): {}", count.count_of(DigitRole::TrailingZero));
+    println!(
+        "Zeros at the end of a whole number (significant only if it was rounded to them): {}",
+        count.count_of(DigitRole::AmbiguousZero)
+    );
+
+    // Print the final result to the console.
+    println!("\nSignificant figures: {}", count.significant_figures_text());
+    println!("Decimal places: {}", count.decimal_places);
+}
@@ -4,6 +4,9 @@
 
 use std::io;
 
+// The missing value is found by the shared `mathrust` library using cross-multiplication.
+use mathrust::fractions::{parse_equivalent, solve_equivalent};
+
 // This is the main function where our program starts.
 fn main() {
     println!("Equivalent Fraction Calculator");
@@ -29,79 +32,67 @@ fn main() {
             break;
         }
 
-        // Split the input string into two parts at the "=" sign.
-        let parts: Vec<&str> = clean_input.split('=').collect();
-        if parts.len() != 2 {
-            println!("Invalid format. Please use the format a/b = c/d");
-            continue;
-        }
-
-        // Split the first fraction into numerator and denominator.
-        let first_fraction: Vec<&str> = parts[0].trim().split('/').collect();
-        // Split the second fraction into numerator and denominator.
-        let second_fraction: Vec<&str> = parts[1].trim().split('/').collect();
-
-        if first_fraction.len() != 2 || second_fraction.len() != 2 {
-            println!("Invalid fraction format. Please use a/b = c/d");
-            continue;
-        }
-
-        // Now we parse the strings into numbers. 'x' will be parsed as an error, which we catch.
-        let a = first_fraction[0].parse::<f64>();
-        let b = first_fraction[1].parse::<f64>();
-        let c = second_fraction[0].parse::<f64>();
-        let d = second_fraction[1].parse::<f64>();
+        // Split the input at the "=" sign and each side at its "/", then parse the four values
+        // into exact fractions, so the answer is never a rounded decimal.
+        // A letter such as 'x', or a '?', marks the unknown.
+        let [a, b, c, d] = match parse_equivalent(clean_input) {
+            Ok(values) => values,
+            Err(e) => {
+                // The report underlines the part of the input that caused the problem.
+                println!("Error: {}", e.report(clean_input));
+                continue;
+            }
+        };
 
-        // We check which of the values is the unknown 'x' and solve for it.
-        // The logic is based on the cross-multiplication rule: if a/b = c/d, then a*d = b*c.
-        if a.is_err() {
-            // If 'a' is the unknown value.
-            // a = (b * c) / d
-            let b_val = b.unwrap();
-            let c_val = c.unwrap();
-            let d_val = d.unwrap();
-            let result = (b_val * c_val) / d_val;
-            println!("The equation is x/{} = {}/{}", b_val, c_val, d_val);
-            println!("To find x, we calculate ({} * {}) / {}", b_val, c_val, d_val);
-            println!("The value of x is: {}", result);
-        } else if b.is_err() {
-            // If 'b' is the unknown value.
-            // b = (a * d) / c
-            let a_val = a.unwrap();
-            let c_val = c.unwrap();
-            let d_val = d.unwrap();
-            let result = (a_val * d_val) / c_val;
-            println!("The equation is {}/x = {}/{}", a_val, c_val, d_val);
-            println!("To find x, we calculate ({} * {}) / {}", a_val, d_val, c_val);
-            println!("The value of x is: {}", result);
-        } else if c.is_err() {
-            // If 'c' is the unknown value.
-            // c = (a * d) / b
-            let a_val = a.unwrap();
-            let b_val = b.unwrap();
-            let d_val = d.unwrap();
-            let result = (a_val * d_val) / b_val;
-            println!("The equation is {}/{} = x/{}", a_val, b_val, d_val);
-            println!("To find x, we calculate ({} * {}) / {}", a_val, d_val, b_val);
-            println!("The value of x is: {}", result);
-        } else if d.is_err() {
-            // If 'd' is the unknown value, like in the example 12/30 = 4/x
-            // d = (b * c) / a
-            let a_val = a.unwrap();
-            let b_val = b.unwrap();
-            let c_val = c.unwrap();
+        // Solve for the unknown using the cross-multiplication rule: if a/b = c/d, then a*d = b*c.
+        let result = match solve_equivalent(a, b, c, d) {
+            Ok(result) => result,
+            Err(e) => {
+                println!("Error: {}", e);
+                println!("\nEnter another problem or type 'exit' to quit.");
+                continue;
+            }
+        };
 
-            // Here we follow the logic from your example.
-            // 1. Find what you need to divide by to get from one numerator to the other.
-            let divisor = a_val / c_val;
-            println!("To get from the first numerator ({}) to the second ({}), you divide by {}", a_val, c_val, divisor);
+        // Explain the working for whichever value was unknown.
+        match (a, b, c, d) {
+            (None, Some(b_val), Some(c_val), Some(d_val)) => {
+                // a = (b * c) / d
+                println!("The equation is x/{} = {}/{}", b_val, c_val, d_val);
+                println!("To find x, we calculate ({} * {}) / {}", b_val, c_val, d_val);
+            }
+            (Some(a_val), None, Some(c_val), Some(d_val)) => {
+                // b = (a * d) / c
+                println!("The equation is {}/x = {}/{}", a_val, c_val, d_val);
+                println!("To find x, we calculate ({} * {}) / {}", a_val, d_val, c_val);
+            }
+            (Some(a_val), Some(b_val), None, Some(d_val)) => {
+                // c = (a * d) / b
+                println!("The equation is {}/{} = x/{}", a_val, b_val, d_val);
+                println!("To find x, we calculate ({} * {}) / {}", a_val, d_val, b_val);
+            }
+            (Some(a_val), Some(b_val), Some(c_val), None) => {
+                // If 'd' is the unknown value, like in the example 12/30 = 4/x
+                // 1. Find what you need to divide by to get from one numerator to the other.
+                match a_val.checked_div(c_val) {
+                    Some(divisor) => {
+                        println!("To get from the first numerator ({}) to the second ({}), you divide by {}", a_val, c_val, divisor);
 
-            // 2. Divide the denominator by the same number.
-            let result = b_val / divisor;
-            println!("So, we divide the first denominator ({}) by the same number ({})", b_val, divisor);
+                        // 2. Divide the denominator by the same number.
+                        println!("So, we divide the first denominator ({}) by the same number ({})", b_val, divisor);
+                    }
+                    None => {
+                        println!("To find x, we calculate ({} * {}) / {}", b_val, c_val, a_val);
+                    }
+                }
+            }
+            _ => {}
+        }
+        // Show the decimal value as well when the answer is not a whole number.
+        if result.is_integer() {
             println!("The value of x is: {}", result);
         } else {
-            println!("No unknown value 'x' found in the equation.");
+            println!("The value of x is: {} (≈ {})", result, result.to_f64());
         }
         println!("\nEnter another problem or type 'exit' to quit.");
     }
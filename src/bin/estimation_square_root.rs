@@ -1,29 +1,9 @@
 // Import the necessary libraries from the standard library.
 use std::io; // Used for handling user input.
 
-// This function estimates the square root of a number `n`.
-fn estimate_square_root(n: f64) -> f64 {
-    // First, find the integer part of the square root.
-    // We can do this by taking the square root and truncating the decimal part.
-    let integer_part = n.sqrt().trunc();
-
-    // Find the two perfect squares the number `n` lies between.
-    let lower_square = integer_part * integer_part;
-    let upper_square = (integer_part + 1.0) * (integer_part + 1.0);
-
-    // Calculate how far the number `n` is from the lower square.
-    let distance_from_lower = n - lower_square;
-    // Calculate the total distance between the two squares.
-    let total_distance = upper_square - lower_square;
-
-    // Estimate the decimal part of the square root.
-    // This is done by finding the proportion of the distance `n` is from the lower square
-    // relative to the total distance between the squares.
-    let decimal_part_estimate = distance_from_lower / total_distance;
-
-    // The final estimated square root is the integer part plus the estimated decimal part.
-    integer_part + decimal_part_estimate
-}
+// The estimate is calculated by the shared `mathrust` library. It finds the two perfect squares
+// the number lies between and estimates the decimal part from how far along it is between them.
+use mathrust::rounding::{estimate_square_root_to_places, surrounding_squares};
 
 // The main function, where the program execution begins.
 fn main() {
@@ -66,17 +46,19 @@ fn main() {
         }
     };
 
-    // Calculate the estimated square root using the `estimate_square_root` function.
-    let estimated_sqrt = estimate_square_root(number);
-
-    // Round the estimated square root to the desired number of decimal places.
-    let multiplier = 10.0_f64.powi(decimal_places as i32);
-    let rounded_estimate = (estimated_sqrt * multiplier).round() / multiplier;
+    // Calculate the estimated square root, rounded to the desired number of decimal places.
+    let rounded_estimate = match estimate_square_root_to_places(number, decimal_places) {
+        Ok(rounded) => rounded,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
 
-    // Find the two perfect squares the number is between.
-    let integer_part = number.sqrt().trunc() as u32;
-    let lower_square = integer_part * integer_part;
-    let upper_square = (integer_part + 1) * (integer_part + 1);
+    // Find the two perfect squares the number is between. The estimate above has already
+    // checked that they can be worked out.
+    let (integer_part, lower_square, upper_square) =
+        surrounding_squares(number).expect("the estimate found the same squares");
 
     // Print the explanation and the final result.
     println!("\n{} is between {} ({}²) and {} ({}²).", number, lower_square, integer_part, upper_square, integer_part + 1);
@@ -0,0 +1,65 @@
+// This program adds, subtracts, multiplies and divides fractions and mixed numbers.
+// It shows each step of the working: writing mixed numbers as improper fractions,
+// finding a common denominator, combining the fractions, and simplifying the answer.
+
+use std::io;
+
+// The working itself is done by the shared `mathrust` library using exact fractions.
+use mathrust::fractions::FractionCalculation;
+use mathrust::standard_form::DIVISION_FIGURES;
+
+// This is the main function where our program starts.
+fn main() {
+    println!("Fraction Arithmetic Calculator");
+    println!("Enter a calculation with a space either side of the operator (+, -, *, /, × or ÷)");
+    println!("For example: 2 1/3 + 3/4 or 5/6 ÷ 1 1/2");
+
+    // This loop allows the user to do multiple calculations without restarting the program.
+    loop {
+        // Create a new, empty String to hold the user's input.
+        let mut input = String::new();
+
+        // Read the user's input from the command line, stopping at the end of the input.
+        if io::stdin().read_line(&mut input).expect("Failed to read line") == 0 {
+            break;
+        }
+
+        // Remove any extra whitespace from the input.
+        let clean_input = input.trim();
+
+        // If the user types "exit", the program will stop.
+        if clean_input == "exit" {
+            break;
+        }
+
+        // Split the input into the two fractions and the operation between them, and work
+        // through the calculation.
+        let calculation = match clean_input.parse::<FractionCalculation>() {
+            Ok(calculation) => calculation,
+            Err(e) => {
+                // The report underlines the part of the input that could not be read.
+                println!("Error: {}", e.report(clean_input));
+                println!("\nEnter another calculation or type 'exit' to quit.");
+                continue;
+            }
+        };
+
+        println!("\nWorking out {}:", clean_input);
+        for (i, step) in calculation.steps().iter().enumerate() {
+            println!("{}. {}", i + 1, step);
+        }
+
+        // Give the answer as an improper fraction, a mixed number and a decimal.
+        // The decimal is found by dividing exactly, so it is only rounded if it recurs.
+        let result = calculation.result;
+        println!("\nImproper fraction: {}", result);
+        println!("Mixed number: {}", result.to_mixed_string());
+        if calculation.exact_decimal {
+            println!("Decimal: {}", calculation.decimal);
+        } else {
+            println!("Decimal: {} (to {} significant figures)", calculation.decimal, DIVISION_FIGURES);
+        }
+
+        println!("\nEnter another calculation or type 'exit' to quit.");
+    }
+}
@@ -1,32 +1,8 @@
 // Import the necessary libraries from the standard library.
 use std::io; // Used for handling user input.
 
-// This function calculates the greatest common divisor (GCD) of two numbers using the Euclidean algorithm.
-fn gcd(a: u32, b: u32) -> u32 {
-    // The Euclidean algorithm is an efficient method for computing the GCD of two integers.
-    // It is based on the principle that the greatest common divisor of two numbers does not change
-    // if the larger number is replaced by its difference with the smaller number.
-    let mut temp_a = a;
-    let mut temp_b = b;
-    while temp_b != 0 {
-        let t = temp_b;
-        temp_b = temp_a % temp_b;
-        temp_a = t;
-    }
-    temp_a
-}
-
-// This function calculates the least common multiple (LCM) of two numbers.
-fn lcm(a: u32, b: u32) -> u32 {
-    // The LCM of two numbers can be calculated using the formula: LCM(a, b) = (|a * b|) / GCD(a, b).
-    // To avoid potential overflow from `a * b`, we can rewrite the formula as: LCM(a, b) = |a / GCD(a, b) * b|.
-    // If either `a` or `b` is zero, the LCM is 0.
-    if a == 0 || b == 0 {
-        0
-    } else {
-        (a / gcd(a, b)) * b
-    }
-}
+// The LCM itself is calculated by the shared `mathrust` library.
+use mathrust::number::{exact_lcm_of, parse_whole_numbers};
 
 // The main function, where the program execution begins.
 fn main() {
@@ -38,23 +14,30 @@ fn main() {
     // Read the line of input from the user.
     io::stdin().read_line(&mut input).expect("Failed to read line");
 
-    // Parse the user's input into a vector of 32-bit unsigned integers.
-    let numbers: Vec<u32> = input
-        .split_whitespace() // Split the input string by whitespace.
-        .filter_map(|s| s.parse().ok()) // Attempt to parse each part into a u32, filtering out any that fail.
-        .collect(); // Collect the valid numbers into a vector.
-
-    // Check if any numbers were entered.
-    if numbers.is_empty() {
-        println!("No valid numbers were entered.");
-        return;
-    }
+    // Parse the user's input into a vector of 64-bit unsigned integers. A range such as 1..10
+    // stands for every number from 1 to 10.
+    // Any part that is not a whole number is reported, rather than silently left out.
+    let input = input.trim();
+    let numbers: Vec<u64> = match parse_whole_numbers(input) {
+        Ok(numbers) => numbers,
+        Err(e) => {
+            // The report underlines the part of the input that is not a number.
+            println!("Error: {}", e.report(input));
+            return;
+        }
+    };
 
     // Calculate the LCM of the list of numbers.
-    // `fold` is used to apply the `lcm` function cumulatively to the items of the vector.
-    // It starts with an initial value of 1 (the identity for LCM) and applies the `lcm` function
-    // to the current accumulator and each number in the vector.
-    let result = numbers.iter().fold(1, |acc, &num| lcm(acc, num));
+    // `exact_lcm_of` starts with 1 (the identity for LCM) and applies the `lcm` function
+    // cumulatively to each number. The LCM grows quickly, so once it no longer fits in a
+    // 128-bit integer it carries on as an arbitrary-precision integer instead of overflowing.
+    let result = match exact_lcm_of(&numbers) {
+        Ok(result) => result,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
 
     // Print the final result to the console.
     println!("The least common multiple is: {}", result);
@@ -1,78 +1,32 @@
 use iced::{
-    widget::{button, column, row, text, text_input},
-    Alignment, Element, Sandbox, Settings,
+    alignment, mouse,
+    widget::canvas::{self, Frame, Geometry, Path, Stroke},
+    widget::{button, canvas as draw, column, pick_list, row, scrollable, text, text_input, Column},
+    Alignment, Color, Element, Font, Length, Point, Rectangle, Renderer, Sandbox, Settings, Theme,
+};
+use mathrust::bodmas::bodmas_working;
+use mathrust::bounds::{Accuracy, Approximation, BoundsCalculation, ErrorInterval, Quantity};
+use mathrust::decimal::{Decimal, RoundingMode};
+use mathrust::error::MathError;
+use mathrust::expressions::{estimate, format_approximate};
+use mathrust::figures::{DigitRole, FigureCount};
+use mathrust::fractions::{
+    common_denominator, order_fractions, ordering_statement, parse_equivalent,
+    parse_fraction_list, solve_equivalent, Fraction, FractionCalculation, Operation, SortOrder,
+};
+use mathrust::number::{exact_lcm_of, get_multiples, hcf_of, parse_number, parse_numbers, parse_whole_numbers};
+use mathrust::primes::{
+    division_ladder, division_steps, format_factors, format_index_form, is_prime, parse_prime_range,
+    prime_factorization, DivisionStep, PrimeRange,
+};
+use mathrust::rounding::{
+    estimate_square_root_to_places, format_significant_figures, round_to_decimal_places, round_to_place,
+    round_to_significant_figures, surrounding_squares,
+};
+use mathrust::session::{Entry, Session};
+use mathrust::standard_form::{
+    parse_standard_form_calculation, StandardFormCalculation, StandardFormConversion, DIVISION_FIGURES,
 };
-use std::collections::BTreeMap;
-
-// --- Helper Functions ---
-
-/// Rounds a floating-point number to a specified number of decimal places.
-fn round_to_decimal_places(n: f64, decimal_places: u32) -> f64 {
-    let multiplier = 10.0_f64.powi(decimal_places as i32);
-    (n * multiplier).round() / multiplier
-}
-
-/// Calculates the greatest common divisor (GCD) of two numbers using the Euclidean algorithm.
-fn hcf(a: u32, b: u32) -> u32 {
-    let mut temp_a = a;
-    let mut temp_b = b;
-    while temp_b != 0 {
-        let t = temp_b;
-        temp_b = temp_a % temp_b;
-        temp_a = t;
-    }
-    temp_a
-}
-
-/// Calculates the least common multiple (LCM) of two numbers.
-fn lcm(a: u32, b: u32) -> u32 {
-    if a == 0 || b == 0 {
-        0
-    } else {
-        (a / hcf(a, b)) * b
-    }
-}
-
-/// Calculates the first `count` multiples of a given `number`.
-fn get_multiples(number: u32, count: usize) -> Vec<u32> {
-    (1..=count).map(|i| number * (i as u32)).collect()
-}
-
-/// Checks if a given number is prime using an optimized method.
-fn is_prime(n: u32) -> bool {
-    if n <= 1 {
-        return false;
-    }
-    let limit = (n as f64).sqrt() as u32;
-    for i in 2..=limit {
-        if n % i == 0 {
-            return false;
-        }
-    }
-    true
-}
-
-/// Calculates the prime factorization of a given number `n`.
-fn prime_factorization(mut n: u32) -> BTreeMap<u32, u32> {
-    let mut factors = BTreeMap::new();
-    let mut d = 2;
-
-    while n > 1 {
-        while n % d == 0 {
-            *factors.entry(d).or_insert(0) += 1;
-            n /= d;
-        }
-        d += 1;
-        if d * d > n {
-            if n > 1 {
-                *factors.entry(n).or_insert(0) += 1;
-            }
-            break;
-        }
-    }
-    factors
-}
-
 
 // --- Calculator Definitions ---
 
@@ -90,11 +44,15 @@ pub enum Calculator {
     ProdPrimeFactor,
     Rounding,
     SignificantFigures,
+    CountingFigures,
     UpperLowerBounds,
+    CalculationBounds,
     EquivalentFraction,
     SimplifyingFractions,
     MixedNumbers,
     OrderingFractions,
+    FractionArithmetic,
+    StandardForm,
 }
 
 impl Calculator {
@@ -111,11 +69,15 @@ impl Calculator {
         Calculator::ProdPrimeFactor,
         Calculator::Rounding,
         Calculator::SignificantFigures,
+        Calculator::CountingFigures,
         Calculator::UpperLowerBounds,
+        Calculator::CalculationBounds,
         Calculator::EquivalentFraction,
         Calculator::SimplifyingFractions,
         Calculator::MixedNumbers,
         Calculator::OrderingFractions,
+        Calculator::FractionArithmetic,
+        Calculator::StandardForm,
     ];
 
     /// Returns the display name of the calculator.
@@ -132,12 +94,218 @@ impl Calculator {
             Calculator::ProdPrimeFactor => "Product of Prime Factors",
             Calculator::Rounding => "Rounding",
             Calculator::SignificantFigures => "Significant Figures",
+            Calculator::CountingFigures => "Counting Significant Figures",
             Calculator::UpperLowerBounds => "Upper and Lower Bounds",
+            Calculator::CalculationBounds => "Bounds of Calculations",
             Calculator::EquivalentFraction => "Equivalent Fractions",
             Calculator::SimplifyingFractions => "Simplifying Fractions",
             Calculator::MixedNumbers => "Mixed Numbers",
             Calculator::OrderingFractions => "Ordering Fractions",
+            Calculator::FractionArithmetic => "Fraction Arithmetic",
+            Calculator::StandardForm => "Standard Form",
+        }
+    }
+}
+
+/// The place values offered by the Rounding calculator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingPlace {
+    #[default]
+    WholeNumber,
+    Ten,
+    Hundred,
+    Thousand,
+}
+
+impl RoundingPlace {
+    /// A list of all rounding places.
+    const ALL: [RoundingPlace; 4] = [
+        RoundingPlace::WholeNumber,
+        RoundingPlace::Ten,
+        RoundingPlace::Hundred,
+        RoundingPlace::Thousand,
+    ];
+
+    /// Returns the place value to round to, e.g. 10 for the nearest ten.
+    fn value(&self) -> Decimal {
+        match self {
+            RoundingPlace::WholeNumber => Decimal::power_of_ten(0),
+            RoundingPlace::Ten => Decimal::power_of_ten(1),
+            RoundingPlace::Hundred => Decimal::power_of_ten(2),
+            RoundingPlace::Thousand => Decimal::power_of_ten(3),
+        }
+    }
+}
+
+impl std::fmt::Display for RoundingPlace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            RoundingPlace::WholeNumber => "Nearest whole number",
+            RoundingPlace::Ten => "Nearest ten",
+            RoundingPlace::Hundred => "Nearest hundred",
+            RoundingPlace::Thousand => "Nearest thousand",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How many primes the sieve lists on screen.
+const LISTED_PRIMES_ON_SCREEN: usize = 100;
+
+/// What the Prime Numbers calculator does with its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrimeMode {
+    /// Check whether each number in a list is prime.
+    #[default]
+    Check,
+    /// List the primes up to N, or from A to B, with a sieve.
+    Sieve,
+}
+
+impl PrimeMode {
+    /// A list of all modes.
+    const ALL: [PrimeMode; 2] = [PrimeMode::Check, PrimeMode::Sieve];
+}
+
+impl std::fmt::Display for PrimeMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            PrimeMode::Check => "Check numbers",
+            PrimeMode::Sieve => "List primes (sieve)",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How the BODMAS calculator gives its answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnswerForm {
+    #[default]
+    Decimal,
+    /// As a fraction or surd, such as 1/2 or 3√2, where the answer has an exact form.
+    Exact,
+}
+
+impl AnswerForm {
+    /// A list of all forms.
+    const ALL: [AnswerForm; 2] = [AnswerForm::Decimal, AnswerForm::Exact];
+}
+
+impl std::fmt::Display for AnswerForm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AnswerForm::Decimal => "Decimal answer",
+            AnswerForm::Exact => "Exact answer (fractions and surds)",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How the Product of Prime Factors calculator shows its working.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FactorDiagram {
+    #[default]
+    Tree,
+    Ladder,
+}
+
+impl FactorDiagram {
+    /// A list of all diagrams.
+    const ALL: [FactorDiagram; 2] = [FactorDiagram::Tree, FactorDiagram::Ladder];
+}
+
+impl std::fmt::Display for FactorDiagram {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            FactorDiagram::Tree => "Factor tree",
+            FactorDiagram::Ladder => "Division ladder",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A factor tree drawn on a canvas. Each number branches into a prime, which is circled, and
+/// what is left after dividing by it, which branches again until it is prime too.
+struct FactorTree {
+    steps: Vec<DivisionStep>,
+}
+
+impl FactorTree {
+    /// The vertical distance between one row of the tree and the next.
+    const ROW_HEIGHT: f32 = 60.0;
+    /// The space left around the tree.
+    const MARGIN: f32 = 30.0;
+
+    /// The steps drawn as branches. The tree stops once both branches are prime, so the final
+    /// division of a prime by itself is left out.
+    fn branches(&self) -> impl Iterator<Item = &DivisionStep> {
+        self.steps.iter().filter(|step| step.quotient > 1)
+    }
+
+    /// How far each branch reaches sideways, wide enough for the longest number.
+    fn branch_width(&self) -> f32 {
+        let digits = self.steps.first().map_or(1, |step| step.number.to_string().len());
+        (digits as f32 * 5.0).max(30.0) + 20.0
+    }
+
+    /// The width and height the whole tree needs.
+    fn size(&self) -> (f32, f32) {
+        let rows = self.branches().count() as f32 + 1.0;
+        let width = 2.0 * Self::MARGIN + (rows + 1.0) * self.branch_width();
+        (width, 2.0 * Self::MARGIN + (rows - 1.0) * Self::ROW_HEIGHT + 20.0)
+    }
+}
+
+impl<Message> canvas::Program<Message> for FactorTree {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let palette = theme.palette();
+        let width = self.branch_width();
+        let label = |frame: &mut Frame, n: u128, position: Point, is_prime: bool| {
+            let content = n.to_string();
+            if is_prime {
+                let radius = content.len() as f32 * 4.5 + 10.0;
+                frame.stroke(&Path::circle(position, radius), Stroke::default().with_color(palette.primary));
+            }
+            frame.fill_text(canvas::Text {
+                content,
+                position,
+                color: palette.text,
+                size: 18.0,
+                horizontal_alignment: alignment::Horizontal::Center,
+                vertical_alignment: alignment::Vertical::Center,
+                ..canvas::Text::default()
+            });
+        };
+
+        // The number being split moves one branch to the right on each row, so the primes
+        // hang off to the left of it.
+        let mut position = Point::new(Self::MARGIN + width, Self::MARGIN + 10.0);
+        if let Some(first) = self.steps.first() {
+            label(&mut frame, first.number, position, first.quotient == 1);
+        }
+        for step in self.branches() {
+            let prime = Point::new(position.x - width, position.y + Self::ROW_HEIGHT);
+            let rest = Point::new(position.x + width, position.y + Self::ROW_HEIGHT);
+            for child in [prime, rest] {
+                let start = Point::new(position.x, position.y + 12.0);
+                let end = Point::new(child.x, child.y - 12.0);
+                frame.stroke(&Path::line(start, end), Stroke::default().with_width(2.0).with_color(palette.text));
+            }
+            label(&mut frame, step.prime, prime, true);
+            label(&mut frame, step.quotient, rest, is_prime(step.quotient));
+            position = rest;
         }
+        vec![frame.into_geometry()]
     }
 }
 
@@ -147,7 +315,18 @@ impl Calculator {
 #[derive(Debug, Clone, Default)]
 struct BodmasState {
     expression: String,
+    form: AnswerForm,
+    /// The answer, variables and functions remembered between calculations.
+    session: Session,
+    /// The lines entered so far, newest first, so they can be used again.
+    history: Vec<String>,
+    /// What the result is: "Result: " for a calculation, or the name given a value.
+    label: String,
     result: Option<String>,
+    /// The answer as a fraction or surd, kept alongside the decimal so the form can be switched.
+    exact: Option<String>,
+    /// The working, one line per step, shown in a scrollable panel.
+    steps: Vec<String>,
 }
 
 /// State for the Decimal Places calculator.
@@ -155,7 +334,26 @@ struct BodmasState {
 struct DecimalPlacesState {
     number_input: String,
     places_input: String,
-    result: Option<f64>,
+    mode: RoundingMode,
+    result: Option<String>,
+}
+
+/// State for the Estimation calculator.
+#[derive(Debug, Clone, Default)]
+struct EstimationState {
+    expression: String,
+    /// How accurately to round each number; left empty for one significant figure.
+    accuracy_input: String,
+    mode: RoundingMode,
+    result: Option<String>,
+}
+
+/// State for the Estimation of Square Root calculator.
+#[derive(Debug, Clone, Default)]
+struct EstimationSquareRootState {
+    number_input: String,
+    places_input: String,
+    result: Option<String>,
 }
 
 /// State for the HCF calculator.
@@ -194,6 +392,7 @@ impl Default for MultiplesState {
 #[derive(Debug, Clone, Default)]
 struct PrimeNumbersState {
     numbers_input: String,
+    mode: PrimeMode,
     result: Option<String>,
 }
 
@@ -201,6 +400,52 @@ struct PrimeNumbersState {
 #[derive(Debug, Clone, Default)]
 struct ProdPrimeFactorState {
     number_input: String,
+    diagram: FactorDiagram,
+    /// The divisions found for the last number, from which the diagrams are drawn.
+    steps: Vec<DivisionStep>,
+    result: Option<String>,
+}
+
+/// State for the Rounding calculator.
+#[derive(Debug, Clone, Default)]
+struct RoundingState {
+    number_input: String,
+    place: RoundingPlace,
+    mode: RoundingMode,
+    result: Option<String>,
+}
+
+/// State for the Significant Figures calculator.
+#[derive(Debug, Clone, Default)]
+struct SignificantFiguresState {
+    number_input: String,
+    sf_input: String,
+    mode: RoundingMode,
+    result: Option<String>,
+}
+
+/// State for the Counting Significant Figures calculator. The count is kept, rather than text,
+/// so that each digit can be coloured by its role.
+#[derive(Debug, Clone, Default)]
+struct CountingFiguresState {
+    number_input: String,
+    result: Option<Result<FigureCount, String>>,
+}
+
+/// State for the Upper and Lower Bounds calculator.
+#[derive(Debug, Clone, Default)]
+struct UpperLowerBoundsState {
+    number_input: String,
+    accuracy_input: String,
+    approximation: Approximation,
+    result: Option<String>,
+}
+
+/// State for the Bounds of Calculations calculator.
+#[derive(Debug, Clone, Default)]
+struct CalculationBoundsState {
+    expression: String,
+    quantities_input: String,
     result: Option<String>,
 }
 
@@ -211,7 +456,7 @@ struct EquivalentFractionState {
     b_input: String,
     c_input: String,
     d_input: String,
-    result: Option<f64>,
+    result: Option<String>,
 }
 
 /// State for the Simplifying Fractions calculator.
@@ -241,6 +486,34 @@ struct MixedNumbersState {
 #[derive(Debug, Clone, Default)]
 struct OrderingFractionsState {
     fractions_input: String,
+    order: SortOrder,
+    result: Option<String>,
+}
+
+/// State for the Fraction Arithmetic calculator.
+#[derive(Debug, Clone)]
+struct FractionArithmeticState {
+    left_input: String,
+    operation: Operation,
+    right_input: String,
+    result: Option<String>,
+}
+
+impl Default for FractionArithmeticState {
+    fn default() -> Self {
+        Self {
+            left_input: String::new(),
+            operation: Operation::Add,
+            right_input: String::new(),
+            result: None,
+        }
+    }
+}
+
+/// State for the Standard Form calculator.
+#[derive(Debug, Clone, Default)]
+struct StandardFormState {
+    expression: String,
     result: Option<String>,
 }
 
@@ -249,21 +522,32 @@ struct MathGui {
     selected_calculator: Option<Calculator>,
     bodmas_state: BodmasState,
     decimal_places_state: DecimalPlacesState,
+    estimation_state: EstimationState,
+    estimation_square_root_state: EstimationSquareRootState,
     hcf_state: HcfState,
     lcm_state: LcmState,
     multiples_state: MultiplesState,
     prime_numbers_state: PrimeNumbersState,
     prod_prime_factor_state: ProdPrimeFactorState,
+    rounding_state: RoundingState,
+    significant_figures_state: SignificantFiguresState,
+    counting_figures_state: CountingFiguresState,
+    upper_lower_bounds_state: UpperLowerBoundsState,
+    calculation_bounds_state: CalculationBoundsState,
     equivalent_fraction_state: EquivalentFractionState,
     simplifying_fractions_state: SimplifyingFractionsState,
     mixed_numbers_state: MixedNumbersState,
     ordering_fractions_state: OrderingFractionsState,
+    fraction_arithmetic_state: FractionArithmeticState,
+    standard_form_state: StandardFormState,
 }
 
 /// Messages for the BODMAS calculator.
 #[derive(Debug, Clone)]
 pub enum BodmasMessage {
     ExpressionChanged(String),
+    FormSelected(AnswerForm),
+    Recall(String),
     Calculate,
     Reset,
 }
@@ -271,6 +555,26 @@ pub enum BodmasMessage {
 /// Messages for the Decimal Places calculator.
 #[derive(Debug, Clone)]
 pub enum DecimalPlacesMessage {
+    NumberInputChanged(String),
+    PlacesInputChanged(String),
+    ModeSelected(RoundingMode),
+    Calculate,
+    Reset,
+}
+
+/// Messages for the Estimation calculator.
+#[derive(Debug, Clone)]
+pub enum EstimationMessage {
+    ExpressionChanged(String),
+    AccuracyInputChanged(String),
+    ModeSelected(RoundingMode),
+    Calculate,
+    Reset,
+}
+
+/// Messages for the Estimation of Square Root calculator.
+#[derive(Debug, Clone)]
+pub enum EstimationSquareRootMessage {
     NumberInputChanged(String),
     PlacesInputChanged(String),
     Calculate,
@@ -306,6 +610,7 @@ pub enum MultiplesMessage {
 #[derive(Debug, Clone)]
 pub enum PrimeNumbersMessage {
     NumbersInputChanged(String),
+    ModeSelected(PrimeMode),
     Calculate,
     Reset,
 }
@@ -314,6 +619,54 @@ pub enum PrimeNumbersMessage {
 #[derive(Debug, Clone)]
 pub enum ProdPrimeFactorMessage {
     NumberInputChanged(String),
+    DiagramSelected(FactorDiagram),
+    Calculate,
+    Reset,
+}
+
+/// Messages for the Rounding calculator.
+#[derive(Debug, Clone)]
+pub enum RoundingMessage {
+    NumberInputChanged(String),
+    PlaceSelected(RoundingPlace),
+    ModeSelected(RoundingMode),
+    Calculate,
+    Reset,
+}
+
+/// Messages for the Significant Figures calculator.
+#[derive(Debug, Clone)]
+pub enum SignificantFiguresMessage {
+    NumberInputChanged(String),
+    SfInputChanged(String),
+    ModeSelected(RoundingMode),
+    Calculate,
+    Reset,
+}
+
+/// Messages for the Counting Significant Figures calculator.
+#[derive(Debug, Clone)]
+pub enum CountingFiguresMessage {
+    NumberInputChanged(String),
+    Calculate,
+    Reset,
+}
+
+/// Messages for the Upper and Lower Bounds calculator.
+#[derive(Debug, Clone)]
+pub enum UpperLowerBoundsMessage {
+    NumberInputChanged(String),
+    AccuracyInputChanged(String),
+    ApproximationSelected(Approximation),
+    Calculate,
+    Reset,
+}
+
+/// Messages for the Bounds of Calculations calculator.
+#[derive(Debug, Clone)]
+pub enum CalculationBoundsMessage {
+    ExpressionChanged(String),
+    QuantitiesChanged(String),
     Calculate,
     Reset,
 }
@@ -355,6 +708,25 @@ pub enum MixedNumbersMessage {
 #[derive(Debug, Clone)]
 pub enum OrderingFractionsMessage {
     FractionsInputChanged(String),
+    OrderSelected(SortOrder),
+    Calculate,
+    Reset,
+}
+
+/// Messages for the Fraction Arithmetic calculator.
+#[derive(Debug, Clone)]
+pub enum FractionArithmeticMessage {
+    LeftChanged(String),
+    OperationSelected(Operation),
+    RightChanged(String),
+    Calculate,
+    Reset,
+}
+
+/// Messages for the Standard Form calculator.
+#[derive(Debug, Clone)]
+pub enum StandardFormMessage {
+    ExpressionChanged(String),
     Calculate,
     Reset,
 }
@@ -366,19 +738,44 @@ enum Message {
     BackToMenu,
     Bodmas(BodmasMessage),
     DecimalPlaces(DecimalPlacesMessage),
+    Estimation(EstimationMessage),
+    EstimationSquareRoot(EstimationSquareRootMessage),
     Hcf(HcfMessage),
     Lcm(LcmMessage),
     Multiples(MultiplesMessage),
     PrimeNumbers(PrimeNumbersMessage),
     ProdPrimeFactor(ProdPrimeFactorMessage),
+    Rounding(RoundingMessage),
+    SignificantFigures(SignificantFiguresMessage),
+    CountingFigures(CountingFiguresMessage),
+    UpperLowerBounds(UpperLowerBoundsMessage),
+    CalculationBounds(CalculationBoundsMessage),
     EquivalentFraction(EquivalentFractionMessage),
     SimplifyingFractions(SimplifyingFractionsMessage),
     MixedNumbers(MixedNumbersMessage),
     OrderingFractions(OrderingFractionsMessage),
+    FractionArithmetic(FractionArithmeticMessage),
+    StandardForm(StandardFormMessage),
 }
 
 // --- Main Application Logic ---
 
+/// Formats an error the same way as the command-line calculators, repeating the input with the
+/// part that caused the error underlined.
+fn error_text(error: &MathError, input: &str) -> String {
+    format!("Error: {}", error.report(input))
+}
+
+/// The colour used to show the role of a digit when counting significant figures.
+fn role_color(role: DigitRole) -> Color {
+    match role {
+        DigitRole::Significant => Color::from_rgb(0.0, 0.55, 0.0),
+        DigitRole::LeadingZero => Color::from_rgb(0.6, 0.6, 0.6),
+        DigitRole::TrailingZero => Color::from_rgb(0.0, 0.35, 0.85),
+        DigitRole::AmbiguousZero => Color::from_rgb(0.9, 0.5, 0.0),
+    }
+}
+
 pub fn main() -> iced::Result {
     MathGui::run(Settings::default())
 }
@@ -391,15 +788,24 @@ impl Sandbox for MathGui {
             selected_calculator: None,
             bodmas_state: BodmasState::default(),
             decimal_places_state: DecimalPlacesState::default(),
+            estimation_state: EstimationState::default(),
+            estimation_square_root_state: EstimationSquareRootState::default(),
             hcf_state: HcfState::default(),
             lcm_state: LcmState::default(),
             multiples_state: MultiplesState::default(),
             prime_numbers_state: PrimeNumbersState::default(),
             prod_prime_factor_state: ProdPrimeFactorState::default(),
+            rounding_state: RoundingState::default(),
+            significant_figures_state: SignificantFiguresState::default(),
+            counting_figures_state: CountingFiguresState::default(),
+            upper_lower_bounds_state: UpperLowerBoundsState::default(),
+            calculation_bounds_state: CalculationBoundsState::default(),
             equivalent_fraction_state: EquivalentFractionState::default(),
             simplifying_fractions_state: SimplifyingFractionsState::default(),
             mixed_numbers_state: MixedNumbersState::default(),
             ordering_fractions_state: OrderingFractionsState::default(),
+            fraction_arithmetic_state: FractionArithmeticState::default(),
+            standard_form_state: StandardFormState::default(),
         }
     }
 
@@ -414,18 +820,31 @@ impl Sandbox for MathGui {
             }
             Message::BackToMenu => {
                 self.selected_calculator = None;
-                // Reset the state when going back to the menu
-                self.bodmas_state = BodmasState::default();
+                // Reset the state when going back to the menu, keeping the BODMAS session and its lines
+                self.bodmas_state = BodmasState {
+                    session: std::mem::take(&mut self.bodmas_state.session),
+                    history: std::mem::take(&mut self.bodmas_state.history),
+                    ..BodmasState::default()
+                };
                 self.decimal_places_state = DecimalPlacesState::default();
+                self.estimation_state = EstimationState::default();
+                self.estimation_square_root_state = EstimationSquareRootState::default();
                 self.hcf_state = HcfState::default();
                 self.lcm_state = LcmState::default();
                 self.multiples_state = MultiplesState::default();
                 self.prime_numbers_state = PrimeNumbersState::default();
                 self.prod_prime_factor_state = ProdPrimeFactorState::default();
+                self.rounding_state = RoundingState::default();
+                self.significant_figures_state = SignificantFiguresState::default();
+                self.counting_figures_state = CountingFiguresState::default();
+                self.upper_lower_bounds_state = UpperLowerBoundsState::default();
+                self.calculation_bounds_state = CalculationBoundsState::default();
                 self.equivalent_fraction_state = EquivalentFractionState::default();
                 self.simplifying_fractions_state = SimplifyingFractionsState::default();
                 self.mixed_numbers_state = MixedNumbersState::default();
                 self.ordering_fractions_state = OrderingFractionsState::default();
+                self.fraction_arithmetic_state = FractionArithmeticState::default();
+                self.standard_form_state = StandardFormState::default();
             }
             Message::Bodmas(msg) => {
                 let state = &mut self.bodmas_state;
@@ -433,20 +852,54 @@ impl Sandbox for MathGui {
                     BodmasMessage::ExpressionChanged(value) => {
                         state.expression = value;
                     }
+                    BodmasMessage::FormSelected(form) => {
+                        state.form = form;
+                    }
+                    BodmasMessage::Recall(line) => {
+                        state.expression = line;
+                    }
                     BodmasMessage::Calculate => {
-                        match meval::eval_str(&state.expression) {
-                            Ok(result) => {
-                                // Check if the result is an integer.
-                                if result.fract() == 0.0 {
-                                    state.result = Some((result as i64).to_string());
-                                } else {
-                                    state.result = Some(result.to_string());
+                        // Work through the expression step by step in the session, then give its value both ways.
+                        let input = state.expression.trim().to_string();
+                        let entry = state.session.enter(&input).and_then(|entry| {
+                            let (steps, result) = match &entry {
+                                Entry::Calculation { expr, .. } | Entry::Assignment { expr, .. } => {
+                                    let working = bodmas_working(expr)?;
+                                    let steps = std::iter::once(expr.to_string())
+                                        .chain(working.steps.iter().map(ToString::to_string))
+                                        .collect();
+                                    (steps, Some(working.written_answer()))
                                 }
+                                Entry::Definition { .. } => (Vec::new(), None),
+                            };
+                            Ok((entry, steps, result))
+                        });
+                        state.exact = None;
+                        state.label = match &entry {
+                            Ok((Entry::Calculation { .. }, ..)) => "Result: ".to_string(),
+                            Ok((Entry::Assignment { name, .. }, ..)) => format!("{} = ", name),
+                            _ => String::new(),
+                        };
+                        match entry {
+                            Ok((Entry::Calculation { answer, .. } | Entry::Assignment { answer, .. }, steps, result)) => {
+                                // The answer is the one the working ends with, as it would be used in place of ans.
+                                state.result = result;
+                                state.exact = answer.exact.map(|exact| exact.to_string());
+                                state.steps = steps;
+                            }
+                            Ok((Entry::Definition { name }, ..)) => {
+                                state.result = Some(format!("Defined {}", name));
+                                state.steps.clear();
                             }
                             Err(e) => {
-                                state.result = Some(format!("Error: {}", e));
+                                state.result = Some(error_text(&e, &input));
+                                state.steps.clear();
                             }
                         }
+                        if !input.is_empty() {
+                            state.history.retain(|line| *line != input);
+                            state.history.insert(0, input);
+                        }
                     }
                     BodmasMessage::Reset => {
                         *state = BodmasState::default();
@@ -462,21 +915,121 @@ impl Sandbox for MathGui {
                     DecimalPlacesMessage::PlacesInputChanged(value) => {
                         state.places_input = value;
                     }
+                    DecimalPlacesMessage::ModeSelected(mode) => {
+                        state.mode = mode;
+                    }
                     DecimalPlacesMessage::Calculate => {
-                        let number: Result<f64, _> = state.number_input.parse();
-                        let places: Result<u32, _> = state.places_input.parse();
+                        let number = parse_number::<Decimal>(&state.number_input);
+                        let places = parse_number::<u32>(&state.places_input);
 
-                        if let (Ok(num), Ok(p)) = (number, places) {
-                            state.result = Some(round_to_decimal_places(num, p));
-                        } else {
-                            state.result = None;
-                        }
+                        state.result = match (number, places) {
+                            (Ok(num), Ok(p)) => Some(match round_to_decimal_places(&num, p, state.mode) {
+                                Ok(rounded) => format!("{} ({})", rounded, state.mode),
+                                Err(e) => error_text(&e, &state.places_input),
+                            }),
+                            (Err(e), _) => Some(error_text(&e, &state.number_input)),
+                            (_, Err(e)) => Some(error_text(&e, &state.places_input)),
+                        };
                     }
                     DecimalPlacesMessage::Reset => {
                         *state = DecimalPlacesState::default();
                     }
                 }
             }
+            Message::Estimation(msg) => {
+                let state = &mut self.estimation_state;
+                match msg {
+                    EstimationMessage::ExpressionChanged(value) => {
+                        state.expression = value;
+                    }
+                    EstimationMessage::ModeSelected(mode) => {
+                        state.mode = mode;
+                    }
+                    EstimationMessage::AccuracyInputChanged(value) => {
+                        state.accuracy_input = value;
+                    }
+                    EstimationMessage::Calculate => {
+                        // Round every number, then work out the rounded expression next to the original one.
+                        let accuracy = match state.accuracy_input.trim() {
+                            "" => Ok(Accuracy::SignificantFigures(1)),
+                            input => input.parse::<Accuracy>(),
+                        };
+                        let estimate = accuracy
+                            .clone()
+                            .and_then(|accuracy| estimate(&state.expression, &accuracy, state.mode));
+                        state.result = Some(match (accuracy, estimate) {
+                            (Ok(accuracy), Ok(estimate)) => {
+                                let mut result_str = format!("Rounding each number {} ({}):\n", accuracy, state.mode);
+                                for (number_str, rounded_number) in &estimate.roundings {
+                                    result_str.push_str(&format!("  {} ≈ {}\n", number_str, rounded_number));
+                                }
+                                result_str
+                                    .push_str(&format!("Estimated expression: {}\n", estimate.rounded_expression));
+                                for warning in &estimate.warnings {
+                                    result_str.push_str(&format!("Warning: {}\n", warning));
+                                }
+                                let estimated =
+                                    estimate.estimate.map_or_else(|| "none".to_string(), format_approximate);
+                                result_str.push_str(&format!(
+                                    "Estimate ≈ {}    Exact answer = {}",
+                                    estimated,
+                                    format_approximate(estimate.exact)
+                                ));
+                                if let Some(error) = estimate.percentage_error() {
+                                    result_str.push_str(&format!("    Percentage error: {:.1}%", error));
+                                }
+                                result_str
+                            }
+                            (Err(e), _) => error_text(&e, state.accuracy_input.trim()),
+                            (_, Err(e)) => error_text(&e, state.expression.trim()),
+                        });
+                    }
+                    EstimationMessage::Reset => {
+                        *state = EstimationState::default();
+                    }
+                }
+            }
+            Message::EstimationSquareRoot(msg) => {
+                let state = &mut self.estimation_square_root_state;
+                match msg {
+                    EstimationSquareRootMessage::NumberInputChanged(value) => {
+                        state.number_input = value;
+                    }
+                    EstimationSquareRootMessage::PlacesInputChanged(value) => {
+                        state.places_input = value;
+                    }
+                    EstimationSquareRootMessage::Calculate => {
+                        let number = parse_number::<f64>(&state.number_input);
+                        // An empty box means rounding to the nearest whole number.
+                        let places = if state.places_input.trim().is_empty() {
+                            Ok(0)
+                        } else {
+                            parse_number::<u32>(&state.places_input)
+                        };
+
+                        state.result = match (number, places) {
+                            (Ok(number), Ok(places)) if number >= 0.0 => Some(match surrounding_squares(number) {
+                                Ok((integer_part, lower_square, upper_square)) => {
+                                    match estimate_square_root_to_places(number, places) {
+                                        Ok(rounded_estimate) => format!(
+                                            "{} is between {} ({}²) and {} ({}²).\nA sensible estimate for the square root of {} is ≈ {}",
+                                            number, lower_square, integer_part, upper_square, integer_part + 1, number, rounded_estimate
+                                        ),
+                                        Err(e) => error_text(&e, &state.places_input),
+                                    }
+                                }
+                                Err(e) => error_text(&e, &state.number_input),
+                            }),
+                            (Ok(_), Ok(_)) => Some(error_text(&MathError::NoRealSquareRoot, &state.number_input)),
+                            (Err(e), _) => Some(error_text(&e, &state.number_input)),
+                            (_, Err(e)) => Some(error_text(&e, &state.places_input)),
+                        };
+                    }
+                    EstimationSquareRootMessage::Reset => {
+                        *state = EstimationSquareRootState::default();
+                    }
+                }
+            }
             Message::Hcf(msg) => {
                 let state = &mut self.hcf_state;
                 match msg {
@@ -484,20 +1037,10 @@ impl Sandbox for MathGui {
                         state.numbers_input = value;
                     }
                     HcfMessage::Calculate => {
-                        let numbers: Vec<u32> = state
-                            .numbers_input
-                            .split_whitespace()
-                            .filter_map(|s| s.parse().ok())
-                            .collect();
-
-                        if numbers.is_empty() {
-                            state.result = Some("Please enter valid numbers.".to_string());
-                        } else {
-                            let result = numbers
-                                .iter()
-                                .fold(numbers[0], |acc, &num| hcf(acc, num));
-                            state.result = Some(result.to_string());
-                        }
+                        state.result = match parse_whole_numbers(&state.numbers_input) {
+                            Ok(numbers) => hcf_of(&numbers).map(|result| result.to_string()),
+                            Err(e) => Some(error_text(&e, &state.numbers_input)),
+                        };
                     }
                     HcfMessage::Reset => {
                         *state = HcfState::default();
@@ -511,19 +1054,11 @@ impl Sandbox for MathGui {
                         state.numbers_input = value;
                     }
                     LcmMessage::Calculate => {
-                        let numbers: Vec<u32> = state
-                            .numbers_input
-                            .split_whitespace()
-                            .filter_map(|s| s.parse().ok())
-                            .collect();
-
-                        if numbers.is_empty() {
-                            state.result = Some("Please enter valid numbers.".to_string());
-                        }
-                        else {
-                            let result = numbers.iter().fold(1, |acc, &num| lcm(acc, num));
-                            state.result = Some(result.to_string());
-                        }
+                        // The LCM grows quickly, so it is worked out exactly however large it gets.
+                        state.result = match parse_whole_numbers(&state.numbers_input) {
+                            Ok(numbers) => exact_lcm_of(&numbers).ok().map(|result| result.to_string()),
+                            Err(e) => Some(error_text(&e, &state.numbers_input)),
+                        };
                     }
                     LcmMessage::Reset => {
                         *state = LcmState::default();
@@ -540,32 +1075,34 @@ impl Sandbox for MathGui {
                         state.count_input = value;
                     }
                     MultiplesMessage::Calculate => {
-                        let numbers: Vec<u32> = state
-                            .numbers_input
-                            .split(',')
-                            .filter_map(|s| s.trim().parse().ok())
-                            .collect();
-                        let count: Result<usize, _> = state.count_input.parse();
-
-                        if numbers.is_empty() || count.is_err() {
-                            state.result =
-                                Some("Please enter valid numbers and a valid count.".to_string());
-                        } else {
-                            let count = count.unwrap();
-                            let mut result_str = String::new();
-                            for num in numbers {
-                                let multiples = get_multiples(num, count);
-                                let multiples_str: Vec<String> =
-                                    multiples.iter().map(|m| m.to_string()).collect();
-                                result_str.push_str(&format!(
-                                    "Multiples of {}: {}
+                        let numbers = parse_numbers::<u32>(&state.numbers_input);
+                        let count = parse_number::<usize>(&state.count_input);
+
+                        state.result = match (numbers, count) {
+                            (Ok(numbers), Ok(count)) => {
+                                let mut result_str = String::new();
+                                for num in numbers {
+                                    let multiples = match get_multiples(num, count) {
+                                        Ok(multiples) => multiples,
+                                        Err(e) => {
+                                            result_str = error_text(&e, &state.count_input);
+                                            break;
+                                        }
+                                    };
+                                    let multiples_str: Vec<String> =
+                                        multiples.iter().map(|m| m.to_string()).collect();
+                                    result_str.push_str(&format!(
+                                        "Multiples of {}: {}
 ",
-                                    num,
-                                    multiples_str.join(", ")
-                                ));
+                                        num,
+                                        multiples_str.join(", ")
+                                    ));
+                                }
+                                Some(result_str)
                             }
-                            state.result = Some(result_str);
-                        }
+                            (Err(e), _) => Some(error_text(&e, &state.numbers_input)),
+                            (_, Err(e)) => Some(error_text(&e, &state.count_input)),
+                        };
                     }
                     MultiplesMessage::Reset => {
                         *state = MultiplesState::default();
@@ -578,28 +1115,48 @@ impl Sandbox for MathGui {
                     PrimeNumbersMessage::NumbersInputChanged(value) => {
                         state.numbers_input = value;
                     }
+                    PrimeNumbersMessage::ModeSelected(mode) => {
+                        state.mode = mode;
+                    }
+                    PrimeNumbersMessage::Calculate if state.mode == PrimeMode::Sieve => {
+                        let range = parse_prime_range(&state.numbers_input)
+                            .and_then(|(start, end)| PrimeRange::sieve(start, end));
+                        state.result = Some(match range {
+                            Ok(range) => {
+                                // Only the first primes fit on the screen; the statistics cover them all.
+                                let primes: Vec<String> =
+                                    range.primes.iter().take(LISTED_PRIMES_ON_SCREEN).map(u64::to_string).collect();
+                                let mut result_str = primes.join(", ");
+                                if range.count > primes.len() as u64 {
+                                    result_str.push_str(&format!(" … (the first {} primes)", primes.len()));
+                                }
+                                for line in range.statistics() {
+                                    result_str.push_str(&format!("\n{}", line));
+                                }
+                                result_str
+                            }
+                            Err(e) => error_text(&e, &state.numbers_input),
+                        });
+                    }
                     PrimeNumbersMessage::Calculate => {
-                        let numbers: Vec<u32> = state
-                            .numbers_input
-                            .split_whitespace()
-                            .filter_map(|s| s.parse().ok())
-                            .collect();
-
-                        if numbers.is_empty() {
-                            state.result = Some("Please enter valid numbers.".to_string());
-                        } else {
-                            let mut result_str = String::new();
-                            for num in numbers {
-                                if is_prime(num) {
-                                    result_str.push_str(&format!("{} is a prime number.\n", num));
-                                } else {
-                                    result_str.push_str(&format!(
-                                        "{} is not a prime number.\n",
-                                        num
-                                    ));
+                        match parse_numbers::<u128>(&state.numbers_input) {
+                            Err(e) => {
+                                state.result = Some(error_text(&e, &state.numbers_input));
+                            }
+                            Ok(numbers) => {
+                                let mut result_str = String::new();
+                                for num in numbers {
+                                    if is_prime(num) {
+                                        result_str.push_str(&format!("{} is a prime number.\n", num));
+                                    } else {
+                                        result_str.push_str(&format!(
+                                            "{} is not a prime number.\n",
+                                            num
+                                        ));
+                                    }
                                 }
+                                state.result = Some(result_str);
                             }
-                            state.result = Some(result_str);
                         }
                     }
                     PrimeNumbersMessage::Reset => {
@@ -613,36 +1170,185 @@ impl Sandbox for MathGui {
                     ProdPrimeFactorMessage::NumberInputChanged(value) => {
                         state.number_input = value;
                     }
+                    ProdPrimeFactorMessage::DiagramSelected(diagram) => {
+                        state.diagram = diagram;
+                    }
                     ProdPrimeFactorMessage::Calculate => {
-                        let number: Result<u32, _> = state.number_input.parse();
-                        if let Ok(num) = number {
-                            if num <= 1 {
-                                state.result =
-                                    Some("The number must be greater than 1.".to_string());
-                            } else {
+                        state.steps = Vec::new();
+                        state.result = match parse_number::<u128>(&state.number_input) {
+                            Ok(num) if num <= 1 => {
+                                Some(error_text(&MathError::TooSmall { minimum: 2 }, &state.number_input))
+                            }
+                            Ok(num) => {
                                 let factors = prime_factorization(num);
-                                let result_str = factors
-                                    .iter()
-                                    .map(|(base, exp)| {
-                                        if *exp == 1 {
-                                            format!("{}", base)
-                                        } else {
-                                            format!("{}^{}", base, exp)
-                                        }
-                                    })
-                                    .collect::<Vec<String>>()
-                                    .join(" * ");
-                                state.result = Some(result_str);
+                                state.steps = division_steps(num);
+                                Some(format!("{} = {} ({})", num, format_index_form(&factors), format_factors(&factors)))
                             }
-                        } else {
-                            state.result = Some("Please enter a valid number.".to_string());
-                        }
+                            Err(e) => Some(error_text(&e, &state.number_input)),
+                        };
                     }
                     ProdPrimeFactorMessage::Reset => {
                         *state = ProdPrimeFactorState::default();
                     }
                 }
             }
+            Message::Rounding(msg) => {
+                let state = &mut self.rounding_state;
+                match msg {
+                    RoundingMessage::NumberInputChanged(value) => {
+                        state.number_input = value;
+                    }
+                    RoundingMessage::PlaceSelected(place) => {
+                        state.place = place;
+                    }
+                    RoundingMessage::ModeSelected(mode) => {
+                        state.mode = mode;
+                    }
+                    RoundingMessage::Calculate => {
+                        state.result = match parse_number::<Decimal>(&state.number_input) {
+                            Ok(number) => {
+                                let rounded = round_to_place(&number, &state.place.value(), state.mode);
+                                Some(format!("{} ≈ {} ({})", number, rounded, state.mode))
+                            }
+                            Err(e) => Some(error_text(&e, &state.number_input)),
+                        };
+                    }
+                    RoundingMessage::Reset => {
+                        *state = RoundingState::default();
+                    }
+                }
+            }
+            Message::SignificantFigures(msg) => {
+                let state = &mut self.significant_figures_state;
+                match msg {
+                    SignificantFiguresMessage::NumberInputChanged(value) => {
+                        state.number_input = value;
+                    }
+                    SignificantFiguresMessage::SfInputChanged(value) => {
+                        state.sf_input = value;
+                    }
+                    SignificantFiguresMessage::ModeSelected(mode) => {
+                        state.mode = mode;
+                    }
+                    SignificantFiguresMessage::Calculate => {
+                        let number = parse_number::<Decimal>(&state.number_input);
+                        let significant_figures = parse_number::<u32>(&state.sf_input);
+
+                        state.result = match (number, significant_figures) {
+                            (Ok(number), Ok(sf)) => Some(match round_to_significant_figures(&number, sf, state.mode) {
+                                Ok(rounded) => format!(
+                                    "The number rounded to {} significant figures is: {} ({})",
+                                    sf,
+                                    format_significant_figures(&rounded),
+                                    state.mode
+                                ),
+                                Err(e) => error_text(&e, &state.sf_input),
+                            }),
+                            (Err(e), _) => Some(error_text(&e, &state.number_input)),
+                            (_, Err(e)) => Some(error_text(&e, &state.sf_input)),
+                        };
+                    }
+                    SignificantFiguresMessage::Reset => {
+                        *state = SignificantFiguresState::default();
+                    }
+                }
+            }
+            Message::CountingFigures(msg) => {
+                let state = &mut self.counting_figures_state;
+                match msg {
+                    CountingFiguresMessage::NumberInputChanged(value) => {
+                        state.number_input = value;
+                    }
+                    CountingFiguresMessage::Calculate => {
+                        state.result = Some(
+                            FigureCount::count(&state.number_input).map_err(|e| error_text(&e, &state.number_input)),
+                        );
+                    }
+                    CountingFiguresMessage::Reset => {
+                        *state = CountingFiguresState::default();
+                    }
+                }
+            }
+            Message::UpperLowerBounds(msg) => {
+                let state = &mut self.upper_lower_bounds_state;
+                match msg {
+                    UpperLowerBoundsMessage::NumberInputChanged(value) => {
+                        state.number_input = value;
+                    }
+                    UpperLowerBoundsMessage::AccuracyInputChanged(value) => {
+                        state.accuracy_input = value;
+                    }
+                    UpperLowerBoundsMessage::ApproximationSelected(approximation) => {
+                        state.approximation = approximation;
+                    }
+                    UpperLowerBoundsMessage::Calculate => {
+                        let number = parse_number::<Decimal>(&state.number_input);
+                        let degree_of_accuracy = state.accuracy_input.parse::<Accuracy>();
+
+                        state.result = match (number, degree_of_accuracy) {
+                            (Ok(number), Ok(accuracy)) => Some(
+                                match ErrorInterval::new(&number, &accuracy, state.approximation) {
+                                    Ok(interval) => format!(
+                                        "Error interval: {}\nThe lower bound is: {}\nThe upper bound is: {}",
+                                        interval, interval.lower_bound, interval.upper_bound
+                                    ),
+                                    Err(e) => error_text(&e, &state.accuracy_input),
+                                },
+                            ),
+                            (Err(e), _) => Some(error_text(&e, &state.number_input)),
+                            (_, Err(e)) => Some(error_text(&e, &state.accuracy_input)),
+                        };
+                    }
+                    UpperLowerBoundsMessage::Reset => {
+                        *state = UpperLowerBoundsState::default();
+                    }
+                }
+            }
+            Message::CalculationBounds(msg) => {
+                let state = &mut self.calculation_bounds_state;
+                match msg {
+                    CalculationBoundsMessage::ExpressionChanged(value) => {
+                        state.expression = value;
+                    }
+                    CalculationBoundsMessage::QuantitiesChanged(value) => {
+                        state.quantities_input = value;
+                    }
+                    CalculationBoundsMessage::Calculate => {
+                        // The values are separated by semicolons, because an accuracy such as
+                        // "1 d.p., truncated" may contain a comma.
+                        let quantities = state
+                            .quantities_input
+                            .split(';')
+                            .filter(|part| !part.trim().is_empty())
+                            .map(|part| part.parse::<Quantity>().map_err(|e| error_text(&e, part)))
+                            .collect::<Result<Vec<Quantity>, String>>();
+                        let worked = quantities.and_then(|quantities| {
+                            BoundsCalculation::calculate(&state.expression, quantities)
+                                .map_err(|e| error_text(&e, &state.expression))
+                        });
+                        state.result = Some(match worked {
+                            Ok(calculation) => {
+                                let mut result_str = String::new();
+                                for (i, step) in calculation.working().iter().enumerate() {
+                                    result_str.push_str(&format!("{}. {}\n", i + 1, step));
+                                }
+                                result_str.push_str(&format!(
+                                    "The lower bound is: {}\nThe upper bound is: {}",
+                                    calculation.interval.lower, calculation.interval.upper
+                                ));
+                                if !calculation.exact {
+                                    result_str.push_str(&format!(" (to {} significant figures)", DIVISION_FIGURES));
+                                }
+                                result_str
+                            }
+                            Err(e) => e,
+                        });
+                    }
+                    CalculationBoundsMessage::Reset => {
+                        *state = CalculationBoundsState::default();
+                    }
+                }
+            }
             Message::EquivalentFraction(msg) => {
                 let state = &mut self.equivalent_fraction_state;
                 match msg {
@@ -659,50 +1365,22 @@ impl Sandbox for MathGui {
                         state.d_input = value;
                     }
                     EquivalentFractionMessage::Calculate => {
-                        let a = state.a_input.parse::<f64>();
-                        let b = state.b_input.parse::<f64>();
-                        let c = state.c_input.parse::<f64>();
-                        let d = state.d_input.parse::<f64>();
-
-                        if a.is_err() {
-                            let b_val = b.unwrap_or(0.0);
-                            let c_val = c.unwrap_or(0.0);
-                            let d_val = d.unwrap_or(0.0);
-                            if d_val != 0.0 {
-                                state.result = Some((b_val * c_val) / d_val);
-                            } else {
-                                state.result = None; // Division by zero
-                            }
-                        } else if b.is_err() {
-                            let a_val = a.unwrap_or(0.0);
-                            let c_val = c.unwrap_or(0.0);
-                            let d_val = d.unwrap_or(0.0);
-                            if c_val != 0.0 {
-                                state.result = Some((a_val * d_val) / c_val);
-                            } else {
-                                state.result = None; // Division by zero
-                            }
-                        } else if c.is_err() {
-                            let a_val = a.unwrap_or(0.0);
-                            let b_val = b.unwrap_or(0.0);
-                            let d_val = d.unwrap_or(0.0);
-                            if b_val != 0.0 {
-                                state.result = Some((a_val * d_val) / b_val);
-                            } else {
-                                state.result = None; // Division by zero
-                            }
-                        } else if d.is_err() {
-                            let a_val = a.unwrap_or(0.0);
-                            let b_val = b.unwrap_or(0.0);
-                            let c_val = c.unwrap_or(0.0);
-                            if a_val != 0.0 {
-                                state.result = Some((b_val * c_val) / a_val);
-                            } else {
-                                state.result = None; // Division by zero
-                            }
-                        } else {
-                            state.result = None; // No 'x' found
-                        }
+                        // Write the four boxes out as an equation, so that an error can point at
+                        // the box that caused it. A blank box, 'x' or '?' marks the unknown.
+                        let equation = format!(
+                            "{}/{} = {}/{}",
+                            state.a_input.trim(),
+                            state.b_input.trim(),
+                            state.c_input.trim(),
+                            state.d_input.trim()
+                        );
+                        let result = parse_equivalent(&equation)
+                            .and_then(|[a, b, c, d]| solve_equivalent(a, b, c, d));
+                        state.result = Some(match result {
+                            Ok(res) if res.is_integer() => res.to_string(),
+                            Ok(res) => format!("{} (≈ {})", res, res.to_f64()),
+                            Err(e) => error_text(&e, &equation),
+                        });
                     }
                     EquivalentFractionMessage::Reset => {
                         *state = EquivalentFractionState::default();
@@ -719,21 +1397,18 @@ impl Sandbox for MathGui {
                         state.denominator_input = value;
                     }
                     SimplifyingFractionsMessage::Calculate => {
-                        let numerator: Result<u32, _> = state.numerator_input.parse();
-                        let denominator: Result<u32, _> = state.denominator_input.parse();
-
-                        if let (Ok(mut num), Ok(mut den)) = (numerator, denominator) {
-                            if den == 0 {
-                                state.result = Some("Error: Denominator cannot be zero.".to_string());
-                            } else {
-                                let common_factor = hcf(num, den);
-                                num /= common_factor;
-                                den /= common_factor;
-                                state.result = Some(format!("{}/{}", num, den));
-                            }
-                        } else {
-                            state.result = Some("Please enter valid numbers.".to_string());
-                        }
+                        let numerator = parse_number::<i64>(&state.numerator_input);
+                        let denominator = parse_number::<i64>(&state.denominator_input);
+
+                        state.result = match (numerator, denominator) {
+                            // `Fraction` always stores a fraction in its lowest terms.
+                            (Ok(num), Ok(den)) => match Fraction::new(num, den) {
+                                Some(fraction) => Some(fraction.to_string()),
+                                None => Some(error_text(&MathError::ZeroDenominator { position: None }, "")),
+                            },
+                            (Err(e), _) => Some(error_text(&e, &state.numerator_input)),
+                            (_, Err(e)) => Some(error_text(&e, &state.denominator_input)),
+                        };
                     }
                     SimplifyingFractionsMessage::Reset => {
                         *state = SimplifyingFractionsState::default();
@@ -753,20 +1428,19 @@ impl Sandbox for MathGui {
                         state.denominator_input = value;
                     }
                     MixedNumbersMessage::CalculateMixedToImproper => {
-                        let whole: Result<u32, _> = state.whole_input.parse();
-                        let numerator: Result<u32, _> = state.numerator_input.parse();
-                        let denominator: Result<u32, _> = state.denominator_input.parse();
-
-                        if let (Ok(w), Ok(n), Ok(d)) = (whole, numerator, denominator) {
-                            if d == 0 {
-                                state.result_improper = Some("Error: Denominator cannot be zero.".to_string());
-                            } else {
-                                let improper_numerator = w * d + n;
-                                state.result_improper = Some(format!("{}/{}", improper_numerator, d));
-                            }
-                        } else {
-                            state.result_improper = Some("Please enter valid numbers.".to_string());
-                        }
+                        let whole = parse_number::<i64>(&state.whole_input);
+                        let numerator = parse_number::<u32>(&state.numerator_input);
+                        let denominator = parse_number::<u32>(&state.denominator_input);
+
+                        state.result_improper = match (whole, numerator, denominator) {
+                            (Ok(w), Ok(n), Ok(d)) => match Fraction::from_mixed(w, n as i64, d as i64) {
+                                Some(improper) => Some(improper.to_string()),
+                                None => Some(error_text(&MathError::ZeroDenominator { position: None }, "")),
+                            },
+                            (Err(e), _, _) => Some(error_text(&e, &state.whole_input)),
+                            (_, Err(e), _) => Some(error_text(&e, &state.numerator_input)),
+                            (_, _, Err(e)) => Some(error_text(&e, &state.denominator_input)),
+                        };
                     }
                     MixedNumbersMessage::ImproperNumeratorChanged(value) => {
                         state.improper_numerator_input = value;
@@ -775,25 +1449,27 @@ impl Sandbox for MathGui {
                         state.improper_denominator_input = value;
                     }
                     MixedNumbersMessage::CalculateImproperToMixed => {
-                        let numerator: Result<u32, _> = state.improper_numerator_input.parse();
-                        let denominator: Result<u32, _> = state.improper_denominator_input.parse();
-
-                        if let (Ok(n), Ok(d)) = (numerator, denominator) {
-                            if d == 0 {
-                                state.result_mixed = Some("Error: Denominator cannot be zero.".to_string());
-                            } else if n < d {
-                                state.result_mixed = Some(format!("{} is not an improper fraction.", format!("{}/{}", n, d)));
-                            } else {
-                                let whole_part = n / d;
-                                let remainder_numerator = n % d;
-                                if remainder_numerator == 0 {
-                                    state.result_mixed = Some(format!("{}", whole_part));
-                                } else {
-                                    state.result_mixed = Some(format!("{}({}/{})", whole_part, remainder_numerator, d));
+                        let numerator = parse_number::<i64>(&state.improper_numerator_input);
+                        let denominator = parse_number::<i64>(&state.improper_denominator_input);
+
+                        if let (Ok(n), Ok(d)) = (&numerator, &denominator) {
+                            let (n, d) = (*n, *d);
+                            match Fraction::new(n, d) {
+                                None => {
+                                    state.result_mixed =
+                                        Some(error_text(&MathError::ZeroDenominator { position: None }, ""));
+                                }
+                                Some(fraction) if n.unsigned_abs() < d.unsigned_abs() => {
+                                    state.result_mixed = Some(format!("{} is not an improper fraction.", fraction));
+                                }
+                                Some(fraction) => {
+                                    state.result_mixed = Some(fraction.to_mixed_string());
                                 }
                             }
-                        } else {
-                            state.result_mixed = Some("Please enter valid numbers.".to_string());
+                        } else if let Err(e) = numerator {
+                            state.result_mixed = Some(error_text(&e, &state.improper_numerator_input));
+                        } else if let Err(e) = denominator {
+                            state.result_mixed = Some(error_text(&e, &state.improper_denominator_input));
                         }
                     }
                     MixedNumbersMessage::Reset => {
@@ -807,75 +1483,177 @@ impl Sandbox for MathGui {
                     OrderingFractionsMessage::FractionsInputChanged(value) => {
                         state.fractions_input = value;
                     }
+                    OrderingFractionsMessage::OrderSelected(order) => {
+                        state.order = order;
+                    }
                     OrderingFractionsMessage::Calculate => {
-                        let fractions_str: Vec<&str> = state.fractions_input.split(',').collect();
-                        let mut fractions: Vec<(u32, u32)> = Vec::new();
-
-                        for f_str in fractions_str {
-                            let parts: Vec<&str> = f_str.trim().split('/').collect();
-                            if parts.len() == 2 {
-                                if let (Ok(num), Ok(den)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>()) {
-                                    if den == 0 {
-                                        state.result = Some(format!("Error: Denominator cannot be zero for fraction {}.", f_str));
-                                        fractions.clear();
-                                        break;
+                        state.result = match parse_fraction_list(&state.fractions_input) {
+                            Err(e) => Some(error_text(&e, &state.fractions_input)),
+                            Ok(fractions) => {
+                                let values: Vec<Fraction> = fractions.iter().map(|(_, fraction)| *fraction).collect();
+                                match common_denominator(&values) {
+                                    None => Some(error_text(&MathError::Overflow, "")),
+                                    Some((common_denominator, numerators)) => {
+                                        let mut result_str = String::new();
+                                        result_str.push_str(&format!("Common Denominator: {}\n", common_denominator));
+                                        result_str.push_str("Rewritten Fractions:\n");
+
+                                        for ((f_str, _), new_numerator) in fractions.iter().zip(numerators) {
+                                            result_str.push_str(&format!("  {} becomes {}/{}\n", f_str, new_numerator, common_denominator));
+                                        }
+
+                                        let ordered: Vec<(&str, Fraction)> = order_fractions(&values, state.order)
+                                            .into_iter()
+                                            .map(|i| fractions[i])
+                                            .collect();
+                                        result_str.push_str(&format!("{} Order:\n", state.order));
+                                        result_str.push_str(&format!("  {}", ordering_statement(&ordered, state.order)));
+                                        Some(result_str)
                                     }
-                                    fractions.push((num, den));
-                                } else {
-                                    state.result = Some(format!("Error: Invalid number in fraction {}.", f_str));
-                                    fractions.clear();
-                                    break;
                                 }
-                            } else {
-                                state.result = Some(format!("Error: Invalid fraction format {}. Expected numerator/denominator.", f_str));
-                                fractions.clear();
-                                break;
                             }
-                        }
+                        };
+                    }
+                    OrderingFractionsMessage::Reset => {
+                        *state = OrderingFractionsState::default();
+                    }
+                }
+            }
+            Message::FractionArithmetic(msg) => {
+                let state = &mut self.fraction_arithmetic_state;
+                match msg {
+                    FractionArithmeticMessage::LeftChanged(value) => {
+                        state.left_input = value;
+                    }
+                    FractionArithmeticMessage::OperationSelected(operation) => {
+                        state.operation = operation;
+                    }
+                    FractionArithmeticMessage::RightChanged(value) => {
+                        state.right_input = value;
+                    }
+                    FractionArithmeticMessage::Calculate => {
+                        // Write the calculation out in full, so that an error can point at the
+                        // fraction that caused it.
+                        let calculation = format!(
+                            "{} {} {}",
+                            state.left_input.trim(),
+                            state.operation,
+                            state.right_input.trim()
+                        );
+                        state.result = match calculation.parse::<FractionCalculation>() {
+                            Err(e) => Some(error_text(&e, &calculation)),
+                            Ok(calculation) => {
+                                let mut result_str = String::new();
+                                for (i, step) in calculation.steps().iter().enumerate() {
+                                    result_str.push_str(&format!("{}. {}\n", i + 1, step));
+                                }
 
-                        if fractions.is_empty() {
-                            state.result = Some("Please enter valid fractions.".to_string());
-                        }
-                        else {
-                            let mut common_denominator = fractions[0].1;
-                            for i in 1..fractions.len() {
-                                common_denominator = lcm(common_denominator, fractions[i].1);
+                                // The decimal is found by dividing exactly, so it is only rounded if it recurs.
+                                let result = calculation.result;
+                                result_str.push_str(&format!("Improper fraction: {}\n", result));
+                                result_str.push_str(&format!("Mixed number: {}\n", result.to_mixed_string()));
+                                if calculation.exact_decimal {
+                                    result_str.push_str(&format!("Decimal: {}", calculation.decimal));
+                                } else {
+                                    result_str.push_str(&format!(
+                                        "Decimal: {} (to {} significant figures)",
+                                        calculation.decimal, DIVISION_FIGURES
+                                    ));
+                                }
+                                Some(result_str)
                             }
-
-                            let mut result_str = String::new();
-                            result_str.push_str(&format!("Common Denominator: {}
-", common_denominator));
-                            result_str.push_str("Rewritten Fractions:\n");
-
-                            for (num, den) in fractions {
-                                let multiplier = common_denominator / den;
-                                let new_numerator = num * multiplier;
-                                result_str.push_str(&format!("  {}/{} becomes {}/{}\n", num, den, new_numerator, common_denominator));
+                        };
+                    }
+                    FractionArithmeticMessage::Reset => {
+                        *state = FractionArithmeticState::default();
+                    }
+                }
+            }
+            Message::StandardForm(msg) => {
+                let state = &mut self.standard_form_state;
+                match msg {
+                    StandardFormMessage::ExpressionChanged(value) => {
+                        state.expression = value;
+                    }
+                    StandardFormMessage::Calculate => {
+                        // A single number is converted; two numbers with an operator between
+                        // them are combined.
+                        let worked = match parse_standard_form_calculation(&state.expression) {
+                            Ok((left, operation, right)) => {
+                                StandardFormCalculation::new(left, operation, right).map(|calculation| {
+                                    let answer = if calculation.exact {
+                                        format!("Answer: {}", calculation.result)
+                                    } else {
+                                        format!("Answer: {} (to {} significant figures)", calculation.result, DIVISION_FIGURES)
+                                    };
+                                    (calculation.steps(), answer)
+                                })
                             }
-                            state.result = Some(result_str);
-                        }
+                            Err(MathError::MissingOperator) => state.expression.parse().map(|number| {
+                                let conversion = StandardFormConversion::new(number);
+                                (conversion.steps(), conversion.answer())
+                            }),
+                            Err(e) => Err(e),
+                        };
+                        state.result = Some(match worked {
+                            Ok((steps, answer)) => {
+                                let mut result_str = String::new();
+                                for (i, step) in steps.iter().enumerate() {
+                                    result_str.push_str(&format!("{}. {}\n", i + 1, step));
+                                }
+                                result_str.push_str(&answer);
+                                result_str
+                            }
+                            Err(e) => error_text(&e, &state.expression),
+                        });
                     }
-                    OrderingFractionsMessage::Reset => {
-                        *state = OrderingFractionsState::default();
+                    StandardFormMessage::Reset => {
+                        *state = StandardFormState::default();
                     }
                 }
             }
         }
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         let content = if let Some(calculator) = self.selected_calculator {
             // --- View for a selected calculator ---
-            let view_content = match calculator {
+            match calculator {
                 Calculator::Bodmas => {
                     let state = &self.bodmas_state;
-                    let result_text = match &state.result {
-                        Some(res) => format!("Result: {}", res),
-                        None => "Enter an expression.".to_string(),
+                    let label = &state.label;
+                    let result_text = match (&state.result, &state.exact, state.form) {
+                        (Some(_), Some(exact), AnswerForm::Exact) => format!("{}{}", label, exact),
+                        // Only a worked answer has steps; an error has nothing to add.
+                        (Some(res), None, AnswerForm::Exact) if !state.steps.is_empty() => {
+                            format!("{}{} (there is no exact form)", label, res)
+                        }
+                        (Some(res), _, _) => format!("{}{}", label, res),
+                        (None, _, _) => {
+                            "Enter an expression, a value such as r = 3.5 or a function such as f(x) = 2x+1.".to_string()
+                        }
                     };
+                    let definitions = match state.session.definitions() {
+                        definitions if definitions.is_empty() => "Nothing has been defined yet.".to_string(),
+                        definitions => definitions.join("\n"),
+                    };
+                    // Earlier lines are listed newest first; pressing one puts it back in the box.
+                    let history = Column::with_children(
+                        state
+                            .history
+                            .iter()
+                            .map(|line| {
+                                button(text(line)).on_press(Message::Bodmas(BodmasMessage::Recall(line.clone()))).into()
+                            })
+                            .collect::<Vec<Element<'_, Message>>>(),
+                    )
+                    .spacing(5);
 
                     column![
                         text(calculator.name()).size(30),
+                        pick_list(&AnswerForm::ALL[..], Some(state.form), |form| {
+                            Message::Bodmas(BodmasMessage::FormSelected(form))
+                        }),
                         text_input("Enter expression (e.g., 2 * (3 + 4))", &state.expression)
                             .on_input(|s| Message::Bodmas(BodmasMessage::ExpressionChanged(s))),
                         row![
@@ -884,14 +1662,28 @@ impl Sandbox for MathGui {
                         ]
                         .spacing(10),
                         text(result_text).size(25),
+                        // Each step names the BODMAS rule it follows; long working scrolls.
+                        scrollable(text(state.steps.join("\n")).size(18)).height(Length::Fixed(220.0)),
+                        row![
+                            column![text("Variables and functions").size(20), text(definitions).size(18)]
+                                .spacing(5)
+                                .width(Length::FillPortion(1)),
+                            column![
+                                text("Earlier lines").size(20),
+                                scrollable(history).height(Length::Fixed(120.0)),
+                            ]
+                            .spacing(5)
+                            .width(Length::FillPortion(1)),
+                        ]
+                        .spacing(20),
                         button("Back").on_press(Message::BackToMenu),
                     ]
                 }
                 Calculator::DecimalPlaces => {
                     let state = &self.decimal_places_state;
-                    let result_text = match state.result {
+                    let result_text = match &state.result {
                         Some(res) => format!("Result: {}", res),
-                        None => "Please enter valid numbers.".to_string(),
+                        None => "Enter a number and the number of decimal places.".to_string(),
                     };
 
                     column![
@@ -904,6 +1696,9 @@ impl Sandbox for MathGui {
                             .on_input(|s| {
                                 Message::DecimalPlaces(DecimalPlacesMessage::PlacesInputChanged(s))
                             }),
+                        pick_list(&RoundingMode::ALL[..], Some(state.mode), |mode| {
+                            Message::DecimalPlaces(DecimalPlacesMessage::ModeSelected(mode))
+                        }),
                         row![
                             button("Calculate")
                                 .on_press(Message::DecimalPlaces(DecimalPlacesMessage::Calculate)),
@@ -924,7 +1719,7 @@ impl Sandbox for MathGui {
 
                     column![
                         text(calculator.name()).size(30),
-                        text_input("Numbers", &state.numbers_input)
+                        text_input("Numbers (e.g., 4 6 8 or 1..20)", &state.numbers_input)
                             .on_input(|s| Message::Hcf(HcfMessage::NumbersInputChanged(s))),
                         row![
                             button("Calculate").on_press(Message::Hcf(HcfMessage::Calculate)),
@@ -944,7 +1739,7 @@ impl Sandbox for MathGui {
 
                     column![
                         text(calculator.name()).size(30),
-                        text_input("Numbers", &state.numbers_input)
+                        text_input("Numbers (e.g., 4 6 8 or 1..20)", &state.numbers_input)
                             .on_input(|s| Message::Lcm(LcmMessage::NumbersInputChanged(s))),
                         row![
                             button("Calculate").on_press(Message::Lcm(LcmMessage::Calculate)),
@@ -987,14 +1782,23 @@ impl Sandbox for MathGui {
                 }
                 Calculator::PrimeNumbers => {
                     let state = &self.prime_numbers_state;
+                    let (placeholder, hint) = match state.mode {
+                        PrimeMode::Check => ("Numbers", "Enter numbers separated by spaces."),
+                        PrimeMode::Sieve => {
+                            ("N, or A and B", "Enter N to list the primes up to N, or A and B for the primes from A to B.")
+                        }
+                    };
                     let result_text = match &state.result {
                         Some(res) => res.clone(),
-                        None => "Enter numbers separated by spaces.".to_string(),
+                        None => hint.to_string(),
                     };
 
                     column![
                         text(calculator.name()).size(30),
-                        text_input("Numbers", &state.numbers_input).on_input(|s| {
+                        pick_list(&PrimeMode::ALL[..], Some(state.mode), |mode| {
+                            Message::PrimeNumbers(PrimeNumbersMessage::ModeSelected(mode))
+                        }),
+                        text_input(placeholder, &state.numbers_input).on_input(|s| {
                             Message::PrimeNumbers(PrimeNumbersMessage::NumbersInputChanged(s))
                         }),
                         row![
@@ -1015,11 +1819,26 @@ impl Sandbox for MathGui {
                         None => "Enter a number.".to_string(),
                     };
 
+                    // The working is drawn as a factor tree on a canvas, or written out as a ladder.
+                    let diagram: Element<Message> = match state.diagram {
+                        FactorDiagram::Tree => {
+                            let tree = FactorTree { steps: state.steps.clone() };
+                            let (width, height) = tree.size();
+                            draw(tree).width(Length::Fixed(width)).height(Length::Fixed(height)).into()
+                        }
+                        FactorDiagram::Ladder => {
+                            text(division_ladder(&state.steps).join("\n")).size(20).font(Font::MONOSPACE).into()
+                        }
+                    };
+
                     column![
                         text(calculator.name()).size(30),
                         text_input("Number", &state.number_input).on_input(|s| {
                             Message::ProdPrimeFactor(ProdPrimeFactorMessage::NumberInputChanged(s))
                         }),
+                        pick_list(&FactorDiagram::ALL[..], Some(state.diagram), |diagram| {
+                            Message::ProdPrimeFactor(ProdPrimeFactorMessage::DiagramSelected(diagram))
+                        }),
                         row![
                             button("Calculate").on_press(Message::ProdPrimeFactor(
                                 ProdPrimeFactorMessage::Calculate
@@ -1029,13 +1848,14 @@ impl Sandbox for MathGui {
                             )),
                         ]
                         .spacing(10),
+                        diagram,
                         text(result_text).size(25),
                         button("Back").on_press(Message::BackToMenu),
                     ]
                 }
                 Calculator::EquivalentFraction => {
                     let state = &self.equivalent_fraction_state;
-                    let result_text = match state.result {
+                    let result_text = match &state.result {
                         Some(res) => format!("Result: {}", res),
                         None => "Enter three values and 'x' for the unknown.".to_string(),
                     };
@@ -1152,13 +1972,20 @@ impl Sandbox for MathGui {
                     let state = &self.ordering_fractions_state;
                     let result_text = match &state.result {
                         Some(res) => res.clone(),
-                        None => "Enter fractions separated by commas (e.g., 5/6, 3/8).".to_string(),
+                        None => "Enter fractions, decimals or percentages separated by commas (e.g., 3/8, 0.4, 35%, 1 1/4).".to_string(),
                     };
 
                     column![
                         text(calculator.name()).size(30),
-                        text_input("Fractions (e.g., 5/6, 3/8)", &state.fractions_input)
-                            .on_input(|s| Message::OrderingFractions(OrderingFractionsMessage::FractionsInputChanged(s))),
+                        row![
+                            text_input("Fractions (e.g., 3/8, 0.4, 35%, 1 1/4)", &state.fractions_input)
+                                .on_input(|s| Message::OrderingFractions(OrderingFractionsMessage::FractionsInputChanged(s))),
+                            pick_list(&SortOrder::ALL[..], Some(state.order), |order| {
+                                Message::OrderingFractions(OrderingFractionsMessage::OrderSelected(order))
+                            }),
+                        ]
+                        .spacing(10)
+                        .align_items(Alignment::Center),
                         row![
                             button("Calculate").on_press(Message::OrderingFractions(OrderingFractionsMessage::Calculate)),
                             button("Reset").on_press(Message::OrderingFractions(OrderingFractionsMessage::Reset)),
@@ -1168,13 +1995,261 @@ impl Sandbox for MathGui {
                         button("Back").on_press(Message::BackToMenu),
                     ]
                 }
-                _ => column![
-                    text(calculator.name()).size(30),
-                    text("This calculator has not been implemented in the GUI yet."),
-                    button("Back").on_press(Message::BackToMenu),
-                ],
-            };
-            view_content
+                Calculator::FractionArithmetic => {
+                    let state = &self.fraction_arithmetic_state;
+                    let result_text = match &state.result {
+                        Some(res) => res.clone(),
+                        None => "Enter two fractions or mixed numbers (e.g., 2 1/3 and 3/4).".to_string(),
+                    };
+
+                    column![
+                        text(calculator.name()).size(30),
+                        row![
+                            text_input("First fraction", &state.left_input)
+                                .on_input(|s| Message::FractionArithmetic(FractionArithmeticMessage::LeftChanged(s))),
+                            pick_list(&Operation::ALL[..], Some(state.operation), |operation| {
+                                Message::FractionArithmetic(FractionArithmeticMessage::OperationSelected(operation))
+                            }),
+                            text_input("Second fraction", &state.right_input)
+                                .on_input(|s| Message::FractionArithmetic(FractionArithmeticMessage::RightChanged(s))),
+                        ]
+                        .spacing(10)
+                        .align_items(Alignment::Center),
+                        row![
+                            button("Calculate").on_press(Message::FractionArithmetic(FractionArithmeticMessage::Calculate)),
+                            button("Reset").on_press(Message::FractionArithmetic(FractionArithmeticMessage::Reset)),
+                        ]
+                        .spacing(10),
+                        text(result_text).size(20),
+                        button("Back").on_press(Message::BackToMenu),
+                    ]
+                }
+                Calculator::StandardForm => {
+                    let state = &self.standard_form_state;
+                    let result_text = match &state.result {
+                        Some(res) => res.clone(),
+                        None => "Enter a number to convert (e.g., 320000 or 3.2 x 10^-4) or a calculation \
+                                 (e.g., 3.2e5 × 4e3)."
+                            .to_string(),
+                    };
+
+                    column![
+                        text(calculator.name()).size(30),
+                        text_input("Number or calculation", &state.expression)
+                            .on_input(|s| Message::StandardForm(StandardFormMessage::ExpressionChanged(s))),
+                        row![
+                            button("Calculate").on_press(Message::StandardForm(StandardFormMessage::Calculate)),
+                            button("Reset").on_press(Message::StandardForm(StandardFormMessage::Reset)),
+                        ]
+                        .spacing(10),
+                        text(result_text).size(20),
+                        button("Back").on_press(Message::BackToMenu),
+                    ]
+                }
+                Calculator::Estimation => {
+                    let state = &self.estimation_state;
+                    let result_text = match &state.result {
+                        Some(res) => res.clone(),
+                        None => "Enter an expression to estimate.".to_string(),
+                    };
+
+                    column![
+                        text(calculator.name()).size(30),
+                        text_input("Expression (e.g., (9.7*326)/(1.823*5.325))", &state.expression)
+                            .on_input(|s| Message::Estimation(EstimationMessage::ExpressionChanged(s))),
+                        text_input("Round each number to (e.g., 1 s.f., 2 d.p. or 10)", &state.accuracy_input)
+                            .on_input(|s| Message::Estimation(EstimationMessage::AccuracyInputChanged(s))),
+                        pick_list(&RoundingMode::ALL[..], Some(state.mode), |mode| {
+                            Message::Estimation(EstimationMessage::ModeSelected(mode))
+                        }),
+                        row![
+                            button("Calculate").on_press(Message::Estimation(EstimationMessage::Calculate)),
+                            button("Reset").on_press(Message::Estimation(EstimationMessage::Reset)),
+                        ]
+                        .spacing(10),
+                        text(result_text).size(20),
+                        button("Back").on_press(Message::BackToMenu),
+                    ]
+                }
+                Calculator::EstimationSquareRoot => {
+                    let state = &self.estimation_square_root_state;
+                    let result_text = match &state.result {
+                        Some(res) => res.clone(),
+                        None => "Enter a number to estimate its square root.".to_string(),
+                    };
+
+                    column![
+                        text(calculator.name()).size(30),
+                        text_input("Number", &state.number_input).on_input(|s| {
+                            Message::EstimationSquareRoot(EstimationSquareRootMessage::NumberInputChanged(s))
+                        }),
+                        text_input("Decimal places (leave empty for the nearest whole number)", &state.places_input)
+                            .on_input(|s| {
+                                Message::EstimationSquareRoot(EstimationSquareRootMessage::PlacesInputChanged(s))
+                            }),
+                        row![
+                            button("Calculate")
+                                .on_press(Message::EstimationSquareRoot(EstimationSquareRootMessage::Calculate)),
+                            button("Reset").on_press(Message::EstimationSquareRoot(EstimationSquareRootMessage::Reset)),
+                        ]
+                        .spacing(10),
+                        text(result_text).size(20),
+                        button("Back").on_press(Message::BackToMenu),
+                    ]
+                }
+                Calculator::Rounding => {
+                    let state = &self.rounding_state;
+                    let result_text = match &state.result {
+                        Some(res) => res.clone(),
+                        None => "Enter a number and choose what to round to.".to_string(),
+                    };
+
+                    column![
+                        text(calculator.name()).size(30),
+                        row![
+                            text_input("Number to round", &state.number_input)
+                                .on_input(|s| Message::Rounding(RoundingMessage::NumberInputChanged(s))),
+                            pick_list(&RoundingPlace::ALL[..], Some(state.place), |place| {
+                                Message::Rounding(RoundingMessage::PlaceSelected(place))
+                            }),
+                            pick_list(&RoundingMode::ALL[..], Some(state.mode), |mode| {
+                                Message::Rounding(RoundingMessage::ModeSelected(mode))
+                            }),
+                        ]
+                        .spacing(10)
+                        .align_items(Alignment::Center),
+                        row![
+                            button("Calculate").on_press(Message::Rounding(RoundingMessage::Calculate)),
+                            button("Reset").on_press(Message::Rounding(RoundingMessage::Reset)),
+                        ]
+                        .spacing(10),
+                        text(result_text).size(25),
+                        button("Back").on_press(Message::BackToMenu),
+                    ]
+                }
+                Calculator::SignificantFigures => {
+                    let state = &self.significant_figures_state;
+                    let result_text = match &state.result {
+                        Some(res) => res.clone(),
+                        None => "Enter a number and the number of significant figures.".to_string(),
+                    };
+
+                    column![
+                        text(calculator.name()).size(30),
+                        text_input("Number to round", &state.number_input).on_input(|s| {
+                            Message::SignificantFigures(SignificantFiguresMessage::NumberInputChanged(s))
+                        }),
+                        text_input("Significant figures", &state.sf_input)
+                            .on_input(|s| Message::SignificantFigures(SignificantFiguresMessage::SfInputChanged(s))),
+                        pick_list(&RoundingMode::ALL[..], Some(state.mode), |mode| {
+                            Message::SignificantFigures(SignificantFiguresMessage::ModeSelected(mode))
+                        }),
+                        row![
+                            button("Calculate").on_press(Message::SignificantFigures(SignificantFiguresMessage::Calculate)),
+                            button("Reset").on_press(Message::SignificantFigures(SignificantFiguresMessage::Reset)),
+                        ]
+                        .spacing(10),
+                        text(result_text).size(25),
+                        button("Back").on_press(Message::BackToMenu),
+                    ]
+                }
+                Calculator::CountingFigures => {
+                    let state = &self.counting_figures_state;
+                    let result_text = match &state.result {
+                        Some(Ok(count)) => format!(
+                            "Green: significant, grey: leading zero, blue: trailing zero, orange: ambiguous zero\n\
+                             Significant figures: {}\nDecimal places: {}",
+                            count.significant_figures_text(),
+                            count.decimal_places
+                        ),
+                        Some(Err(error)) => error.clone(),
+                        None => "Enter a number, e.g. 0.003040 or 12000.".to_string(),
+                    };
+
+                    // Write out the number with each digit coloured by its role. The sign, the
+                    // decimal point and any power of ten are left in the normal colour.
+                    let digits = match &state.result {
+                        Some(Ok(count)) => {
+                            let mut roles = count.digits.iter().map(|digit| digit.role);
+                            count.number.chars().fold(row![], |row, c| {
+                                let role = if c.is_ascii_digit() { roles.next() } else { None };
+                                let digit = text(c).size(40);
+                                match role {
+                                    Some(role) => row.push(digit.style(role_color(role))),
+                                    None => row.push(digit),
+                                }
+                            })
+                        }
+                        _ => row![],
+                    };
+
+                    column![
+                        text(calculator.name()).size(30),
+                        text_input("Number (e.g., 0.003040)", &state.number_input)
+                            .on_input(|s| Message::CountingFigures(CountingFiguresMessage::NumberInputChanged(s))),
+                        row![
+                            button("Calculate").on_press(Message::CountingFigures(CountingFiguresMessage::Calculate)),
+                            button("Reset").on_press(Message::CountingFigures(CountingFiguresMessage::Reset)),
+                        ]
+                        .spacing(10),
+                        digits,
+                        text(result_text).size(20),
+                        button("Back").on_press(Message::BackToMenu),
+                    ]
+                }
+                Calculator::UpperLowerBounds => {
+                    let state = &self.upper_lower_bounds_state;
+                    let result_text = match &state.result {
+                        Some(res) => res.clone(),
+                        None => "Enter the rounded or truncated number and the degree of accuracy.".to_string(),
+                    };
+
+                    column![
+                        text(calculator.name()).size(30),
+                        text_input("Rounded number", &state.number_input).on_input(|s| {
+                            Message::UpperLowerBounds(UpperLowerBoundsMessage::NumberInputChanged(s))
+                        }),
+                        text_input("Degree of accuracy (e.g., 10, 0.1, 1 d.p. or 2 s.f.)", &state.accuracy_input)
+                            .on_input(|s| Message::UpperLowerBounds(UpperLowerBoundsMessage::AccuracyInputChanged(s))),
+                        pick_list(&Approximation::ALL[..], Some(state.approximation), |approximation| {
+                            Message::UpperLowerBounds(UpperLowerBoundsMessage::ApproximationSelected(approximation))
+                        }),
+                        row![
+                            button("Calculate").on_press(Message::UpperLowerBounds(UpperLowerBoundsMessage::Calculate)),
+                            button("Reset").on_press(Message::UpperLowerBounds(UpperLowerBoundsMessage::Reset)),
+                        ]
+                        .spacing(10),
+                        text(result_text).size(25),
+                        button("Back").on_press(Message::BackToMenu),
+                    ]
+                }
+                Calculator::CalculationBounds => {
+                    let state = &self.calculation_bounds_state;
+                    let result_text = match &state.result {
+                        Some(res) => res.clone(),
+                        None => "Enter a calculation such as d / t and the rounded values it uses.".to_string(),
+                    };
+
+                    column![
+                        text(calculator.name()).size(30),
+                        text_input("Calculation (e.g., d / t or (a + b) × c)", &state.expression).on_input(|s| {
+                            Message::CalculationBounds(CalculationBoundsMessage::ExpressionChanged(s))
+                        }),
+                        text_input(
+                            "Values, separated by ; (e.g., d = 120 (nearest 10); t = 2.5 (1 d.p.))",
+                            &state.quantities_input
+                        )
+                        .on_input(|s| Message::CalculationBounds(CalculationBoundsMessage::QuantitiesChanged(s))),
+                        row![
+                            button("Calculate").on_press(Message::CalculationBounds(CalculationBoundsMessage::Calculate)),
+                            button("Reset").on_press(Message::CalculationBounds(CalculationBoundsMessage::Reset)),
+                        ]
+                        .spacing(10),
+                        text(result_text).size(20),
+                        button("Back").on_press(Message::BackToMenu),
+                    ]
+                }
+            }
         } else {
             // --- Main menu view ---
             let menu_buttons = Calculator::ALL.iter().fold(column![], |col, &calc| {
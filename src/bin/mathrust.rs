@@ -0,0 +1,761 @@
+// This program brings every calculator together in one `mathrust` command.
+// Each calculator is a subcommand, so answers can be worked out straight from the command line
+// or from a shell script, for example:
+//
+//   mathrust hcf 12 18 30
+//   mathrust sf 0.00097151 3
+//   mathrust bodmas "20-3*4"
+//   mathrust order 5/6 3/8
+//
+// Running `mathrust` with no arguments starts an interactive prompt where the same commands
+// can be typed one after another, and `mathrust batch problems.txt` solves a whole file of
+// problems at once.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+// Clap reads the command-line arguments and writes the `--help` pages for us.
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+
+use mathrust::bounds::{Accuracy, Approximation, BoundsCalculation, Quantity};
+use mathrust::decimal::{Decimal, RoundingMode};
+use mathrust::error::{MathError, Span};
+use mathrust::figures::FigureCount;
+use mathrust::fractions::{parse_equivalent, parse_fraction_list, Fraction, SortOrder};
+use mathrust::number::{parse_number, parse_whole_numbers};
+use mathrust::primes::{division_ladder, factor_tree, parse_prime_range, PrimeRange};
+use mathrust::results::{
+    BodmasResult, BoundsResult, CalculatorResult, DecimalPlacesResult, EquivalentFractionResult, EstimationResult,
+    HcfResult, LcmResult, MixedNumberResult, MultiplesResult, OrderingResult, PrimeFactorsResult, PrimesResult,
+    RoundingResult, SignificantFiguresResult, SimplifyResult, SquareRootEstimateResult,
+};
+use mathrust::standard_form::{parse_standard_form_calculation, StandardFormCalculation, StandardFormConversion};
+
+/// GCSE maths calculators that show their working.
+#[derive(Debug, Parser)]
+#[command(name = "mathrust", version, after_help = "Run without a command to type commands interactively.")]
+struct Cli {
+    /// Show the working before each answer.
+    #[arg(short, long, global = true)]
+    working: bool,
+
+    /// How to print the answer.
+    #[arg(short, long, global = true, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// The calculators available as subcommands.
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Evaluate an expression such as "20 - 3 * 4" using the BODMAS order of operations,
+    /// working through it one step at a time with `--working`.
+    #[command(alias = "calc")]
+    Bodmas {
+        /// Give the answer exactly, as a fraction or surd such as 1/2 or 3√2, where it has one.
+        #[arg(short, long)]
+        exact: bool,
+        #[command(flatten)]
+        expression: Expression,
+    },
+    /// Round a number to a number of decimal places.
+    #[command(name = "dp", alias = "decimal-places")]
+    DecimalPlaces {
+        #[arg(allow_negative_numbers = true)]
+        number: Decimal,
+        places: u32,
+        /// The rounding rule: half-up, half-even, truncate, floor or ceiling.
+        #[arg(short, long, default_value = "half-up")]
+        mode: RoundingMode,
+    },
+    /// Estimate an expression such as "(9.7*326)/(1.823*5.325)" by rounding each number to one
+    /// significant figure, and compare the estimate with the exact answer.
+    Estimate {
+        #[command(flatten)]
+        expression: Expression,
+        /// How accurately to round each number: "1 s.f.", "2 d.p." or a step such as 10.
+        #[arg(short, long, default_value = "1 s.f.")]
+        accuracy: String,
+        /// The rounding rule: half-up, half-even, truncate, floor or ceiling.
+        #[arg(short, long, default_value = "half-up")]
+        mode: RoundingMode,
+    },
+    /// Estimate a square root from the perfect squares either side of the number.
+    Sqrt {
+        #[arg(allow_negative_numbers = true)]
+        number: f64,
+        /// Decimal places for the estimate.
+        #[arg(default_value_t = 0)]
+        places: u32,
+    },
+    /// Find the highest common factor of some numbers.
+    Hcf {
+        /// The numbers, e.g. 12 18 30, or a range such as 1..10.
+        #[arg(required = true)]
+        numbers: Vec<String>,
+    },
+    /// Find the lowest common multiple of some numbers.
+    Lcm {
+        /// The numbers, e.g. 4 6 8, or a range such as 1..40.
+        #[arg(required = true)]
+        numbers: Vec<String>,
+    },
+    /// List the first multiples of some numbers.
+    Multiples {
+        #[arg(required = true)]
+        numbers: Vec<u32>,
+        /// How many multiples to list.
+        #[arg(short, long, default_value_t = 20)]
+        count: usize,
+    },
+    /// Check whether numbers are prime. Numbers can be as large as 2^128 - 1.
+    Primes {
+        #[arg(required = true)]
+        numbers: Vec<u128>,
+    },
+    /// List the primes up to N, or from A to B, with a Sieve of Eratosthenes, e.g. 100 or
+    /// 1000 2000. Ranges can reach 10000000000.
+    Sieve {
+        #[arg(required = true, num_args = 1..)]
+        range: Vec<String>,
+    },
+    /// Write a number as a product of its prime factors in index form, e.g. 2³ × 3². Numbers can
+    /// be as large as 2^128 - 1.
+    #[command(alias = "prime-factors")]
+    Factors {
+        number: u128,
+        /// Draw the working as a factor tree or a division ladder.
+        #[arg(short, long, value_enum)]
+        diagram: Option<Diagram>,
+    },
+    /// Round a number to a place value such as 10, 100 or 1000.
+    Round {
+        #[arg(allow_negative_numbers = true)]
+        number: Decimal,
+        /// The place value to round to, e.g. 1 for the nearest whole number or 10 for the nearest ten.
+        #[arg(default_value = "1")]
+        place: Decimal,
+        /// The rounding rule: half-up, half-even, truncate, floor or ceiling.
+        #[arg(short, long, default_value = "half-up")]
+        mode: RoundingMode,
+    },
+    /// Round a number to a number of significant figures.
+    #[command(alias = "significant-figures")]
+    Sf {
+        #[arg(allow_negative_numbers = true)]
+        number: Decimal,
+        figures: u32,
+        /// The rounding rule: half-up, half-even, truncate, floor or ceiling.
+        #[arg(short, long, default_value = "half-up")]
+        mode: RoundingMode,
+    },
+    /// Count the significant figures and decimal places in a number, e.g. 0.003040.
+    #[command(alias = "count")]
+    Figures {
+        /// The number, exactly as written, so that zeros at the end are counted.
+        #[arg(allow_negative_numbers = true)]
+        number: String,
+    },
+    /// Find the error interval of a rounded or truncated number, e.g. 6.5 "1 d.p.".
+    Bounds {
+        #[arg(allow_negative_numbers = true)]
+        number: Decimal,
+        /// The degree of accuracy: a step such as 10 for the nearest ten, or "1 d.p." or "2 s.f.".
+        #[arg(required = true, num_args = 1..)]
+        accuracy: Vec<String>,
+        /// The number was truncated rather than rounded.
+        #[arg(short, long)]
+        truncated: bool,
+    },
+    /// Find the bounds of a calculation with rounded values, e.g. "d / t" "d = 120 (nearest 10)"
+    /// "t = 2.5 (1 d.p.)".
+    #[command(name = "calc-bounds", alias = "calculation-bounds")]
+    CalcBounds {
+        /// The calculation, using the names of the values and + - × ÷ and brackets.
+        expression: String,
+        /// Each value with its accuracy, e.g. "d = 120 (nearest 10)" or "m = 4.3 (1 d.p., truncated)".
+        #[arg(required = true)]
+        quantities: Vec<String>,
+    },
+    /// Find the missing value in a pair of equivalent fractions, e.g. "12/30 = 4/x".
+    #[command(alias = "equiv")]
+    Equivalent {
+        #[arg(required = true, num_args = 1..)]
+        equation: Vec<String>,
+    },
+    /// Simplify a fraction to its lowest terms.
+    Simplify {
+        /// The fraction, e.g. 24/30 or -24/-30.
+        #[arg(allow_hyphen_values = true)]
+        fraction: String,
+    },
+    /// Convert between mixed numbers and improper fractions, e.g. "2 1/3" or 7/3.
+    Mixed {
+        #[command(flatten)]
+        number: Expression,
+    },
+    /// Put fractions, decimals and percentages in order.
+    Order {
+        /// The values to order, e.g. 3/8 -0.4 35% "1 1/4". Any options go before them.
+        #[arg(required = true, num_args = 1.., allow_hyphen_values = true)]
+        values: Vec<String>,
+        /// Put the largest value first.
+        #[arg(short, long)]
+        descending: bool,
+    },
+    /// Add, subtract, multiply or divide fractions, e.g. "2 1/3 + 3/4".
+    Fraction {
+        #[command(flatten)]
+        calculation: Expression,
+    },
+    /// Convert a number to or from standard form, or calculate with numbers in standard form,
+    /// e.g. 320000, "3.2 x 10^-4" or "3.2e5 × 4e3".
+    #[command(name = "sform", alias = "standard-form")]
+    StandardForm {
+        #[command(flatten)]
+        expression: Expression,
+    },
+    /// Solve a file of problems, one per line, such as "sf: 52691, 1" or "equiv: 12/30 = 4/x".
+    ///
+    /// Each line starts with the name of a calculator and a colon. Blank lines and lines
+    /// starting with '#' are skipped. The answers are written as CSV, or as JSON with
+    /// `--format json`, with an error column for any line that could not be solved.
+    Batch {
+        /// The file of problems.
+        file: PathBuf,
+        /// Write the answers to this file instead of printing them.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// An expression, typed as one argument in quotes or as several. Only the first argument may
+/// start with any minus sign, as in `-3^2`; after it, only negative numbers such as `-4` are
+/// read as part of the expression, so that options such as `--working` can still follow it.
+#[derive(Debug, Args)]
+struct Expression {
+    /// What to work out, in quotes or as several arguments.
+    #[arg(value_name = "EXPRESSION", required = true, allow_hyphen_values = true)]
+    first: String,
+    #[arg(value_name = "EXPRESSION", allow_negative_numbers = true, hide = true)]
+    rest: Vec<String>,
+}
+
+impl Expression {
+    /// The arguments joined back together with spaces.
+    fn joined(&self) -> String {
+        std::iter::once(&self.first).chain(&self.rest).cloned().collect::<Vec<_>>().join(" ")
+    }
+}
+
+/// How answers are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+enum Format {
+    /// The answer in words, with the working if `--working` is given.
+    #[default]
+    Text,
+    /// One JSON object per answer, holding every value found along the way.
+    Json,
+}
+
+/// The diagrams that the working for prime factors can be drawn as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Diagram {
+    /// A factor tree, splitting off one prime on each branch.
+    Tree,
+    /// A ladder of repeated divisions by primes.
+    Ladder,
+}
+
+// This is the main function where our program starts.
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if cli.command.is_none() {
+        interactive();
+        return ExitCode::SUCCESS;
+    }
+    if execute(&cli) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Why a command could not be answered.
+#[derive(Debug)]
+enum Failure {
+    /// The calculator could not use its input. The input is kept so that the error can point
+    /// out the part of it that caused the problem.
+    Math { error: MathError, input: String },
+    /// The command itself could not be run, e.g. because a batch file could not be read.
+    Usage(String),
+}
+
+impl Failure {
+    /// The error message on its own, without the input.
+    fn message(&self) -> String {
+        match self {
+            Failure::Math { error, .. } => error.to_string(),
+            Failure::Usage(message) => message.clone(),
+        }
+    }
+
+    /// The error as JSON: its kind, message and, for calculator errors, the input and the
+    /// position of the offending part of it.
+    fn to_json(&self) -> serde_json::Value {
+        match self {
+            Failure::Math { error, input } => {
+                let mut json = serde_json::to_value(error).expect("errors always serialise to JSON");
+                json["message"] = error.to_string().into();
+                json["input"] = input.as_str().into();
+                json
+            }
+            Failure::Usage(message) => serde_json::json!({ "kind": "usage", "message": message }),
+        }
+    }
+}
+
+impl From<MathError> for Failure {
+    // For calculators that take numbers rather than text, there is no input to point into.
+    fn from(error: MathError) -> Failure {
+        Failure::Math { error, input: String::new() }
+    }
+}
+
+/// Attaches the input a calculator was given to any error it reports.
+fn reading(input: &str) -> impl FnOnce(MathError) -> Failure + '_ {
+    move |error| Failure::Math { error, input: input.to_string() }
+}
+
+/// Runs the command given on the command line and prints its answer, returning `false` if it
+/// failed.
+fn execute(cli: &Cli) -> bool {
+    let outcome = match &cli.command {
+        None => return true,
+        Some(Command::Batch { file, output }) => run_batch(file, output.as_deref(), cli.format).map_err(Failure::Usage),
+        Some(command) => run(command).and_then(|result| {
+            if cli.format == Format::Text {
+                print_diagram(command, &result);
+            }
+            print_result(&result, cli.format, cli.working)
+        }),
+    };
+    match outcome {
+        Ok(()) => true,
+        Err(e) => {
+            print_error(&e, cli.format);
+            false
+        }
+    }
+}
+
+/// Draws the diagram a command asked for, if any, above its answer.
+fn print_diagram(command: &Command, result: &CalculatorResult) {
+    if let (Command::Factors { diagram: Some(diagram), .. }, CalculatorResult::PrimeFactors(factors)) = (command, result) {
+        let lines = match diagram {
+            Diagram::Tree => factor_tree(&factors.steps),
+            Diagram::Ladder => division_ladder(&factors.steps),
+        };
+        for line in lines {
+            println!("{}", line);
+        }
+    }
+}
+
+/// Prints a result in the chosen format. Text answers come after their working if that was
+/// asked for; JSON always includes the working.
+fn print_result(result: &CalculatorResult, format: Format, show_working: bool) -> Result<(), Failure> {
+    match format {
+        Format::Text => {
+            if show_working {
+                for (i, step) in result.working().iter().enumerate() {
+                    println!("{}. {}", i + 1, step);
+                }
+            }
+            println!("{}", result.answer());
+        }
+        Format::Json => println!("{}", result_to_json(result)?),
+    }
+    Ok(())
+}
+
+/// Converts a result to JSON, adding its answer and working as text.
+fn result_to_json(result: &CalculatorResult) -> Result<serde_json::Value, Failure> {
+    let mut json = serde_json::to_value(result)
+        .map_err(|e| Failure::Usage(format!("the answer could not be written as JSON: {}", e)))?;
+    json["answer"] = result.answer().into();
+    json["working"] = result.working().into();
+    Ok(json)
+}
+
+/// Prints an error in the chosen format. Text errors go to stderr, with the offending part of
+/// the input underlined; JSON errors go to stdout so that every line of output can be read as
+/// JSON.
+fn print_error(failure: &Failure, format: Format) {
+    match (format, failure) {
+        (Format::Text, Failure::Math { error, input }) => eprintln!("Error: {}", error.report(input)),
+        (Format::Text, Failure::Usage(message)) => eprintln!("Error: {}", message),
+        (Format::Json, _) => println!("{}", serde_json::json!({ "error": failure.to_json() })),
+    }
+}
+
+/// Reads commands from the user one line at a time until they type "exit".
+fn interactive() {
+    println!("MathRust");
+    println!("Type a command such as 'hcf 12 18 30' or 'fraction 2 1/3 + 3/4'.");
+    println!("Type 'help' to list the commands, or 'exit' to quit.");
+
+    loop {
+        print!("> ");
+        io::stdout().flush().expect("Failed to flush stdout");
+
+        let mut input = String::new();
+        // Reading zero bytes means the input has ended, e.g. at the end of a piped file.
+        if io::stdin().read_line(&mut input).expect("Failed to read line") == 0 {
+            break;
+        }
+
+        let clean_input = input.trim();
+        match clean_input {
+            "" => continue,
+            "exit" | "quit" => break,
+            "help" => {
+                let mut command = Cli::command().no_binary_name(true);
+                println!("{}", command.render_help());
+                continue;
+            }
+            _ => {}
+        }
+
+        // Parse the line exactly as if it had been typed after `mathrust` on the command line.
+        let cli = match Cli::try_parse_from(std::iter::once("mathrust".to_string()).chain(split_arguments(clean_input))) {
+            Ok(cli) => cli,
+            Err(e) => {
+                println!("{}", e.render());
+                continue;
+            }
+        };
+
+        execute(&cli);
+    }
+}
+
+/// Splits a line into arguments at whitespace, keeping anything in double quotes together, so
+/// that `mixed "2 1/3"` gives two arguments as it would in a shell.
+fn split_arguments(line: &str) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_argument = false;
+
+    for c in line.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_argument = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_argument {
+                    arguments.push(std::mem::take(&mut current));
+                    has_argument = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_argument = true;
+            }
+        }
+    }
+    if has_argument {
+        arguments.push(current);
+    }
+    arguments
+}
+
+/// Works out the answer to a single command.
+fn run(command: &Command) -> Result<CalculatorResult, Failure> {
+    let result = match command {
+        Command::Bodmas { exact, expression } => {
+            let expression = expression.joined();
+            CalculatorResult::Bodmas(BodmasResult::calculate(&expression, *exact).map_err(reading(&expression))?)
+        }
+        Command::DecimalPlaces { number, places, mode } => {
+            CalculatorResult::DecimalPlaces(DecimalPlacesResult::calculate(number.clone(), *places, *mode)?)
+        }
+        Command::Estimate { expression, accuracy, mode } => {
+            let expression = expression.joined();
+            let accuracy: Accuracy = accuracy.parse().map_err(reading(accuracy))?;
+            CalculatorResult::Estimation(
+                EstimationResult::calculate(&expression, accuracy, *mode).map_err(reading(&expression))?,
+            )
+        }
+        Command::Sqrt { number, places } => {
+            CalculatorResult::SquareRootEstimate(SquareRootEstimateResult::calculate(*number, *places)?)
+        }
+        Command::Hcf { numbers } => {
+            let numbers = numbers.join(" ");
+            let numbers = parse_whole_numbers(&numbers).map_err(reading(&numbers))?;
+            CalculatorResult::Hcf(HcfResult::calculate(&numbers)?)
+        }
+        Command::Lcm { numbers } => {
+            let numbers = numbers.join(" ");
+            let numbers = parse_whole_numbers(&numbers).map_err(reading(&numbers))?;
+            CalculatorResult::Lcm(LcmResult::calculate(&numbers)?)
+        }
+        Command::Multiples { numbers, count } => {
+            CalculatorResult::Multiples(MultiplesResult::calculate(numbers, *count)?)
+        }
+        Command::Primes { numbers } => CalculatorResult::Primes(PrimesResult::calculate(numbers)),
+        Command::Sieve { range } => {
+            let range = range.join(" ");
+            let (start, end) = parse_prime_range(&range).map_err(reading(&range))?;
+            CalculatorResult::PrimeRange(PrimeRange::sieve(start, end).map_err(reading(&range))?)
+        }
+        Command::Factors { number, .. } => CalculatorResult::PrimeFactors(PrimeFactorsResult::calculate(*number)?),
+        Command::Round { number, place, mode } => {
+            CalculatorResult::Rounding(RoundingResult::calculate(number.clone(), place.clone(), *mode)?)
+        }
+        Command::Sf { number, figures, mode } => {
+            CalculatorResult::SignificantFigures(SignificantFiguresResult::calculate(number.clone(), *figures, *mode)?)
+        }
+        Command::Figures { number } => {
+            CalculatorResult::FigureCount(FigureCount::count(number).map_err(reading(number))?)
+        }
+        Command::Bounds { number, accuracy, truncated } => {
+            let accuracy = accuracy.join(" ");
+            let degree_of_accuracy: Accuracy = accuracy.parse().map_err(reading(&accuracy))?;
+            let approximation = if *truncated { Approximation::Truncated } else { Approximation::Rounded };
+            CalculatorResult::Bounds(BoundsResult::calculate(number.clone(), degree_of_accuracy, approximation)?)
+        }
+        Command::CalcBounds { expression, quantities } => {
+            let quantities = quantities
+                .iter()
+                .map(|quantity| quantity.parse::<Quantity>().map_err(reading(quantity)))
+                .collect::<Result<Vec<Quantity>, Failure>>()?;
+            CalculatorResult::CalculationBounds(
+                BoundsCalculation::calculate(expression, quantities).map_err(reading(expression))?,
+            )
+        }
+        Command::Equivalent { equation } => {
+            // Read "a/b = c/d", where the unknown is written as a letter such as 'x' or as '?'.
+            let equation = equation.join(" ");
+            let [a, b, c, d] = parse_equivalent(&equation).map_err(reading(&equation))?;
+            CalculatorResult::EquivalentFraction(
+                EquivalentFractionResult::calculate(a, b, c, d).map_err(reading(&equation))?,
+            )
+        }
+        Command::Simplify { fraction } => {
+            let (top, bottom) = fraction.split_once('/').ok_or(Failure::Usage(
+                "enter a fraction of two whole numbers, e.g. 24/30".to_string(),
+            ))?;
+            let numerator: i64 = parse_number(top).map_err(reading(fraction))?;
+            let denominator: i64 = parse_number(bottom)
+                .map_err(|e| e.shifted(Span::of(bottom, fraction).start))
+                .map_err(reading(fraction))?;
+            if denominator == 0 {
+                let position = Some(Span::of(bottom.trim(), fraction));
+                return Err(Failure::Math { error: MathError::ZeroDenominator { position }, input: fraction.clone() });
+            }
+            CalculatorResult::Simplify(SimplifyResult::calculate(numerator, denominator)?)
+        }
+        Command::Mixed { number } => {
+            // A mixed number is written with a space, so convert it to an improper fraction,
+            // and convert anything else to a mixed number.
+            let number = number.joined();
+            let value: Fraction = number.parse().map_err(reading(&number))?;
+            let from_mixed = number.trim().contains(char::is_whitespace);
+            CalculatorResult::MixedNumber(MixedNumberResult::calculate(value, from_mixed))
+        }
+        Command::Order { values, descending } => {
+            // Values may be separated by spaces, commas or both.
+            let joined = values.join(", ");
+            let inputs = parse_fraction_list(&joined).map_err(reading(&joined))?;
+            let order = if *descending { SortOrder::Descending } else { SortOrder::Ascending };
+            CalculatorResult::Ordering(OrderingResult::calculate(&inputs, order))
+        }
+        Command::Fraction { calculation } => {
+            let calculation = calculation.joined();
+            CalculatorResult::FractionArithmetic(calculation.parse().map_err(reading(&calculation))?)
+        }
+        Command::StandardForm { expression } => {
+            // A single number is converted; two numbers with an operator between them are
+            // combined.
+            let expression = expression.joined();
+            match parse_standard_form_calculation(&expression) {
+                Ok((left, operation, right)) => CalculatorResult::StandardFormArithmetic(
+                    StandardFormCalculation::new(left, operation, right).map_err(reading(&expression))?,
+                ),
+                Err(MathError::MissingOperator) => {
+                    let number = expression.parse().map_err(reading(&expression))?;
+                    CalculatorResult::StandardForm(StandardFormConversion::new(number))
+                }
+                Err(e) => return Err(reading(&expression)(e)),
+            }
+        }
+        Command::Batch { .. } => {
+            return Err(Failure::Usage("a batch file cannot run another batch file".to_string()));
+        }
+    };
+    Ok(result)
+}
+
+/// One solved line of a batch file.
+struct BatchAnswer {
+    line: usize,
+    problem: String,
+    calculator: Option<String>,
+    result: Result<CalculatorResult, Failure>,
+}
+
+/// Solves every problem in a batch file and writes the answers as CSV or JSON.
+fn run_batch(file: &Path, output: Option<&Path>, format: Format) -> Result<(), String> {
+    let contents = fs::read_to_string(file).map_err(|e| format!("could not read {}: {}", file.display(), e))?;
+
+    let answers: Vec<BatchAnswer> = contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(i, line)| {
+            let (calculator, result) = match batch_command(line) {
+                Ok((calculator, command)) => (Some(calculator), run(&command)),
+                Err((calculator, e)) => (calculator, Err(Failure::Usage(e))),
+            };
+            BatchAnswer { line: i + 1, problem: line.trim().to_string(), calculator, result }
+        })
+        .collect();
+
+    let text = match format {
+        Format::Text => batch_to_csv(&answers),
+        Format::Json => batch_to_json(&answers),
+    };
+    match output {
+        Some(path) => fs::write(path, text).map_err(|e| format!("could not write {}: {}", path.display(), e)),
+        None => {
+            print!("{}", text);
+            Ok(())
+        }
+    }
+}
+
+/// Turns a batch line such as `sf: 52691, 1` into the command it stands for, along with the
+/// calculator's full name. The name is also returned with the error when it is known.
+fn batch_command(line: &str) -> Result<(String, Command), (Option<String>, String)> {
+    let (tag, problem) = line
+        .split_once(':')
+        .ok_or((None, "expected a calculator name and a colon, e.g. 'sf: 52691, 1'".to_string()))?;
+    let name = Cli::command()
+        .find_subcommand(tag.trim())
+        .map(|command| command.get_name().to_string())
+        .ok_or((None, format!("'{}' is not a calculator", tag.trim())))?;
+
+    // Expressions, equations and lists of fractions are read exactly as written; anything else
+    // is a list of numbers that may be separated by commas or spaces.
+    let arguments = match name.as_str() {
+        "bodmas" | "estimate" | "equivalent" | "simplify" | "mixed" | "order" | "fraction" | "sform" => {
+            vec![problem.trim().to_string()]
+        }
+        // The calculation and each of its values are separated by semicolons, because the
+        // accuracy of a value may itself contain a comma.
+        "calc-bounds" => problem.split(';').map(|part| part.trim().to_string()).collect(),
+        _ => split_arguments(&problem.replace(',', " ")),
+    };
+
+    let cli = Cli::try_parse_from(["mathrust".to_string(), name.clone()].into_iter().chain(arguments))
+        .map_err(|e| (Some(name.clone()), clap_error_message(&e)))?;
+    let command = cli.command.expect("a subcommand was given");
+    Ok((name, command))
+}
+
+/// The first line of a clap error, without its "error: " prefix.
+fn clap_error_message(error: &clap::Error) -> String {
+    let rendered = error.render().to_string();
+    let first_line = rendered.lines().next().unwrap_or_default();
+    first_line.strip_prefix("error: ").unwrap_or(first_line).to_string()
+}
+
+/// Writes batch answers as CSV with the columns `line,problem,calculator,answer,error`.
+fn batch_to_csv(answers: &[BatchAnswer]) -> String {
+    let mut csv = String::from("line,problem,calculator,answer,error\n");
+    for answer in answers {
+        let (result, error) = match &answer.result {
+            Ok(result) => (result.answer(), String::new()),
+            Err(e) => (String::new(), e.message()),
+        };
+        let fields = [
+            answer.line.to_string(),
+            answer.problem.clone(),
+            answer.calculator.clone().unwrap_or_default(),
+            result,
+            error,
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quotes a CSV field if it contains a comma, quote or line break, doubling any quotes.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Writes batch answers as a JSON array with one object per problem.
+fn batch_to_json(answers: &[BatchAnswer]) -> String {
+    let rows: Vec<serde_json::Value> = answers
+        .iter()
+        .map(|answer| {
+            let (result, error) = match &answer.result {
+                Ok(result) => match result_to_json(result) {
+                    Ok(json) => (json, serde_json::Value::Null),
+                    Err(e) => (serde_json::Value::Null, e.to_json()),
+                },
+                Err(e) => (serde_json::Value::Null, e.to_json()),
+            };
+            serde_json::json!({
+                "line": answer.line,
+                "problem": answer.problem,
+                "calculator": answer.calculator,
+                "result": result,
+                "error": error,
+            })
+        })
+        .collect();
+    let mut json = serde_json::to_string_pretty(&rows).expect("answers always serialise to JSON");
+    json.push('\n');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads a command line, without `mathrust` at the start.
+    fn parse(arguments: &[&str]) -> Cli {
+        Cli::try_parse_from(std::iter::once(&"mathrust").chain(arguments)).unwrap()
+    }
+
+    /// The answer to a command line, without `mathrust` at the start.
+    fn answer(arguments: &[&str]) -> String {
+        let command = parse(arguments).command.expect("a command was given");
+        run(&command).unwrap().answer()
+    }
+
+    #[test]
+    fn reads_values_that_start_with_a_minus_sign() {
+        assert_eq!(answer(&["simplify", "-24/-30"]), "4/5");
+        assert!(parse(&["simplify", "-24/-30", "--working"]).working);
+        assert_eq!(answer(&["order", "-3/4", "1/2"]), "-3/4 < 1/2");
+        assert_eq!(answer(&["order", "-d", "-3/4", "-1/2", "0.2"]), "0.2 > -1/2 > -3/4");
+        assert_eq!(answer(&["bodmas", "-3^2", "+", "-4"]), "-13");
+        assert!(parse(&["bodmas", "-3^2", "-w"]).working);
+    }
+}
@@ -4,6 +4,9 @@
 
 use std::io;
 
+// The conversion itself is done by the shared `mathrust` library.
+use mathrust::fractions::Fraction;
+
 // This is the main function where our program starts.
 fn main() {
     println!("Mixed Number to Improper Fraction Converter");
@@ -34,8 +37,8 @@ fn main() {
             continue;
         }
 
-        // Parse the whole number.
-        let whole_number: Result<u32, _> = parts[0].parse();
+        // Parse the whole number. A negative whole number makes the whole mixed number negative.
+        let whole_number: Result<i64, _> = parts[0].parse();
 
         // Split the fraction part into numerator and denominator.
         let fraction_parts: Vec<&str> = parts[1].split('/').collect();
@@ -50,13 +53,19 @@ fn main() {
 
         // Check if all parts are valid numbers.
         if let (Ok(whole), Ok(num), Ok(den)) = (whole_number, numerator, denominator) {
-            if den == 0 {
+            // The shared `Fraction` type does the conversion exactly, and rejects a zero denominator.
+            let Some(improper) = Fraction::from_mixed(whole, num as i64, den as i64) else {
                 println!("Error: Denominator cannot be zero.");
                 continue;
-            }
+            };
 
             println!("\nConverting mixed number {}({}/{}) to an improper fraction:", whole, num, den);
 
+            // Work with the size of the whole number, and put the sign back at the end.
+            let sign = if whole < 0 { "-" } else { "" };
+            let whole = whole.unsigned_abs();
+            let (num, den) = (num as u64, den as u64);
+
             // Step 1: Convert the whole number into a fraction with the same denominator.
             // To do this, multiply the whole number by the denominator.
             let equivalent_numerator_from_whole = whole * den;
@@ -70,7 +79,10 @@ fn main() {
             println!("2. Add the numerator of the original fraction ({}) to the new numerator ({})", num, equivalent_numerator_from_whole);
             println!("   {} + {} = {}", equivalent_numerator_from_whole, num, improper_numerator);
 
-            println!("The improper fraction is: {}/{}", improper_numerator, den);
+            println!("The improper fraction is: {}{}/{}", sign, improper_numerator, den);
+            if improper.denominator() as u64 != den {
+                println!("In its lowest terms, this is: {}", improper);
+            }
         } else {
             println!("Invalid input. Please enter valid numbers for the whole number, numerator, and denominator.");
         }
@@ -8,6 +8,8 @@
 
 use std::io::{self, Write};
 
+use mathrust::number::get_multiples;
+
 fn main() {
     println!("=== GCSE Mathematics: Multiples Calculator ===");
     println!("Section 1.2 - Multiples and Factors\n");
@@ -195,17 +197,18 @@ fn option_three_range_of_numbers() {
 fn calculate_and_display_multiples(number: u32, count: usize) {
     println!("  Calculation method: {} × 1, {} × 2, {} × 3, ...", number, number, number);
     
-    let mut multiples = Vec::new();
-    
     // Calculate the multiples
-    for i in 1..=count {
-        let multiple = number * (i as u32);
-        multiples.push(multiple);
-        
-        // Show the calculation for the first few multiples to be educational
-        if i <= 3 {
-            println!("  {} × {} = {}", number, i, multiple);
+    let multiples = match get_multiples(number, count) {
+        Ok(multiples) => multiples,
+        Err(e) => {
+            println!("  Error: {}", e);
+            return;
         }
+    };
+    
+    // Show the calculation for the first few multiples to be educational
+    for (i, multiple) in multiples.iter().enumerate().take(3) {
+        println!("  {} × {} = {}", number, i + 1, multiple);
     }
     
     // Display all multiples in a formatted way
@@ -224,7 +227,7 @@ fn calculate_and_display_multiples(number: u32, count: usize) {
 }
 
 // Function to provide educational insights about multiplication patterns
-fn print_educational_notes(number: u32, multiples: &[u32]) {
+fn print_educational_notes(number: u32, multiples: &[u64]) {
     println!("  📚 Educational Notes:");
     
     match number {
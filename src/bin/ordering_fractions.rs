@@ -1,35 +1,20 @@
-// This program helps you find a common denominator for two or more fractions.
-// Finding a common denominator is the first step to comparing or adding/subtracting fractions.
+// This program puts fractions in order, smallest first or largest first.
+// Fractions, mixed numbers, decimals and percentages can be mixed together, for example
+// 3/8, 0.4, 35%, 1 1/4. To compare them, each one is written as a fraction and then
+// rewritten over a common denominator, so only the numerators need comparing.
 
 use std::io;
 
-// Helper function to calculate the greatest common divisor (GCD) of two numbers.
-// This is needed by the LCM function.
-fn hcf(a: u32, b: u32) -> u32 {
-    let mut temp_a = a;
-    let mut temp_b = b;
-    while temp_b != 0 {
-        let t = temp_b;
-        temp_b = temp_a % temp_b;
-        temp_a = t;
-    }
-    temp_a
-}
-
-// Helper function to calculate the least common multiple (LCM) of two numbers.
-// The LCM of the denominators will be our common denominator.
-fn lcm(a: u32, b: u32) -> u32 {
-    if a == 0 || b == 0 {
-        0
-    } else {
-        (a / hcf(a, b)) * b
-    }
-}
+// The common denominator is the Least Common Multiple (LCM) of the denominators,
+// which the shared `mathrust` library calculates for us along with the ordering itself.
+use mathrust::fractions::{
+    common_denominator, order_fractions, ordering_statement, parse_fraction_list, Fraction, SortOrder,
+};
 
 // This is the main function where our program starts.
 fn main() {
-    println!("Ordering Fractions by Common Denominator");
-    println!("Enter fractions separated by commas (e.g., 5/6, 3/8)");
+    println!("Ordering Fractions");
+    println!("Enter fractions, decimals or percentages separated by commas (e.g., 3/8, 0.4, 35%, 1 1/4)");
 
     // This loop allows the user to process multiple sets of fractions.
     loop {
@@ -44,56 +29,73 @@ fn main() {
             break;
         }
 
-        // Parse the input string into individual fractions.
-        let fractions_str: Vec<&str> = clean_input.split(',').collect();
-        let mut fractions: Vec<(u32, u32)> = Vec::new();
+        // Parse the input string into individual fractions, keeping each one as it was typed.
+        let fractions: Vec<(&str, Fraction)> = match parse_fraction_list(clean_input) {
+            Ok(fractions) => fractions,
+            Err(e) => {
+                println!("Error: {}", e.report(clean_input));
+                println!("Please enter valid fractions.");
+                continue;
+            }
+        };
 
-        for f_str in fractions_str {
-            let parts: Vec<&str> = f_str.trim().split('/').collect();
-            if parts.len() == 2 {
-                if let (Ok(num), Ok(den)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>()) {
-                    if den == 0 {
-                        println!("Error: Denominator cannot be zero for fraction {}.", f_str);
-                        fractions.clear(); // Clear to avoid processing invalid data
-                        break;
-                    }
-                    fractions.push((num, den));
-                } else {
-                    println!("Error: Invalid number in fraction {}.", f_str);
-                    fractions.clear();
-                    break;
+        // Ask which way to order them. Pressing Enter gives ascending order.
+        println!("Ascending or descending order? (press Enter for ascending)");
+        let mut order_input = String::new();
+        io::stdin()
+            .read_line(&mut order_input)
+            .expect("Failed to read line");
+        let order = if order_input.trim().is_empty() {
+            SortOrder::Ascending
+        } else {
+            match SortOrder::from_name(&order_input) {
+                Some(order) => order,
+                None => {
+                    println!("Error: Please enter 'ascending' or 'descending'.");
+                    continue;
                 }
+            }
+        };
+
+        let inputs: Vec<&str> = fractions.iter().map(|(f_str, _)| *f_str).collect();
+        println!("\nPutting in {} order: {}", order.to_string().to_lowercase(), inputs.join(", "));
+
+        // Step 1: Write any decimals, percentages and mixed numbers as fractions.
+        println!("1. Writing each number as a fraction:");
+        for (f_str, fraction) in &fractions {
+            if *f_str == fraction.to_string() {
+                println!("   {}", f_str);
             } else {
-                println!("Error: Invalid fraction format {}. Expected numerator/denominator.", f_str);
-                fractions.clear();
-                break;
+                println!("   {} = {}", f_str, fraction);
             }
         }
 
-        if fractions.is_empty() {
-            println!("Please enter valid fractions.");
+        // Step 2: Find the Least Common Multiple (LCM) of all denominators.
+        // This LCM will be our common denominator.
+        let values: Vec<Fraction> = fractions.iter().map(|(_, fraction)| *fraction).collect();
+        let Some((common_denominator, numerators)) = common_denominator(&values) else {
+            println!("Error: The common denominator is too large to calculate.");
             continue;
-        }
+        };
+        println!("2. The Least Common Multiple (LCM) of the denominators is: {}", common_denominator);
 
-        println!("\nFinding a common denominator for: {:?}", fractions);
-
-        // Step 1: Find the Least Common Multiple (LCM) of all denominators.
-        // This LCM will be our common denominator.
-        let mut common_denominator = fractions[0].1; // Start with the first denominator
-        for i in 1..fractions.len() {
-            common_denominator = lcm(common_denominator, fractions[i].1);
+        // Step 3: Rewrite each fraction with the common denominator.
+        // Each numerator and denominator is multiplied by (common denominator / original denominator).
+        println!("3. Rewriting each fraction with the common denominator:");
+        for (f_str, new_numerator) in inputs.iter().zip(&numerators) {
+            println!("   {} becomes {}/{}", f_str, new_numerator, common_denominator);
         }
-        println!("1. The Least Common Multiple (LCM) of the denominators is: {}", common_denominator);
 
-        // Step 2: Rewrite each fraction with the common denominator.
-        println!("2. Rewriting each fraction with the common denominator:");
-        for (num, den) in fractions {
-            // How many times do we need to multiply the original denominator to get the common denominator?
-            let multiplier = common_denominator / den;
-            // Multiply both the numerator and denominator by this multiplier.
-            let new_numerator = num * multiplier;
-            println!("   {}/{} becomes {}/{}", num, den, new_numerator, common_denominator);
-        }
+        // Step 4: Compare the numerators. Equal numerators mean the fractions are equivalent.
+        let positions = order_fractions(&values, order);
+        let ordered_numerators: Vec<String> = positions.iter().map(|&i| numerators[i].to_string()).collect();
+        println!("4. Ordering the numerators: {}", ordered_numerators.join(", "));
+
+        // Give the final order using each number as it was typed.
+        let ordered: Vec<(&str, Fraction)> = positions.iter().map(|&i| fractions[i]).collect();
+        let ordered_inputs: Vec<&str> = ordered.iter().map(|(f_str, _)| *f_str).collect();
+        println!("\nIn {} order: {}", order.to_string().to_lowercase(), ordered_inputs.join(", "));
+        println!("{}", ordering_statement(&ordered, order));
 
         println!("\nEnter another set of fractions or type 'exit' to quit.");
     }
@@ -1,39 +1,42 @@
 use std::io;
 
-// This function checks if a given number is prime.
-fn is_prime(number: u32) -> bool { // Defines a function named 'is_prime' that takes a 'u32' and returns a 'bool' [1-4]
-    // Numbers less than or equal to 1 are not prime.
-    if number <= 1 { // Uses an 'if' expression for conditional branching [5, 6]
-        return false; // Returns 'false' if the condition is met [4, 7]
-    }
+// Primality is tested, and primes are listed with a sieve, by the shared `mathrust` library.
+// Numbers up to 2^128 - 1 are tested with the Miller-Rabin test rather than by trying every
+// divisor, so even very large numbers are checked almost instantly.
+// The Sieve of Eratosthenes crosses out the multiples of each prime in turn; whatever is left
+// is prime. Wide ranges are sieved a segment at a time, so they can reach 10^10.
+use mathrust::number::parse_numbers;
+use mathrust::primes::{is_prime, parse_prime_range, PrimeRange};
 
-    // Check for divisibility from 2 up to half of the number.
-    // The 'for' loop iterates over a range of numbers [8-10]
-    for i in 2..(number / 2 + 1) { // Uses integer division and range syntax [11-13]
-        if number % i == 0 { // Uses the remainder operator '%' to check for divisibility [11, 13]
-            return false; // If divisible, it's not prime, so return 'false' [4, 7]
-        }
-    }
+fn main() {
+    println!("Choose an option:");
+    println!("1. Check whether numbers are prime");
+    println!("2. List the primes up to N, or from A to B");
+
+    let mut choice = String::new();
+    io::stdin().read_line(&mut choice).expect("Failed to read line");
 
-    // If no divisors were found, the number is prime.
-    true // This is an expression that evaluates to 'true' and is implicitly returned [4, 7, 14, 15]
+    match choice.trim() {
+        "2" => list_primes(),
+        _ => check_numbers(),
+    }
 }
 
-fn main() {
+/// Checks each number in a list one at a time.
+fn check_numbers() {
     println!("Enter a set of numbers separated by spaces:");
 
     let mut input = String::new();
     io::stdin().read_line(&mut input).expect("Failed to read line");
 
-    let numbers: Vec<u32> = input
-        .split_whitespace()
-        .filter_map(|s| s.parse().ok())
-        .collect();
-
-    if numbers.is_empty() {
-        println!("No valid numbers were entered.");
-        return;
-    }
+    let input = input.trim();
+    let numbers: Vec<u128> = match parse_numbers(input) {
+        Ok(numbers) => numbers,
+        Err(e) => {
+            println!("Error: {}", e.report(input));
+            return;
+        }
+    };
 
     println!("\nChecking for prime numbers in the list:");
 
@@ -44,4 +47,34 @@ fn main() {
             println!("{} is not a prime number.", num);
         }
     }
-}
\ No newline at end of file
+}
+
+/// Lists the primes in a range with the sieve, along with how many there are, the twin primes
+/// and the gaps between them.
+fn list_primes() {
+    println!("Enter N to list the primes up to N, or A and B separated by a space (e.g., 100 or 1000 2000):");
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).expect("Failed to read line");
+
+    let input = input.trim();
+    let range = match parse_prime_range(input).and_then(|(start, end)| PrimeRange::sieve(start, end)) {
+        Ok(range) => range,
+        Err(e) => {
+            println!("Error: {}", e.report(input));
+            return;
+        }
+    };
+
+    // Very wide ranges have too many primes to print, so only the first ones are listed.
+    let primes: Vec<String> = range.primes.iter().map(u64::to_string).collect();
+    println!("\n{}", primes.join(", "));
+    if !range.lists_every_prime() {
+        println!("(Only the first {} primes are listed.)", range.primes.len());
+    }
+
+    println!();
+    for line in range.statistics() {
+        println!("{}", line);
+    }
+}
@@ -1,16 +1,11 @@
 // Import the necessary libraries from the standard library.
 use std::io; // Used for handling user input.
 
-// This function rounds a floating-point number `n` to the specified `place`.
-// The `place` determines the position to round to (e.g., 1.0 for whole number, 10.0 for ten, etc.).
-fn round_to_place(n: f64, place: f64) -> f64 {
-    // The rounding logic is based on the formula: round(n / place) * place.
-    // For example, to round 5468.9 to the nearest ten (place = 10.0):
-    // 1. Divide by the place: 5468.9 / 10.0 = 546.89
-    // 2. Round to the nearest whole number: 546.89.round() = 547.0
-    // 3. Multiply by the place: 547.0 * 10.0 = 5470.0
-    (n / place).round() * place
-}
+// Rounding to a place value is done by the shared `mathrust` library, on the exact digits typed.
+// For example, to round 5468.9 to the nearest ten (place = 10):
+// 5468.9 is 546.89 tens, which rounds to 547 tens, or 5470
+use mathrust::decimal::{Decimal, RoundingMode};
+use mathrust::rounding::round_to_place;
 
 // The main function, where the program execution begins.
 fn main() {
@@ -22,8 +17,8 @@ fn main() {
     // Read the line of input from the user.
     io::stdin().read_line(&mut number_input).expect("Failed to read line");
 
-    // Parse the user's input into a 64-bit floating-point number.
-    let number: f64 = match number_input.trim().parse() {
+    // Parse the user's input into an exact decimal, keeping every digit as typed.
+    let number: Decimal = match number_input.trim().parse() {
         // If parsing is successful, assign the number to the `number` variable.
         Ok(num) => num,
         // If parsing fails, print an error message and exit the program.
@@ -56,12 +51,12 @@ fn main() {
         }
     };
 
-    // Determine the rounding place based on the user's choice.
+    // Determine the rounding place, as a power of ten, based on the user's choice.
     let place = match choice {
-        1 => 1.0,      // Nearest whole number
-        2 => 10.0,     // Nearest ten
-        3 => 100.0,    // Nearest hundred
-        4 => 1000.0,   // Nearest thousand
+        1 => Decimal::power_of_ten(0), // Nearest whole number
+        2 => Decimal::power_of_ten(1), // Nearest ten
+        3 => Decimal::power_of_ten(2), // Nearest hundred
+        4 => Decimal::power_of_ten(3), // Nearest thousand
         // If the choice is invalid, print an error message and exit.
         _ => {
             println!("Invalid choice.");
@@ -70,7 +65,7 @@ fn main() {
     };
 
     // Calculate the rounded number using the `round_to_place` function.
-    let rounded_number = round_to_place(number, place);
+    let rounded_number = round_to_place(&number, &place, RoundingMode::HalfUp);
 
     // Print the result with an approximation sign.
     println!("{} ≈ {}", number, rounded_number);
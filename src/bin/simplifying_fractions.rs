@@ -4,6 +4,13 @@
 
 use std::io;
 
+// The Highest Common Factor (HCF) is calculated by the shared `mathrust` library.
+use mathrust::number::hcf;
+
+// The simplified fraction is checked against the shared `Fraction` type, which always stores
+// fractions in their lowest terms.
+use mathrust::fractions::Fraction;
+
 // This is the main function where our program starts.
 fn main() {
     println!("Fraction Simplifier");
@@ -64,7 +71,9 @@ fn main() {
                 // So, the fraction is already in its lowest terms.
                 if common_factor == 1 {
                     println!("{} and {} have no common factors other than 1.", num, den);
-                    println!("The fraction {}/{} in its lowest terms is: {}/{}", original_num, original_den, num, den);
+                    if let Some(lowest_terms) = Fraction::new(original_num as i64, original_den as i64) {
+                        println!("The fraction {}/{} in its lowest terms is: {}", original_num, original_den, lowest_terms);
+                    }
                     break; // Exit the loop
                 } else {
                     // If there is a common factor greater than 1, divide both numerator and denominator by it.
@@ -80,16 +89,3 @@ fn main() {
         println!("\nEnter another fraction or type 'exit' to quit.");
     }
 }
-
-// Helper function to calculate the Highest Common Factor (HCF) or Greatest Common Divisor (GCD).
-// This uses the Euclidean algorithm.
-fn hcf(a: u32, b: u32) -> u32 {
-    let mut temp_a = a;
-    let mut temp_b = b;
-    while temp_b != 0 {
-        let t = temp_b;
-        temp_b = temp_a % temp_b;
-        temp_a = t;
-    }
-    temp_a
-}
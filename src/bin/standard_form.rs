@@ -0,0 +1,52 @@
+// Import the necessary libraries from the standard library.
+use std::io; // Used for handling user input.
+
+// Standard form is done by the shared `mathrust` library. A number in standard form is written
+// as A × 10ⁿ, where A is at least 1 and less than 10, so 320000 is 3.2 × 10⁵ and 0.00032 is
+// 3.2 × 10⁻⁴. The library reads 3.2e5, 3.2×10^5 and 3.2 x 10^-4 alike.
+use mathrust::error::MathError;
+use mathrust::standard_form::{
+    parse_standard_form_calculation, StandardFormCalculation, StandardFormConversion, DIVISION_FIGURES,
+};
+
+// The main function, where the program execution begins.
+fn main() {
+    // Prompt the user to enter a number or a calculation.
+    println!("Enter a number to convert (e.g. 320000 or 3.2 x 10^-4),");
+    println!("or a calculation with spaces around the operator (e.g. 3.2e5 × 4e3):");
+
+    // Create a mutable string to store the user's input.
+    let mut input = String::new();
+    // Read the line of input from the user.
+    io::stdin().read_line(&mut input).expect("Failed to read line");
+    let input = input.trim_end();
+
+    // A single number is converted; two numbers with an operator between them are combined.
+    let worked = match parse_standard_form_calculation(input) {
+        Ok((left, operation, right)) => StandardFormCalculation::new(left, operation, right).map(|calculation| {
+            let answer = if calculation.exact {
+                calculation.result.to_string()
+            } else {
+                format!("{} (to {} significant figures)", calculation.result, DIVISION_FIGURES)
+            };
+            (calculation.steps(), answer)
+        }),
+        Err(MathError::MissingOperator) => input.parse().map(|number| {
+            let conversion = StandardFormConversion::new(number);
+            (conversion.steps(), conversion.answer())
+        }),
+        Err(e) => Err(e),
+    };
+
+    match worked {
+        Ok((steps, answer)) => {
+            // Print each step of the working, then the answer.
+            println!("\nWorking:");
+            for (i, step) in steps.iter().enumerate() {
+                println!("{}. {}", i + 1, step);
+            }
+            println!("\nAnswer: {}", answer);
+        }
+        Err(e) => println!("Error: {}", e.report(input)),
+    }
+}
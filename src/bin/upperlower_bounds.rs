@@ -1,34 +1,25 @@
 // Import the necessary libraries from the standard library.
 use std::io; // Used for handling user input.
 
-// This function calculates the lower and upper bounds of a number `n` that has been rounded
-// to a certain `degree_of_accuracy`.
-fn calculate_bounds(n: f64, degree_of_accuracy: f64) -> (f64, f64) {
-    // The error margin is half of the degree of accuracy.
-    // For example, if a number is rounded to the nearest 10, the degree of accuracy is 10,
-    // and the error margin is 10 / 2 = 5.
-    let error_margin = degree_of_accuracy / 2.0;
-
-    // The lower bound is the number minus the error margin.
-    let lower_bound = n - error_margin;
-    // The upper bound is the number plus the error margin.
-    let upper_bound = n + error_margin;
-
-    (lower_bound, upper_bound)
-}
+// The bounds are calculated by the shared `mathrust` library.
+// The error margin is half of the degree of accuracy, so a number rounded to the nearest 10
+// has an error margin of 10 / 2 = 5 either side. A truncated number has only been cut towards
+// zero, so it can be up to a whole step further from zero, but never nearer.
+use mathrust::bounds::{Accuracy, Approximation, ErrorInterval};
+use mathrust::decimal::Decimal;
 
 // The main function, where the program execution begins.
 fn main() {
-    // Prompt the user to enter the rounded number.
-    println!("Enter the rounded number:");
+    // Prompt the user to enter the rounded or truncated number.
+    println!("Enter the rounded or truncated number:");
 
     // Create a mutable string to store the user's input for the number.
     let mut number_input = String::new();
     // Read the line of input from the user.
     io::stdin().read_line(&mut number_input).expect("Failed to read line");
 
-    // Parse the user's input into a 64-bit floating-point number.
-    let number: f64 = match number_input.trim().parse() {
+    // Parse the user's input into an exact decimal, keeping every digit as typed.
+    let number: Decimal = match number_input.trim().parse() {
         Ok(num) => num,
         Err(_) => {
             println!("Please enter a valid number.");
@@ -37,26 +28,44 @@ fn main() {
     };
 
     // Prompt the user to enter the degree of accuracy.
-    println!("Enter the degree of accuracy (e.g., 1 for nearest whole number, 10 for nearest ten, 0.1 for one decimal place):");
+    println!("Enter the degree of accuracy (e.g., 10 for the nearest ten, 0.1 or 1 d.p. for one decimal place, 2 s.f.):");
 
     // Create a mutable string to store the user's input for the degree of accuracy.
     let mut accuracy_input = String::new();
     // Read the line of input from the user.
     io::stdin().read_line(&mut accuracy_input).expect("Failed to read line");
 
-    // Parse the user's input into a 64-bit floating-point number.
-    let degree_of_accuracy: f64 = match accuracy_input.trim().parse() {
-        Ok(num) => num,
-        Err(_) => {
-            println!("Please enter a valid degree of accuracy.");
+    // Read the degree of accuracy, which may be a step or a number of decimal places or
+    // significant figures.
+    let accuracy: Accuracy = match accuracy_input.parse() {
+        Ok(accuracy) => accuracy,
+        Err(e) => {
+            println!("Error: {}", e.report(accuracy_input.trim_end()));
             return;
         }
     };
 
-    // Calculate the lower and upper bounds using the `calculate_bounds` function.
-    let (lower_bound, upper_bound) = calculate_bounds(number, degree_of_accuracy);
+    // Ask whether the number was rounded or truncated.
+    println!("Was the number rounded or truncated? (r/t, default r):");
+    let mut approximation_input = String::new();
+    io::stdin().read_line(&mut approximation_input).expect("Failed to read line");
+    let approximation = if approximation_input.trim().to_lowercase().starts_with('t') {
+        Approximation::Truncated
+    } else {
+        Approximation::Rounded
+    };
+
+    // Work out the error interval from the degree of accuracy as a step, e.g. 0.1 for 1 d.p.
+    let interval = match ErrorInterval::new(&number, &accuracy, approximation) {
+        Ok(interval) => interval,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
 
     // Print the final result to the console.
-    println!("The lower bound is: {}", lower_bound);
-    println!("The upper bound is: {}", upper_bound);
+    println!("The lower bound is: {}", interval.lower_bound);
+    println!("The upper bound is: {}", interval.upper_bound);
+    println!("Error interval: {}", interval);
 }
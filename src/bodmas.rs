@@ -0,0 +1,561 @@
+//! Step-by-step BODMAS working.
+//!
+//! An expression is rewritten one operation at a time: first the Brackets, innermost first,
+//! then Orders (powers and roots), then Division and Multiplication from left to right, and
+//! finally Addition and Subtraction from left to right. Each step names the rule it follows.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use num_traits::ToPrimitive;
+use serde::Serialize;
+
+use crate::decimal::{Decimal, RoundingMode};
+use crate::error::{MathError, Span};
+use crate::exact::LARGEST_EXACT_EXPONENT;
+use crate::expressions::evaluate_expr;
+use crate::parser::{BinaryOp, Expr, ExprKind};
+use crate::rounding::round_to_significant_figures;
+use crate::standard_form::{StandardForm, DIVISION_FIGURES};
+
+/// The answers that were rounded for the working, before rounding, by the place given to them
+/// by [`give_each_part_a_place`].
+type Carried = HashMap<Span, Decimal>;
+
+/// The rule of BODMAS that a step follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodmasRule {
+    Brackets,
+    Orders,
+    /// A function such as `sin(x)`, worked out once its brackets have been.
+    Function,
+    Division,
+    Multiplication,
+    Addition,
+    Subtraction,
+}
+
+impl BodmasRule {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BodmasRule::Brackets => "Brackets",
+            BodmasRule::Orders => "Orders",
+            BodmasRule::Function => "Function",
+            BodmasRule::Division => "Division",
+            BodmasRule::Multiplication => "Multiplication",
+            BodmasRule::Addition => "Addition",
+            BodmasRule::Subtraction => "Subtraction",
+        }
+    }
+}
+
+impl fmt::Display for BodmasRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// One operation worked out, and the whole expression rewritten with its answer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BodmasStep {
+    pub rule: BodmasRule,
+    /// The operation worked out, e.g. `3 × 4 = 12`. Answers that have been rounded, or that were
+    /// worked out from a rounded value, use `≈`.
+    pub operation: String,
+    /// The expression once the operation has been worked out, e.g. `20 - 12 + 8`.
+    pub expression: String,
+}
+
+impl fmt::Display for BodmasStep {
+    /// Writes the step as a line of working, e.g. `= 20 - 12 + 8   (Multiplication: 3 × 4 = 12)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "= {}   ({}: {})", self.expression, self.rule, self.operation)
+    }
+}
+
+/// An expression worked through in BODMAS order, and the answer the working ends with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BodmasWorking {
+    pub steps: Vec<BodmasStep>,
+    /// The answer as the last step writes it, rounded to [`DIVISION_FIGURES`] significant figures
+    /// if it is not exact.
+    pub answer: Decimal,
+    /// `false` if the answer has been rounded, or was worked out from a rounded value.
+    pub exact: bool,
+}
+
+impl BodmasWorking {
+    /// The answer as [`write_answer`] writes it.
+    pub fn written_answer(&self) -> String {
+        write_answer(&self.answer, self.exact)
+    }
+}
+
+/// Writes the answer a working ends with. A rounded answer starts with `≈` and is written as
+/// [`write_rounded`] writes it, so 3^40 ÷ 7 is `≈ 1.736809351 × 10¹⁸`.
+pub fn write_answer(answer: &Decimal, exact: bool) -> String {
+    if exact { answer.to_string() } else { format!("≈ {}", write_rounded(answer)) }
+}
+
+/// Writes a number rounded to [`DIVISION_FIGURES`] significant figures. One with more
+/// whole-number digits than that would end in zeros standing in for digits that are not known,
+/// so it is written in standard form instead.
+fn write_rounded(number: &Decimal) -> String {
+    standard_form_of_rounded(number).map_or_else(|| number.to_string(), |standard_form| standard_form.to_string())
+}
+
+/// A rounded number in standard form, if it has more whole-number digits than the
+/// [`DIVISION_FIGURES`] it was rounded to.
+fn standard_form_of_rounded(number: &Decimal) -> Option<StandardForm> {
+    let position = number.leading_digit_position()?;
+    let figures = i32::try_from(DIVISION_FIGURES).expect("the number of figures is small");
+    (position >= figures).then(|| StandardForm { mantissa: number.clone(), power: 0 }.normalised())
+}
+
+/// Works out an expression one operation at a time, in BODMAS order.
+///
+/// Additions, subtractions, multiplications and whole-number powers are exact, as are
+/// divisions that end; anything else is shown to [`DIVISION_FIGURES`] significant figures. The
+/// later steps use the answer before it was rounded, so 1 ÷ 3 × 3 comes to 1, not 0.9999999999.
+pub fn bodmas_working(expr: &Expr) -> Result<BodmasWorking, MathError> {
+    // Unknown names and impossible operations are reported before any working is given.
+    evaluate_expr(expr)?;
+
+    let mut expr = expr.clone();
+    give_each_part_a_place(&mut expr, &mut 0);
+    let mut carried = Carried::new();
+    let mut steps = Vec::new();
+    while let Some((rule, operation)) = reduce(&mut expr, &mut carried)? {
+        bracket_negatives(&mut expr);
+        steps.push(BodmasStep { rule, operation, expression: written(&expr, &carried) });
+    }
+    let answer = value(&expr, &carried).expect("the working ends with a single value");
+    Ok(BodmasWorking { steps, answer: answer.shown()?.trim(), exact: answer.exact })
+}
+
+/// The steps of [`bodmas_working`], for when the answer is not needed.
+pub fn bodmas_steps(expr: &Expr) -> Result<Vec<BodmasStep>, MathError> {
+    Ok(bodmas_working(expr)?.steps)
+}
+
+/// Gives every part of the expression a span of its own, numbering them from `next`, so a rounded
+/// answer can be carried by where it is. Parts can share a span otherwise: a session writes
+/// every part of a function's body at the place the function was called.
+fn give_each_part_a_place(expr: &mut Expr, next: &mut usize) {
+    expr.span = Span::new(*next, *next);
+    *next += 1;
+    for child in expr.children_mut() {
+        give_each_part_a_place(child, next);
+    }
+}
+
+/// The stages of BODMAS, in the order they are worked through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Brackets,
+    Orders,
+    DivisionMultiplication,
+    AdditionSubtraction,
+}
+
+const STAGES: [Stage; 4] =
+    [Stage::Brackets, Stage::Orders, Stage::DivisionMultiplication, Stage::AdditionSubtraction];
+
+/// A number reached while working, and whether it is exact or has been rounded. A rounded
+/// number is held as the `f64` it was worked out as, and only rounded when it is shown.
+#[derive(Debug, Clone)]
+struct Value {
+    number: Decimal,
+    exact: bool,
+}
+
+impl Value {
+    fn exact(number: Decimal) -> Value {
+        Value { number, exact: true }
+    }
+
+    /// The value of an answer worked out with `f64`. It is exact if it needs no more than
+    /// [`DIVISION_FIGURES`] significant figures.
+    fn approximate(value: f64) -> Result<Value, MathError> {
+        let number = Decimal::from_f64(value).ok_or(MathError::Overflow)?;
+        let rounded = round_to_significant_figures(&number, DIVISION_FIGURES, RoundingMode::HalfUp)?.trim();
+        Ok(if rounded == number { Value::exact(rounded) } else { Value { number, exact: false } })
+    }
+
+    /// The number as it is written in the working, rounded to [`DIVISION_FIGURES`] significant
+    /// figures if it is not exact.
+    fn shown(&self) -> Result<Decimal, MathError> {
+        if self.exact {
+            return Ok(self.number.clone());
+        }
+        Ok(round_to_significant_figures(&self.number, DIVISION_FIGURES, RoundingMode::HalfUp)?.trim())
+    }
+}
+
+/// The value of a part of the expression that needs no more working: a number, possibly
+/// negated or in brackets, or a constant such as `pi`. A number that is a rounded answer takes
+/// the value carried for it.
+fn value(expr: &Expr, carried: &Carried) -> Option<Value> {
+    match &expr.kind {
+        ExprKind::Number(number) => Some(match carried.get(&expr.span) {
+            Some(number) => Value { number: number.clone(), exact: false },
+            None => Value::exact(number.clone()),
+        }),
+        ExprKind::Negate(operand) => {
+            value(operand, carried).map(|value| Value { number: Decimal::zero().sub(&value.number), ..value })
+        }
+        ExprKind::Brackets(inner) => value(inner, carried),
+        ExprKind::Name(_) => evaluate_expr(expr).ok().and_then(|value| Value::approximate(value).ok()),
+        _ => None,
+    }
+}
+
+fn is_value(expr: &Expr) -> bool {
+    value(expr, &Carried::new()).is_some()
+}
+
+/// Works out the next operation in BODMAS order, rewriting `expr` in place, or returns `None`
+/// once `expr` is a single value.
+fn reduce(expr: &mut Expr, carried: &mut Carried) -> Result<Option<(BodmasRule, String)>, MathError> {
+    for stage in STAGES {
+        if let Some(step) = reduce_first(expr, stage, carried)? {
+            return Ok(Some(step));
+        }
+    }
+    Ok(None)
+}
+
+/// Works out the first operation belonging to `stage`, reading from left to right.
+fn reduce_first(
+    expr: &mut Expr,
+    stage: Stage,
+    carried: &mut Carried,
+) -> Result<Option<(BodmasRule, String)>, MathError> {
+    let Some(rule) = rule_for(expr, stage) else {
+        for child in expr.children_mut() {
+            if let Some(step) = reduce_first(child, stage, carried)? {
+                return Ok(Some(step));
+            }
+        }
+        return Ok(None);
+    };
+
+    if rule == BodmasRule::Brackets {
+        // Inside a bracket, BODMAS starts again, so a bracket within it is worked out first.
+        let inner = match &mut expr.kind {
+            ExprKind::Brackets(inner) => &mut **inner,
+            ExprKind::Call { arguments, .. } => {
+                arguments.iter_mut().find(|argument| !is_value(argument)).expect("an argument needs working out")
+            }
+            _ => unreachable!("only brackets and calls have brackets to work out"),
+        };
+        let step = reduce(inner, carried)?.map(|(_, operation)| (BodmasRule::Brackets, operation));
+
+        // A bracket around a single number is no longer needed, unless the number is negative.
+        let finished = match &expr.kind {
+            ExprKind::Brackets(inner) => match &inner.kind {
+                ExprKind::Number(number) if !number.is_negative() => Some((**inner).clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(inner) = finished {
+            *expr = inner;
+        }
+        return Ok(step);
+    }
+
+    let answer = work_out(expr, carried)?;
+    let shown = answer.shown()?;
+    let written_answer = if answer.exact { shown.to_string() } else { write_rounded(&shown) };
+    let sign = if answer.exact { "=" } else { "≈" };
+    let operation = format!("{} {} {}", describe(expr, carried), sign, written_answer);
+    if !answer.exact {
+        carried.insert(expr.span, answer.number);
+    }
+    *expr = Expr { kind: ExprKind::Number(shown), span: expr.span };
+    Ok(Some((rule, operation)))
+}
+
+/// The rule for working out `expr` itself at `stage`, or `None` if it belongs to another
+/// stage or its parts need working out first.
+fn rule_for(expr: &Expr, stage: Stage) -> Option<BodmasRule> {
+    match (&expr.kind, stage) {
+        (ExprKind::Brackets(inner), Stage::Brackets) if !is_value(inner) => Some(BodmasRule::Brackets),
+        (ExprKind::Call { arguments, .. }, Stage::Brackets) if !arguments.iter().all(is_value) => {
+            Some(BodmasRule::Brackets)
+        }
+        (ExprKind::Call { name, arguments }, Stage::Orders) if arguments.iter().all(is_value) => {
+            Some(if matches!(name.as_str(), "sqrt" | "cbrt") { BodmasRule::Orders } else { BodmasRule::Function })
+        }
+        (ExprKind::Index(operand, _) | ExprKind::SquareRoot(operand), Stage::Orders) if is_value(operand) => {
+            Some(BodmasRule::Orders)
+        }
+        (ExprKind::Binary { op, left, right, .. }, _) if is_value(left) && is_value(right) => match (op, stage) {
+            (BinaryOp::Power, Stage::Orders) => Some(BodmasRule::Orders),
+            (BinaryOp::Divide | BinaryOp::Remainder, Stage::DivisionMultiplication) => Some(BodmasRule::Division),
+            (BinaryOp::Multiply, Stage::DivisionMultiplication) => Some(BodmasRule::Multiplication),
+            (BinaryOp::Add, Stage::AdditionSubtraction) => Some(BodmasRule::Addition),
+            (BinaryOp::Subtract, Stage::AdditionSubtraction) => Some(BodmasRule::Subtraction),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Works out an operation whose parts are all values. Exact values give an exact answer where
+/// there is one; anything else is worked out with `f64`, just as the answer to the whole
+/// expression is, so the working and the answer agree.
+fn work_out(expr: &Expr, carried: &Carried) -> Result<Value, MathError> {
+    let values: Vec<Value> = expr.children().into_iter().filter_map(|child| value(child, carried)).collect();
+    if !values.iter().all(|value| value.exact) {
+        return approximately(expr, &values);
+    }
+    let answer = match (&expr.kind, values.as_slice()) {
+        (ExprKind::Binary { op: BinaryOp::Add, .. }, [a, b]) => Some(a.number.add(&b.number)),
+        (ExprKind::Binary { op: BinaryOp::Subtract, .. }, [a, b]) => Some(a.number.sub(&b.number)),
+        (ExprKind::Binary { op: BinaryOp::Multiply, .. }, [a, b]) => Some(a.number.mul(&b.number)),
+        (ExprKind::Binary { op: BinaryOp::Divide, .. }, [a, b]) => {
+            let (quotient, ends) = a.number.div(&b.number, DIVISION_FIGURES).ok_or(MathError::DivisionByZero)?;
+            ends.then_some(quotient)
+        }
+        (ExprKind::Binary { op: BinaryOp::Power, .. }, [base, exponent]) => power(&base.number, &exponent.number)?,
+        (ExprKind::Index(_, exponent), [base]) => power(&base.number, &Decimal::from(i64::from(*exponent)))?,
+        _ => None,
+    };
+    match answer {
+        Some(number) => Ok(Value::exact(number.trim())),
+        None => approximately(expr, &values),
+    }
+}
+
+/// Works out `base` to a whole-number power exactly, by repeated multiplication, or returns
+/// `None` if the exponent is not a whole number or is larger than [`LARGEST_EXACT_EXPONENT`],
+/// or the answer does not end.
+fn power(base: &Decimal, exponent: &Decimal) -> Result<Option<Decimal>, MathError> {
+    let Some(exponent) = exponent.to_i64().filter(|exponent| exponent.unsigned_abs() <= LARGEST_EXACT_EXPONENT) else {
+        return Ok(None);
+    };
+    let mut answer = Decimal::from(1);
+    for _ in 0..exponent.unsigned_abs() {
+        answer = answer.mul(base);
+    }
+    if exponent < 0 {
+        let (reciprocal, ends) = Decimal::from(1).div(&answer, DIVISION_FIGURES).ok_or(MathError::DivisionByZero)?;
+        return Ok(ends.then_some(reciprocal));
+    }
+    Ok(Some(answer))
+}
+
+/// Works out an operation with `f64` from the values of its parts, which are used as they were
+/// before any rounding. The answer is only exact if every part was.
+fn approximately(expr: &Expr, values: &[Value]) -> Result<Value, MathError> {
+    let mut expr = expr.clone();
+    for (part, value) in expr.children_mut().into_iter().zip(values) {
+        *part = Expr { kind: ExprKind::Number(value.number.clone()), span: part.span };
+    }
+    let answer = Value::approximate(evaluate_expr(&expr)?)?;
+    Ok(Value { exact: answer.exact && values.iter().all(|value| value.exact), ..answer })
+}
+
+/// Writes the operation about to be worked out, with its operator written in full even when it
+/// was left out, as in `2 × (3)` for `2(3)`.
+fn describe(expr: &Expr, carried: &Carried) -> String {
+    match &expr.kind {
+        ExprKind::Binary { op: BinaryOp::Power, left, right, .. } => {
+            format!("{}^{}", written(left, carried), written(right, carried))
+        }
+        ExprKind::Binary { op, left, right, .. } => {
+            format!("{} {} {}", written(left, carried), op.symbol(), written(right, carried))
+        }
+        _ => written(expr, carried),
+    }
+}
+
+/// Writes the expression as it stands in the working, with rounded numbers written as
+/// [`write_rounded`] writes them. Inside a larger expression, standard form goes in brackets.
+fn written(expr: &Expr, carried: &Carried) -> String {
+    if let Some(standard_form) = rounded_standard_form(expr, carried) {
+        return standard_form.to_string();
+    }
+    let mut expr = expr.clone();
+    write_in_standard_form(&mut expr, carried);
+    expr.to_string()
+}
+
+/// The part in standard form, if it is a rounded number that [`write_rounded`] would write in
+/// standard form.
+fn rounded_standard_form(expr: &Expr, carried: &Carried) -> Option<StandardForm> {
+    match &expr.kind {
+        ExprKind::Number(number) if carried.contains_key(&expr.span) => standard_form_of_rounded(number),
+        _ => None,
+    }
+}
+
+fn write_in_standard_form(expr: &mut Expr, carried: &Carried) {
+    if let Some(standard_form) = rounded_standard_form(expr, carried) {
+        let product = standard_form_product(standard_form, expr.span);
+        expr.kind = ExprKind::Brackets(Box::new(product));
+        return;
+    }
+    match &mut expr.kind {
+        // A negative number already has brackets around it, which do for both.
+        ExprKind::Brackets(inner) => match rounded_standard_form(inner, carried) {
+            Some(standard_form) => **inner = standard_form_product(standard_form, inner.span),
+            None => write_in_standard_form(inner, carried),
+        },
+        _ => {
+            for child in expr.children_mut() {
+                write_in_standard_form(child, carried);
+            }
+        }
+    }
+}
+
+/// A number in standard form written as a multiplication such as `1.2 × 10²⁰`.
+fn standard_form_product(standard_form: StandardForm, span: Span) -> Expr {
+    let StandardForm { mantissa, power } = standard_form;
+    let power = u32::try_from(power).expect("a number with more whole-number digits has a positive power");
+    let ten = Expr { kind: ExprKind::Number(Decimal::from(10)), span };
+    Expr {
+        kind: ExprKind::Binary {
+            op: BinaryOp::Multiply,
+            left: Box::new(Expr { kind: ExprKind::Number(mantissa), span }),
+            right: Box::new(Expr { kind: ExprKind::Index(Box::new(ten), power), span }),
+            implicit: false,
+        },
+        span,
+    }
+}
+
+/// Puts brackets around negative answers that would otherwise read oddly, such as `5 - -3` or
+/// `-3^2`.
+fn bracket_negatives(expr: &mut Expr) {
+    let operands: Vec<&mut Expr> = match &mut expr.kind {
+        ExprKind::Binary { op: BinaryOp::Power, left, right, .. } => vec![&mut **left, &mut **right],
+        ExprKind::Binary { right, .. } => vec![&mut **right],
+        ExprKind::Negate(operand) | ExprKind::Index(operand, _) | ExprKind::SquareRoot(operand) => {
+            vec![&mut **operand]
+        }
+        _ => Vec::new(),
+    };
+    for operand in operands {
+        if matches!(&operand.kind, ExprKind::Number(number) if number.is_negative()) {
+            let span = operand.span;
+            let number = operand.clone();
+            *operand = Expr { kind: ExprKind::Brackets(Box::new(number)), span };
+        }
+    }
+    for child in expr.children_mut() {
+        bracket_negatives(child);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn working(expression: &str) -> Vec<String> {
+        bodmas_steps(&parse(expression).unwrap()).unwrap().iter().map(BodmasStep::to_string).collect()
+    }
+
+    #[test]
+    fn works_through_bodmas_in_order() {
+        assert_eq!(
+            working("20 - 3 * 4 + 2^3"),
+            vec![
+                "= 20 - 3 × 4 + 8   (Orders: 2^3 = 8)",
+                "= 20 - 12 + 8   (Multiplication: 3 × 4 = 12)",
+                "= 8 + 8   (Subtraction: 20 - 12 = 8)",
+                "= 16   (Addition: 8 + 8 = 16)",
+            ]
+        );
+    }
+
+    #[test]
+    fn works_out_brackets_from_the_inside() {
+        assert_eq!(
+            working("2(3 + (8 - 2) ÷ 4)"),
+            vec![
+                "= 2(3 + 6 ÷ 4)   (Brackets: 8 - 2 = 6)",
+                "= 2(3 + 1.5)   (Brackets: 6 ÷ 4 = 1.5)",
+                "= 2 × 4.5   (Brackets: 3 + 1.5 = 4.5)",
+                "= 9   (Multiplication: 2 × 4.5 = 9)",
+            ]
+        );
+        assert_eq!(
+            working("5 - (1 - 4)²"),
+            vec![
+                "= 5 - (-3)²   (Brackets: 1 - 4 = -3)",
+                "= 5 - 9   (Orders: (-3)² = 9)",
+                "= -4   (Subtraction: 5 - 9 = -4)",
+            ]
+        );
+    }
+
+    #[test]
+    fn rounds_answers_that_do_not_end() {
+        // The answer before rounding is carried forward, so the working ends at the right answer.
+        assert_eq!(
+            working("1 / 3 * 3"),
+            vec![
+                "= 0.3333333333 × 3   (Division: 1 ÷ 3 ≈ 0.3333333333)",
+                "= 1   (Multiplication: 0.3333333333 × 3 ≈ 1)",
+            ]
+        );
+        assert_eq!(working("2 ÷ 3 × 3").last().unwrap(), "= 2   (Multiplication: 0.6666666667 × 3 ≈ 2)");
+        assert_eq!(working("(1 ÷ 3) × 3 - 1").last().unwrap(), "= 0   (Subtraction: 1 - 1 ≈ 0)");
+        assert_eq!(
+            working("√16 + 2^-2 + √2"),
+            vec![
+                "= 4 + 2^-2 + √2   (Orders: √16 = 4)",
+                "= 4 + 0.25 + √2   (Orders: 2^-2 = 0.25)",
+                "= 4 + 0.25 + 1.414213562   (Orders: √2 ≈ 1.414213562)",
+                "= 4.25 + 1.414213562   (Addition: 4 + 0.25 = 4.25)",
+                "= 5.664213562   (Addition: 4.25 + 1.414213562 ≈ 5.664213562)",
+            ]
+        );
+    }
+
+    #[test]
+    fn ends_with_the_answer_the_working_gives() {
+        let answer = |expression: &str| {
+            let working = bodmas_working(&parse(expression).unwrap()).unwrap();
+            (working.answer.to_string(), working.exact)
+        };
+        assert_eq!(answer("0.1 + 0.2"), ("0.3".to_string(), true));
+        assert_eq!(answer("1 / 3 * 3"), ("1".to_string(), false));
+        assert_eq!(answer("sin(pi / 6)"), ("0.5".to_string(), false));
+        assert_eq!(answer("2.50"), ("2.5".to_string(), true));
+        assert_eq!(answer("pi"), ("3.141592654".to_string(), false));
+    }
+
+    #[test]
+    fn writes_long_rounded_numbers_in_standard_form() {
+        let working = bodmas_working(&parse("3^40 / 7 + 1").unwrap()).unwrap();
+        let steps: Vec<String> = working.steps.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            steps,
+            [
+                "= 12157665459056928801 ÷ 7 + 1   (Orders: 3^40 = 12157665459056928801)",
+                "= (1.736809351 × 10¹⁸) + 1   (Division: 12157665459056928801 ÷ 7 ≈ 1.736809351 × 10¹⁸)",
+                "= 1.736809351 × 10¹⁸   (Addition: 1.736809351 × 10¹⁸ + 1 ≈ 1.736809351 × 10¹⁸)",
+            ]
+        );
+        assert_eq!(working.written_answer(), "≈ 1.736809351 × 10¹⁸");
+
+        let working = bodmas_working(&parse("2^1000").unwrap()).unwrap();
+        assert_eq!(working.written_answer(), "≈ 1.071508607 × 10³⁰¹");
+        // Exact answers keep every digit, and rounded ones with no more whole-number digits than
+        // the figures they were rounded to are written out.
+        assert_eq!(bodmas_working(&parse("2^70").unwrap()).unwrap().written_answer(), "1180591620717411303424");
+        assert_eq!(bodmas_working(&parse("1e10 / 3").unwrap()).unwrap().written_answer(), "≈ 3333333333");
+    }
+
+    #[test]
+    fn reports_impossible_steps_before_working() {
+        assert_eq!(bodmas_steps(&parse("2 + 3 / (1 - 1)").unwrap()), Err(MathError::DivisionByZero));
+    }
+}
@@ -0,0 +1,745 @@
+//! Upper and lower bounds, and the error intervals they make.
+//!
+//! A number given to some degree of accuracy stands for every value that would round (or
+//! truncate) to it. The error interval writes that range with inequalities: 6.5 to 1 decimal
+//! place is `6.45 ≤ x < 6.55`, and 6.4 truncated to 1 decimal place is `6.4 ≤ x < 6.5`. All of
+//! the arithmetic is exact, so a bound is never printed as `6.449999999`.
+//!
+//! The bounds of a calculation such as `d / t` are found with [`Interval`] arithmetic, which
+//! records which bound of each value gives each bound of the answer: the upper bound of `d / t`
+//! comes from the upper bound of `d` and the lower bound of `t`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::decimal::Decimal;
+use crate::error::{MathError, Span};
+use crate::fractions::Operation;
+use crate::parser::{parse, BinaryOp, Expr, ExprKind};
+use crate::rounding::{decimal_place, last_figure};
+use crate::standard_form::DIVISION_FIGURES;
+
+/// How accurately a number was given.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Accuracy {
+    /// To the nearest multiple of a step, such as 10 for the nearest ten or 0.1 for one decimal
+    /// place.
+    Nearest(Decimal),
+    /// To a number of decimal places.
+    DecimalPlaces(u32),
+    /// To a number of significant figures.
+    SignificantFigures(u32),
+}
+
+/// The ways of writing decimal places and significant figures that [`Accuracy`] reads, with
+/// spaces and dots removed.
+const DECIMAL_PLACES: [&str; 3] = ["decimalplaces", "decimalplace", "dp"];
+const SIGNIFICANT_FIGURES: [&str; 5] = ["significantfigures", "significantfigure", "sigfigs", "sigfig", "sf"];
+
+impl Accuracy {
+    /// The degree of accuracy as a step for the number `n`, e.g. 0.1 for 1 d.p., or 100 for
+    /// 6500 to 2 s.f.
+    ///
+    /// Fails with [`MathError::Overflow`] for more decimal places or significant figures than
+    /// can be written out.
+    pub fn step(&self, n: &Decimal) -> Result<Decimal, MathError> {
+        match self {
+            Accuracy::Nearest(step) => Ok(step.clone()),
+            Accuracy::DecimalPlaces(places) => decimal_place(*places),
+            Accuracy::SignificantFigures(figures) => last_figure(n.leading_digit_position().unwrap_or(0), *figures),
+        }
+    }
+
+    /// The step for the numbers just nearer zero than `n`. This is the same as
+    /// [`step`](Accuracy::step), except for a power of ten given to significant figures, where
+    /// it is a tenth of it: 1000 to 1 s.f. is to the nearest 1000, but 999 is to the nearest 100.
+    pub fn step_nearer_zero(&self, n: &Decimal) -> Result<Decimal, MathError> {
+        let step = self.step(n)?;
+        match self {
+            Accuracy::SignificantFigures(_) if n.is_power_of_ten() => Ok(step.shifted(-1)),
+            _ => Ok(step),
+        }
+    }
+}
+
+impl fmt::Display for Accuracy {
+    /// Writes the accuracy the way it is said: "to the nearest 10", "to 1 decimal place" or "to
+    /// 2 significant figures".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |count: u32| if count == 1 { "" } else { "s" };
+        match self {
+            Accuracy::Nearest(step) => write!(f, "to the nearest {}", step),
+            Accuracy::DecimalPlaces(places) => write!(f, "to {} decimal place{}", places, plural(*places)),
+            Accuracy::SignificantFigures(figures) => {
+                write!(f, "to {} significant figure{}", figures, plural(*figures))
+            }
+        }
+    }
+}
+
+impl FromStr for Accuracy {
+    type Err = MathError;
+
+    /// Reads a degree of accuracy written as a step, such as `10` or `0.1`, or as a number of
+    /// decimal places or significant figures, such as `1 d.p.`, `2sf` or `3 sig figs`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let s = input.trim();
+        if s.is_empty() {
+            return Err(MathError::EmptyInput);
+        }
+        let compact: String = s.chars().filter(|c| !c.is_whitespace() && *c != '.').collect::<String>().to_lowercase();
+        let count = |suffixes: &[&str]| {
+            suffixes.iter().find_map(|suffix| compact.strip_suffix(suffix)).map(|count| {
+                count.parse::<u32>().map_err(|_| MathError::InvalidToken {
+                    token: s.to_string(),
+                    position: Span::of(s, input),
+                })
+            })
+        };
+
+        if let Some(places) = count(&DECIMAL_PLACES) {
+            return Ok(Accuracy::DecimalPlaces(places?));
+        }
+        if let Some(figures) = count(&SIGNIFICANT_FIGURES) {
+            return match figures? {
+                0 => Err(MathError::NotPositive),
+                figures => Ok(Accuracy::SignificantFigures(figures)),
+            };
+        }
+        let step: Decimal = input.parse()?;
+        if !step.is_positive() {
+            return Err(MathError::NotPositive);
+        }
+        Ok(Accuracy::Nearest(step))
+    }
+}
+
+/// How a number was cut down to its degree of accuracy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Approximation {
+    /// Rounded to the nearest value, so the number can be up to half a step out either way.
+    #[default]
+    Rounded,
+    /// Truncated, with the digits after the degree of accuracy cut off, so the number can be up
+    /// to a whole step further from zero.
+    Truncated,
+}
+
+impl Approximation {
+    /// A list of both approximations.
+    pub const ALL: [Approximation; 2] = [Approximation::Rounded, Approximation::Truncated];
+}
+
+impl fmt::Display for Approximation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Approximation::Rounded => write!(f, "Rounded"),
+            Approximation::Truncated => write!(f, "Truncated"),
+        }
+    }
+}
+
+/// The range of values a number could have been before it was rounded or truncated, such as
+/// `6.45 ≤ x < 6.55`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ErrorInterval {
+    pub lower_bound: Decimal,
+    pub upper_bound: Decimal,
+    /// Whether `x` can equal the lower bound (`≤`) or must be greater than it (`<`).
+    pub includes_lower: bool,
+    /// Whether `x` can equal the upper bound (`≤`) or must be less than it (`<`).
+    pub includes_upper: bool,
+}
+
+impl ErrorInterval {
+    /// The error interval of `n`, given to `accuracy` by `approximation`.
+    ///
+    /// Halves round away from zero, so the bound nearer zero is the one that is included: 6.5
+    /// to 1 d.p. is `6.45 ≤ x < 6.55`, but -6.5 is `-6.55 < x ≤ -6.45`. A rounded power of ten
+    /// given to significant figures is only half of a smaller step from its bound nearer zero, so
+    /// 1000 to 1 s.f. is `950 ≤ x < 1500`. A truncated number is only ever cut towards zero, so
+    /// 6.4 is `6.4 ≤ x < 6.5` and -6.4 is `-6.5 < x ≤ -6.4`.
+    ///
+    /// Fails if the accuracy is too fine to write out.
+    pub fn new(n: &Decimal, accuracy: &Accuracy, approximation: Approximation) -> Result<ErrorInterval, MathError> {
+        let step = accuracy.step(n)?.trim();
+        let (lower_bound, upper_bound) = match approximation {
+            Approximation::Rounded => {
+                let (lower, upper) = calculate_bounds(n, &step);
+                let (lower_nearer_zero, upper_nearer_zero) = calculate_bounds(n, &accuracy.step_nearer_zero(n)?);
+                if n.is_negative() { (lower, upper_nearer_zero) } else { (lower_nearer_zero, upper) }
+            }
+            Approximation::Truncated if n.is_negative() => (n.sub(&step).trim(), n.trim()),
+            Approximation::Truncated if n.is_zero() => (Decimal::zero().sub(&step).trim(), step),
+            Approximation::Truncated => (n.trim(), n.add(&step).trim()),
+        };
+        // Zero can be reached from either side, so neither bound is included.
+        let (includes_lower, includes_upper) = if n.is_zero() { (false, false) } else { (!n.is_negative(), n.is_negative()) };
+        Ok(ErrorInterval { lower_bound, upper_bound, includes_lower, includes_upper })
+    }
+}
+
+impl ErrorInterval {
+    /// Writes the interval with `variable` in place of `x`, e.g. `115 ≤ d < 125`.
+    pub fn describe(&self, variable: &str) -> String {
+        let sign = |included: bool| if included { "≤" } else { "<" };
+        format!(
+            "{} {} {} {} {}",
+            self.lower_bound,
+            sign(self.includes_lower),
+            variable,
+            sign(self.includes_upper),
+            self.upper_bound
+        )
+    }
+}
+
+impl fmt::Display for ErrorInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.describe("x"))
+    }
+}
+
+/// Calculates the lower and upper bounds of a number `n` that has been rounded to a given
+/// degree of accuracy, e.g. 10 for the nearest ten or 0.1 for one decimal place.
+///
+/// The error margin is half the degree of accuracy, so 70 to the nearest 10 has bounds 65
+/// and 75.
+pub fn calculate_bounds(n: &Decimal, degree_of_accuracy: &Decimal) -> (Decimal, Decimal) {
+    let error_margin = degree_of_accuracy.half();
+    (n.sub(&error_margin).trim(), n.add(&error_margin).trim())
+}
+
+/// A closed range of values, `lower ≤ x ≤ upper`, carried through a calculation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Interval {
+    pub lower: Decimal,
+    pub upper: Decimal,
+}
+
+/// One end of an [`Interval`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Bound {
+    Lower,
+    Upper,
+}
+
+impl Bound {
+    /// The name of the bound, "lower bound" or "upper bound".
+    pub fn name(&self) -> &'static str {
+        match self {
+            Bound::Lower => "lower bound",
+            Bound::Upper => "upper bound",
+        }
+    }
+}
+
+impl Interval {
+    /// The interval holding only `n`, for a value known exactly.
+    pub fn exact(n: Decimal) -> Interval {
+        Interval { lower: n.clone(), upper: n }
+    }
+
+    /// The bounds of `n` rounded to the nearest `step`, as found by [`calculate_bounds`].
+    pub fn rounded(n: &Decimal, step: &Decimal) -> Interval {
+        let (lower, upper) = calculate_bounds(n, step);
+        Interval { lower, upper }
+    }
+
+    /// Returns `true` if the interval holds a single value.
+    pub fn is_exact(&self) -> bool {
+        self.lower == self.upper
+    }
+
+    /// One end of the interval.
+    pub fn bound(&self, bound: Bound) -> &Decimal {
+        match bound {
+            Bound::Lower => &self.lower,
+            Bound::Upper => &self.upper,
+        }
+    }
+}
+
+impl From<&ErrorInterval> for Interval {
+    /// The bounds of an error interval, ignoring whether each end is included.
+    fn from(interval: &ErrorInterval) -> Interval {
+        Interval { lower: interval.lower_bound.clone(), upper: interval.upper_bound.clone() }
+    }
+}
+
+/// A named value given to a degree of accuracy, such as `d = 120 (nearest 10)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Quantity {
+    pub name: String,
+    pub value: Decimal,
+    pub accuracy: Accuracy,
+    pub approximation: Approximation,
+    pub error_interval: ErrorInterval,
+}
+
+impl Quantity {
+    /// Fails if the accuracy is too fine to write out.
+    pub fn new(
+        name: &str,
+        value: Decimal,
+        accuracy: Accuracy,
+        approximation: Approximation,
+    ) -> Result<Quantity, MathError> {
+        let error_interval = ErrorInterval::new(&value, &accuracy, approximation)?;
+        Ok(Quantity { name: name.to_string(), value, accuracy, approximation, error_interval })
+    }
+}
+
+impl FromStr for Quantity {
+    type Err = MathError;
+
+    /// Reads a quantity such as `d = 120 (nearest 10)`, `t = 2.5 (1 d.p.)` or
+    /// `m = 4.3 (1 d.p., truncated)`. The brackets may be left out.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let s = input.trim();
+        if s.is_empty() {
+            return Err(MathError::EmptyInput);
+        }
+        let invalid = |part: &str| {
+            let part = if part.trim().is_empty() { s } else { part.trim() };
+            MathError::InvalidToken { token: part.to_string(), position: Span::of(part, input) }
+        };
+
+        let (name, rest) = s.split_once('=').ok_or_else(|| invalid(s))?;
+        if !is_name(name.trim()) {
+            return Err(invalid(name));
+        }
+        let rest = rest.trim_start();
+        let value_end = rest.find(|c: char| c.is_whitespace() || c == '(').unwrap_or(rest.len());
+        let value = &rest[..value_end];
+        let offset = Span::of(value, input).start;
+        let value: Decimal = value.parse().map_err(|e: MathError| e.shifted(offset))?;
+
+        // The accuracy may be followed by whether the value was rounded or truncated.
+        let details = rest[value_end..].trim();
+        let details = details.strip_prefix('(').and_then(|d| d.strip_suffix(')')).unwrap_or(details);
+        let mut accuracy = None;
+        let mut approximation = Approximation::Rounded;
+        for part in details.split(',') {
+            match part.trim().to_lowercase().as_str() {
+                "truncated" => approximation = Approximation::Truncated,
+                "rounded" => approximation = Approximation::Rounded,
+                _ => accuracy = Some(part),
+            }
+        }
+        let accuracy = accuracy.filter(|part| !part.trim().is_empty()).ok_or_else(|| MathError::InvalidExpression {
+            message: format!("give the accuracy of {}, e.g. {} = 120 (nearest 10)", name.trim(), name.trim()),
+        })?;
+        let step = strip_nearest(accuracy.trim());
+        let offset = Span::of(step, input).start;
+        let accuracy: Accuracy = step.parse().map_err(|e: MathError| e.shifted(offset))?;
+        Quantity::new(name.trim(), value, accuracy, approximation)
+    }
+}
+
+/// Removes the words in front of a step written as "to the nearest 10" or "nearest 10".
+fn strip_nearest(accuracy: &str) -> &str {
+    for prefix in ["to the nearest", "to nearest", "nearest", "to"] {
+        if accuracy.len() >= prefix.len() && accuracy.is_char_boundary(prefix.len()) {
+            let (start, rest) = accuracy.split_at(prefix.len());
+            if start.eq_ignore_ascii_case(prefix) && rest.starts_with(char::is_whitespace) {
+                return rest.trim_start();
+            }
+        }
+    }
+    accuracy
+}
+
+/// Returns `true` if `name` can be used as the name of a quantity: a letter followed by letters,
+/// digits or underscores.
+fn is_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_alphabetic()) && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// A value used in one step of a calculation with bounds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Operand {
+    /// The value as written in the expression, e.g. `d` or `(a + b)`.
+    pub expression: String,
+    pub interval: Interval,
+}
+
+impl Operand {
+    /// Names one bound of the value, e.g. "upper bound of d". A value known exactly, such as a
+    /// number written in the expression, has only one value to use.
+    fn describe(&self, bound: Bound) -> String {
+        if self.interval.is_exact() {
+            self.expression.clone()
+        } else {
+            format!("{} of {}", bound.name(), self.expression)
+        }
+    }
+}
+
+/// One bound of the answer to a step, and the bounds of the two values that give it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BoundChoice {
+    /// The bounds of the left and right values that were used.
+    pub from: [Bound; 2],
+    pub value: Decimal,
+    /// `false` if a division recurs and the value was rounded to [`DIVISION_FIGURES`]
+    /// significant figures.
+    pub exact: bool,
+}
+
+/// One operation in a calculation with bounds, such as `d / t`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BoundsStep {
+    /// The part of the expression worked out, as written.
+    pub expression: String,
+    pub operation: Operation,
+    pub left: Operand,
+    pub right: Operand,
+    pub lower: BoundChoice,
+    pub upper: BoundChoice,
+}
+
+impl BoundsStep {
+    /// Works out one operation. Every pairing of a bound of the left value with a bound of the
+    /// right value is tried, and the smallest and largest answers are the bounds, so the rule
+    /// "divide by the lower bound to get the upper bound" falls out rather than being assumed.
+    fn new(expression: &str, operation: Operation, left: Operand, right: Operand) -> Result<BoundsStep, MathError> {
+        let divisor = &right.interval;
+        if operation == Operation::Divide && !divisor.lower.is_positive() && !divisor.upper.is_negative() {
+            return Err(MathError::DivisionByZero);
+        }
+
+        let mut choices = Vec::new();
+        for from in [Bound::Lower, Bound::Upper].into_iter().flat_map(|a| [[a, Bound::Lower], [a, Bound::Upper]]) {
+            let (a, b) = (left.interval.bound(from[0]), right.interval.bound(from[1]));
+            let (value, exact) = match operation {
+                Operation::Add => (a.add(b), true),
+                Operation::Subtract => (a.sub(b), true),
+                Operation::Multiply => (a.mul(b), true),
+                Operation::Divide => a.div(b, DIVISION_FIGURES).ok_or(MathError::DivisionByZero)?,
+            };
+            choices.push(BoundChoice { from, value: value.trim(), exact });
+        }
+        // The first pairing found wins a tie, so the working reads the same way every time.
+        let lower = choices.iter().min_by(|x, y| x.value.cmp(&y.value)).cloned().expect("there are four pairings");
+        let upper = choices.iter().rev().max_by(|x, y| x.value.cmp(&y.value));
+        let upper = upper.cloned().expect("there are four pairings");
+        Ok(BoundsStep { expression: expression.to_string(), operation, left, right, lower, upper })
+    }
+
+    /// The bounds of the answer to the step.
+    pub fn interval(&self) -> Interval {
+        Interval { lower: self.lower.value.clone(), upper: self.upper.value.clone() }
+    }
+
+    /// Explains one bound of the step, e.g. "Upper bound of d / t = upper bound of d ÷ lower
+    /// bound of t = 125 ÷ 2.45 ≈ 51.02040816".
+    pub fn describe(&self, bound: Bound) -> String {
+        let choice = match bound {
+            Bound::Lower => &self.lower,
+            Bound::Upper => &self.upper,
+        };
+        let [left, right] = choice.from;
+        let name = bound.name();
+        format!(
+            "{}{} of {} = {} {} {} = {} {} {} {} {}",
+            name[..1].to_uppercase(),
+            &name[1..],
+            self.expression,
+            self.left.describe(left),
+            self.operation,
+            self.right.describe(right),
+            self.left.interval.bound(left),
+            self.operation,
+            self.right.interval.bound(right),
+            if choice.exact { "=" } else { "≈" },
+            choice.value
+        )
+    }
+}
+
+/// The bounds of a calculation such as `d / t`, where each letter stands for a rounded
+/// [`Quantity`], with the working for every operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BoundsCalculation {
+    pub expression: String,
+    pub quantities: Vec<Quantity>,
+    /// Each operation in the order it is worked out.
+    pub steps: Vec<BoundsStep>,
+    pub interval: Interval,
+    /// `false` if a division recurs and a bound was rounded to [`DIVISION_FIGURES`]
+    /// significant figures.
+    pub exact: bool,
+}
+
+impl BoundsCalculation {
+    /// Finds the bounds of `expression`, which is read by [`parse`] and may use `+`, `-`, `×`,
+    /// `÷` (or `*` and `/`), brackets, numbers, which are taken as exact, and the names of the
+    /// quantities.
+    pub fn calculate(expression: &str, quantities: Vec<Quantity>) -> Result<BoundsCalculation, MathError> {
+        let expr = parse(expression)?;
+        let mut steps = Vec::new();
+        let interval = bounds_of(&expr, expression, &quantities, &mut steps)?;
+        let exact = steps.iter().all(|step| step.lower.exact && step.upper.exact);
+        Ok(BoundsCalculation { expression: expression.trim().to_string(), quantities, steps, interval, exact })
+    }
+
+    /// Describes the bounds of each quantity and then each bound of every operation, one line
+    /// per step.
+    pub fn working(&self) -> Vec<String> {
+        let mut working: Vec<String> = self
+            .quantities
+            .iter()
+            .map(|quantity| {
+                let approximation = match quantity.approximation {
+                    Approximation::Rounded => "",
+                    Approximation::Truncated => " (truncated)",
+                };
+                format!(
+                    "{} = {} {}{}, so {}",
+                    quantity.name,
+                    quantity.value,
+                    quantity.accuracy,
+                    approximation,
+                    quantity.error_interval.describe(&quantity.name)
+                )
+            })
+            .collect();
+        for step in &self.steps {
+            working.push(step.describe(Bound::Lower));
+            working.push(step.describe(Bound::Upper));
+        }
+        working
+    }
+}
+
+/// Works out the bounds of a part of `input`, adding a step to `steps` for each operation in the
+/// order it is worked out. Numbers are exact, and names stand for the quantities given.
+fn bounds_of(
+    expr: &Expr,
+    input: &str,
+    quantities: &[Quantity],
+    steps: &mut Vec<BoundsStep>,
+) -> Result<Interval, MathError> {
+    let written = |expr: &Expr| input[expr.span.start..expr.span.end].to_string();
+    let unsupported = || MathError::InvalidToken { token: written(expr), position: expr.span };
+    match &expr.kind {
+        ExprKind::Number(number) => Ok(Interval::exact(number.clone())),
+        ExprKind::Name(name) => match quantities.iter().rev().find(|quantity| quantity.name == *name) {
+            Some(quantity) => Ok(Interval::from(&quantity.error_interval)),
+            None => Err(unsupported()),
+        },
+        ExprKind::Brackets(inner) => bounds_of(inner, input, quantities, steps),
+        // Negating swaps the bounds over: the lower bound of -d is minus the upper bound of d.
+        ExprKind::Negate(operand) => {
+            let interval = bounds_of(operand, input, quantities, steps)?;
+            Ok(Interval { lower: Decimal::zero().sub(&interval.upper), upper: Decimal::zero().sub(&interval.lower) })
+        }
+        ExprKind::Binary { op, left, right, .. } => {
+            let operation = match op {
+                BinaryOp::Add => Operation::Add,
+                BinaryOp::Subtract => Operation::Subtract,
+                BinaryOp::Multiply => Operation::Multiply,
+                BinaryOp::Divide => Operation::Divide,
+                BinaryOp::Remainder | BinaryOp::Power => return Err(unsupported()),
+            };
+            let left = Operand { expression: written(left), interval: bounds_of(left, input, quantities, steps)? };
+            let right = Operand { expression: written(right), interval: bounds_of(right, input, quantities, steps)? };
+            let step = BoundsStep::new(&written(expr), operation, left, right)?;
+            let interval = step.interval();
+            steps.push(step);
+            Ok(interval)
+        }
+        ExprKind::Index(..) | ExprKind::SquareRoot(_) | ExprKind::Call { .. } => Err(unsupported()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    fn interval(n: &str, accuracy: &str, approximation: Approximation) -> String {
+        let accuracy: Accuracy = accuracy.parse().unwrap();
+        ErrorInterval::new(&dec(n), &accuracy, approximation).unwrap().to_string()
+    }
+
+    #[test]
+    fn calculates_bounds() {
+        assert_eq!(calculate_bounds(&dec("70"), &dec("10")), (dec("65"), dec("75")));
+        assert_eq!(calculate_bounds(&dec("6.5"), &dec("1")), (dec("6"), dec("7")));
+        assert_eq!(calculate_bounds(&dec("0.3"), &dec("0.1")).0.to_string(), "0.25");
+    }
+
+    #[test]
+    fn reads_every_way_of_giving_accuracy() {
+        assert_eq!("1 d.p.".parse(), Ok(Accuracy::DecimalPlaces(1)));
+        assert_eq!("2dp".parse(), Ok(Accuracy::DecimalPlaces(2)));
+        assert_eq!("2 s.f.".parse(), Ok(Accuracy::SignificantFigures(2)));
+        assert_eq!("3 Sig Figs".parse(), Ok(Accuracy::SignificantFigures(3)));
+        assert_eq!("1 significant figure".parse(), Ok(Accuracy::SignificantFigures(1)));
+        assert_eq!("0.1".parse(), Ok(Accuracy::Nearest(dec("0.1"))));
+        assert_eq!("0 s.f.".parse::<Accuracy>(), Err(MathError::NotPositive));
+        assert_eq!("-10".parse::<Accuracy>(), Err(MathError::NotPositive));
+        assert!("x d.p.".parse::<Accuracy>().is_err());
+        assert_eq!(Accuracy::SignificantFigures(1).to_string(), "to 1 significant figure");
+    }
+
+    #[test]
+    fn finds_the_step_for_each_accuracy() {
+        assert_eq!(Accuracy::DecimalPlaces(2).step(&dec("6.45")), Ok(dec("0.01")));
+        assert_eq!(Accuracy::SignificantFigures(2).step(&dec("6500")), Ok(dec("100")));
+        assert_eq!(Accuracy::SignificantFigures(3).step(&dec("0.0340")), Ok(dec("0.0001")));
+        assert_eq!(Accuracy::Nearest(dec("5")).step(&dec("70")), Ok(dec("5")));
+    }
+
+    #[test]
+    fn fails_when_the_accuracy_is_too_fine() {
+        for count in [1001, 2_147_483_648, 3_000_000_000] {
+            assert_eq!(Accuracy::DecimalPlaces(count).step(&dec("6.5")), Err(MathError::Overflow));
+            assert_eq!(Accuracy::SignificantFigures(count).step(&dec("6.5")), Err(MathError::Overflow));
+        }
+        assert_eq!("d = 6.5 (2147483648 d.p.)".parse::<Quantity>(), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn writes_error_intervals() {
+        assert_eq!(interval("6.5", "1 d.p.", Approximation::Rounded), "6.45 ≤ x < 6.55");
+        assert_eq!(interval("6500", "2 s.f.", Approximation::Rounded), "6450 ≤ x < 6550");
+        assert_eq!(interval("70", "10", Approximation::Rounded), "65 ≤ x < 75");
+        assert_eq!(interval("-6.5", "1 d.p.", Approximation::Rounded), "-6.55 < x ≤ -6.45");
+        assert_eq!(interval("0", "1 d.p.", Approximation::Rounded), "-0.05 < x < 0.05");
+    }
+
+    #[test]
+    fn writes_error_intervals_for_powers_of_ten() {
+        // 950 rounds up to 1000 to 1 s.f., as numbers below 1000 are to the nearest 100.
+        assert_eq!(interval("1000", "1 s.f.", Approximation::Rounded), "950 ≤ x < 1500");
+        assert_eq!(interval("100", "2 s.f.", Approximation::Rounded), "99.5 ≤ x < 105");
+        assert_eq!(interval("0.1", "1 s.f.", Approximation::Rounded), "0.095 ≤ x < 0.15");
+        assert_eq!(interval("-1000", "1 s.f.", Approximation::Rounded), "-1500 < x ≤ -950");
+        assert_eq!(interval("1000", "1 d.p.", Approximation::Rounded), "999.95 ≤ x < 1000.05");
+        // A truncated number is never cut down to a power of ten from below it.
+        assert_eq!(interval("1000", "1 s.f.", Approximation::Truncated), "1000 ≤ x < 2000");
+        assert_eq!(interval("-0.1", "1 s.f.", Approximation::Truncated), "-0.2 < x ≤ -0.1");
+    }
+
+    #[test]
+    fn writes_error_intervals_for_truncated_numbers() {
+        assert_eq!(interval("6.4", "1 d.p.", Approximation::Truncated), "6.4 ≤ x < 6.5");
+        assert_eq!(interval("-6.4", "1 d.p.", Approximation::Truncated), "-6.5 < x ≤ -6.4");
+        assert_eq!(interval("0", "1", Approximation::Truncated), "-1 < x < 1");
+        assert_eq!(interval("2.0", "1 d.p.", Approximation::Truncated), "2 ≤ x < 2.1");
+    }
+
+    fn quantities(inputs: &[&str]) -> Vec<Quantity> {
+        inputs.iter().map(|input| input.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn reads_quantities() {
+        let d: Quantity = "d = 120 (nearest 10)".parse().unwrap();
+        assert_eq!(d.name, "d");
+        assert_eq!(d.accuracy, Accuracy::Nearest(dec("10")));
+        assert_eq!(d.error_interval.describe("d"), "115 ≤ d < 125");
+        let t: Quantity = "t = 2.5 (1 d.p.)".parse().unwrap();
+        assert_eq!(t.accuracy, Accuracy::DecimalPlaces(1));
+        let m: Quantity = "m = 4.3 (to 1 d.p., truncated)".parse().unwrap();
+        assert_eq!(m.approximation, Approximation::Truncated);
+        assert_eq!(m.error_interval.describe("m"), "4.3 ≤ m < 4.4");
+        let a: Quantity = "a=70 to the nearest 5".parse().unwrap();
+        assert_eq!(a.accuracy, Accuracy::Nearest(dec("5")));
+
+        let input = "d = 12o (nearest 10)";
+        assert_eq!(
+            input.parse::<Quantity>(),
+            Err(MathError::InvalidToken { token: "12o".to_string(), position: Span::new(4, 7) })
+        );
+        assert!(matches!("2d = 120 (nearest 10)".parse::<Quantity>(), Err(MathError::InvalidToken { .. })));
+        assert!(matches!("d = 120".parse::<Quantity>(), Err(MathError::InvalidExpression { .. })));
+    }
+
+    #[test]
+    fn chooses_the_bound_of_each_operand() {
+        let calculation =
+            BoundsCalculation::calculate("d / t", quantities(&["d = 120 (nearest 10)", "t = 2.5 (1 d.p.)"])).unwrap();
+        assert_eq!(calculation.steps.len(), 1);
+        let step = &calculation.steps[0];
+        assert_eq!(step.upper.from, [Bound::Upper, Bound::Lower]);
+        assert_eq!(step.lower.from, [Bound::Lower, Bound::Upper]);
+        assert_eq!(calculation.interval.upper, dec("51.02040816"));
+        assert_eq!(calculation.interval.lower, dec("45.09803922"));
+        assert!(!calculation.exact);
+        assert_eq!(
+            step.describe(Bound::Upper),
+            "Upper bound of d / t = upper bound of d ÷ lower bound of t = 125 ÷ 2.45 ≈ 51.02040816"
+        );
+
+        let given = quantities(&["a = 10 (nearest 1)", "b = 4 (nearest 1)"]);
+        let calculation = BoundsCalculation::calculate("a - b", given).unwrap();
+        assert_eq!(calculation.interval, Interval { lower: dec("5"), upper: dec("7") });
+        assert_eq!(calculation.steps[0].upper.from, [Bound::Upper, Bound::Lower]);
+        assert!(calculation.exact);
+    }
+
+    #[test]
+    fn works_through_brackets_in_order() {
+        let given = quantities(&["a = 2.5 (1 d.p.)", "b = 1.5 (1 d.p.)", "c = 4 (nearest 1)"]);
+        let calculation = BoundsCalculation::calculate("(a + b) × c", given).unwrap();
+        let steps: Vec<&str> = calculation.steps.iter().map(|step| step.expression.as_str()).collect();
+        assert_eq!(steps, ["a + b", "(a + b) × c"]);
+        assert_eq!(calculation.interval, Interval { lower: dec("13.65"), upper: dec("18.45") });
+        assert_eq!(
+            calculation.working(),
+            [
+                "a = 2.5 to 1 decimal place, so 2.45 ≤ a < 2.55",
+                "b = 1.5 to 1 decimal place, so 1.45 ≤ b < 1.55",
+                "c = 4 to the nearest 1, so 3.5 ≤ c < 4.5",
+                "Lower bound of a + b = lower bound of a + lower bound of b = 2.45 + 1.45 = 3.9",
+                "Upper bound of a + b = upper bound of a + upper bound of b = 2.55 + 1.55 = 4.1",
+                "Lower bound of (a + b) × c = lower bound of (a + b) × lower bound of c = 3.9 × 3.5 = 13.65",
+                "Upper bound of (a + b) × c = upper bound of (a + b) × upper bound of c = 4.1 × 4.5 = 18.45",
+            ]
+        );
+
+        let calculation = BoundsCalculation::calculate("2 * a", quantities(&["a = 2.5 (1 d.p.)"])).unwrap();
+        assert_eq!(
+            calculation.steps[0].describe(Bound::Lower),
+            "Lower bound of 2 * a = 2 × lower bound of a = 2 × 2.45 = 4.9"
+        );
+
+        // A minus sign in front swaps the bounds over, and a sign may be left out before a name.
+        let calculation = BoundsCalculation::calculate("-a + 2a", quantities(&["a = 2.5 (1 d.p.)"])).unwrap();
+        let steps: Vec<&str> = calculation.steps.iter().map(|step| step.expression.as_str()).collect();
+        assert_eq!(steps, ["2a", "-a + 2a"]);
+        assert_eq!(calculation.steps[1].left.interval, Interval { lower: dec("-2.55"), upper: dec("-2.45") });
+        assert_eq!(calculation.interval, Interval { lower: dec("2.35"), upper: dec("2.65") });
+    }
+
+    #[test]
+    fn rejects_bad_calculations() {
+        let quantities = quantities(&["a = 2.5 (1 d.p.)", "z = 0 (nearest 1)"]);
+        assert_eq!(
+            BoundsCalculation::calculate("a / b", quantities.clone()),
+            Err(MathError::InvalidToken { token: "b".to_string(), position: Span::new(4, 5) })
+        );
+        assert_eq!(BoundsCalculation::calculate("a / z", quantities.clone()), Err(MathError::DivisionByZero));
+        assert_eq!(
+            BoundsCalculation::calculate("1 + a^2", quantities.clone()),
+            Err(MathError::InvalidToken { token: "a^2".to_string(), position: Span::new(4, 7) })
+        );
+        for incomplete in ["(a + 1", "a +"] {
+            let result = BoundsCalculation::calculate(incomplete, quantities.clone());
+            assert!(matches!(result, Err(MathError::InvalidExpression { .. })));
+        }
+        assert_eq!(BoundsCalculation::calculate(" ", quantities), Err(MathError::EmptyInput));
+    }
+}
@@ -0,0 +1,573 @@
+//! Exact decimal numbers, used for rounding.
+//!
+//! An `f64` cannot hold most decimals exactly: 2.675 is stored as 2.67499999…, so rounding it
+//! to 2 decimal places with `f64` arithmetic gives 2.67. A [`Decimal`] keeps exactly the digits
+//! that were typed, so every rounding calculator gives the answer that would be worked out on
+//! paper.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::num::IntErrorKind;
+use std::str::FromStr;
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use serde::{Serialize, Serializer};
+
+use crate::error::{MathError, Span};
+
+/// The largest power of ten that can be written after an `e` when reading a number, so `1e1000`
+/// is accepted but `1e1001` is too large. This keeps every number small enough to write out in
+/// full and to line up with another number's digits.
+pub const LARGEST_EXPONENT: i32 = 1000;
+
+/// The rule used to round a number that lies between two possible answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    /// Round to the nearest answer, with halves rounded away from zero: 2.5 → 3, -2.5 → -3.
+    #[default]
+    HalfUp,
+    /// Round to the nearest answer, with halves rounded to the even one: 2.5 → 2, 3.5 → 4.
+    /// Also called banker's rounding.
+    HalfEven,
+    /// Cut off the digits that are not kept, rounding towards zero: 2.9 → 2, -2.9 → -2.
+    Truncate,
+    /// Always round down: 2.9 → 2, -2.1 → -3.
+    Floor,
+    /// Always round up: 2.1 → 3, -2.9 → -2.
+    Ceiling,
+}
+
+impl RoundingMode {
+    /// A list of every mode.
+    pub const ALL: [RoundingMode; 5] = [
+        RoundingMode::HalfUp,
+        RoundingMode::HalfEven,
+        RoundingMode::Truncate,
+        RoundingMode::Floor,
+        RoundingMode::Ceiling,
+    ];
+
+    /// The short name of the mode, such as `half-up`, as accepted by [`RoundingMode::from_name`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            RoundingMode::HalfUp => "half-up",
+            RoundingMode::HalfEven => "half-even",
+            RoundingMode::Truncate => "truncate",
+            RoundingMode::Floor => "floor",
+            RoundingMode::Ceiling => "ceiling",
+        }
+    }
+
+    /// Reads a mode from its short name, ignoring case and allowing `_` or a space in place of
+    /// `-`, so `half-even`, `HALF_EVEN` and `half even` are all accepted.
+    pub fn from_name(name: &str) -> Option<RoundingMode> {
+        let name = name.trim().to_lowercase().replace(['_', ' '], "-");
+        RoundingMode::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    /// Whether a number whose magnitude is `multiples` whole steps plus `remainder` (out of a
+    /// step of `step`) should be rounded to the next step away from zero.
+    fn rounds_away_from_zero(&self, multiples: &BigUint, remainder: &BigUint, step: &BigUint, negative: bool) -> bool {
+        let twice_remainder = remainder * 2u32;
+        match self {
+            RoundingMode::HalfUp => twice_remainder >= *step,
+            RoundingMode::HalfEven => twice_remainder > *step || (twice_remainder == *step && multiples.is_odd()),
+            RoundingMode::Truncate => false,
+            RoundingMode::Floor => negative && !remainder.is_zero(),
+            RoundingMode::Ceiling => !negative && !remainder.is_zero(),
+        }
+    }
+}
+
+impl fmt::Display for RoundingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoundingMode::HalfUp => write!(f, "Round half up"),
+            RoundingMode::HalfEven => write!(f, "Round half to even"),
+            RoundingMode::Truncate => write!(f, "Truncate"),
+            RoundingMode::Floor => write!(f, "Round down (floor)"),
+            RoundingMode::Ceiling => write!(f, "Round up (ceiling)"),
+        }
+    }
+}
+
+impl FromStr for RoundingMode {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        RoundingMode::from_name(name).ok_or_else(|| {
+            let names: Vec<&str> = RoundingMode::ALL.iter().map(RoundingMode::name).collect();
+            format!("expected one of {}", names.join(", "))
+        })
+    }
+}
+
+/// An exact decimal number, stored as a whole-number coefficient and a power of ten:
+/// `coefficient × 10^exponent`.
+///
+/// The number keeps the precision it was written or rounded to, so `2.50` and `2.5` are held
+/// differently, but they compare equal because they have the same value.
+#[derive(Debug, Clone)]
+pub struct Decimal {
+    coefficient: BigInt,
+    exponent: i32,
+}
+
+impl Decimal {
+    /// The number 0.
+    pub fn zero() -> Decimal {
+        Decimal { coefficient: BigInt::zero(), exponent: 0 }
+    }
+
+    /// The number `10^exponent`, such as 100 for 2 or 0.1 for -1.
+    pub fn power_of_ten(exponent: i32) -> Decimal {
+        Decimal { coefficient: BigInt::from(1), exponent }
+    }
+
+    /// The shortest decimal that converts back to the same `f64`, or `None` for infinity and
+    /// NaN. This is the number `f64` prints as, so `0.1` gives exactly 0.1.
+    pub fn from_f64(value: f64) -> Option<Decimal> {
+        if !value.is_finite() {
+            return None;
+        }
+        value.to_string().parse().ok()
+    }
+
+    /// The nearest `f64` to the number.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().expect("a decimal always prints as a valid f64")
+    }
+
+    /// Returns `true` if the number is zero.
+    pub fn is_zero(&self) -> bool {
+        self.coefficient.is_zero()
+    }
+
+    /// Returns `true` if the number is greater than zero.
+    pub fn is_positive(&self) -> bool {
+        self.coefficient.is_positive()
+    }
+
+    /// Returns `true` if the number is less than zero.
+    pub fn is_negative(&self) -> bool {
+        self.coefficient.is_negative()
+    }
+
+    /// The absolute value of the number.
+    pub fn abs(&self) -> Decimal {
+        Decimal { coefficient: self.coefficient.abs(), exponent: self.exponent }
+    }
+
+    /// The power of ten of the first significant digit, e.g. 4 for 52691 and -4 for 0.00097151,
+    /// or `None` for zero.
+    pub fn leading_digit_position(&self) -> Option<i32> {
+        if self.is_zero() {
+            return None;
+        }
+        let digits = i32::try_from(self.coefficient.magnitude().to_string().len()).ok()?;
+        let position = self.exponent.checked_add(digits - 1);
+        Some(position.expect("the exponent of a decimal is kept well within range"))
+    }
+
+    /// Returns `true` if the number is a power of ten, ignoring its sign, such as 1000, 0.1 or
+    /// -100.
+    pub fn is_power_of_ten(&self) -> bool {
+        self.leading_digit_position().is_some_and(|position| self.abs() == Decimal::power_of_ten(position))
+    }
+
+    /// The number of digits held after the decimal point, including any zeros at the end, so
+    /// 2 for `2.50` and 0 for `1500`.
+    pub fn decimal_places(&self) -> u32 {
+        if self.exponent < 0 { self.exponent.unsigned_abs() } else { 0 }
+    }
+
+    /// The same value without any trailing zeros after the decimal point, so `2.50` gives `2.5`.
+    pub fn trim(&self) -> Decimal {
+        let mut trimmed = self.clone();
+        let ten = BigInt::from(10);
+        while trimmed.exponent < 0 && !trimmed.coefficient.is_zero() && (&trimmed.coefficient % &ten).is_zero() {
+            trimmed.coefficient /= &ten;
+            trimmed.exponent += 1;
+        }
+        if trimmed.coefficient.is_zero() {
+            trimmed.exponent = trimmed.exponent.max(0);
+        }
+        trimmed
+    }
+
+    /// The same value without any zeros at the end of a whole number, so `320000` gives 32 × 10⁴.
+    /// Numbers with digits after the decimal point are left as they are.
+    pub fn trim_whole_number_zeros(&self) -> Decimal {
+        let mut trimmed = self.clone();
+        let ten = BigInt::from(10);
+        while trimmed.exponent >= 0 && !trimmed.coefficient.is_zero() && (&trimmed.coefficient % &ten).is_zero() {
+            trimmed.coefficient /= &ten;
+            trimmed.exponent += 1;
+        }
+        trimmed
+    }
+
+    /// Both coefficients rewritten over the smaller of the two exponents, so that they can be
+    /// added or compared directly.
+    fn aligned(&self, other: &Decimal) -> (BigInt, BigInt, i32) {
+        let exponent = self.exponent.min(other.exponent);
+        (self.scaled_to(exponent), other.scaled_to(exponent), exponent)
+    }
+
+    /// The coefficient when the number is written over `10^exponent`, which must be no larger
+    /// than the number's own exponent.
+    fn scaled_to(&self, exponent: i32) -> BigInt {
+        let shift = self.exponent.checked_sub(exponent).and_then(|shift| u32::try_from(shift).ok());
+        let shift = shift.expect("can only scale to a smaller exponent within range");
+        &self.coefficient * BigInt::from(10).pow(shift)
+    }
+
+    /// Adds two numbers exactly.
+    pub fn add(&self, other: &Decimal) -> Decimal {
+        let (a, b, exponent) = self.aligned(other);
+        Decimal { coefficient: a + b, exponent }
+    }
+
+    /// Subtracts `other` from this number exactly.
+    pub fn sub(&self, other: &Decimal) -> Decimal {
+        let (a, b, exponent) = self.aligned(other);
+        Decimal { coefficient: a - b, exponent }
+    }
+
+    /// Multiplies two numbers exactly.
+    pub fn mul(&self, other: &Decimal) -> Decimal {
+        Decimal { coefficient: &self.coefficient * &other.coefficient, exponent: self.exponent + other.exponent }
+    }
+
+    /// Divides this number by `other`, or returns `None` if `other` is zero.
+    ///
+    /// A quotient that ends, such as 6.3 ÷ 2 = 3.15, is exact and is returned with `true`. One
+    /// that recurs, such as 1 ÷ 3, is rounded half up to `figures` significant figures and is
+    /// returned with `false`.
+    pub fn div(&self, other: &Decimal, figures: u32) -> Option<(Decimal, bool)> {
+        if other.is_zero() {
+            return None;
+        }
+        let exponent = self.exponent - other.exponent;
+        if self.is_zero() {
+            return Some((Decimal::zero(), true));
+        }
+
+        // The quotient ends only if the denominator of the fraction in its lowest terms has no
+        // prime factors other than 2 and 5.
+        let common = self.coefficient.gcd(&other.coefficient);
+        let mut denominator = (&other.coefficient / &common).magnitude().clone();
+        let (mut twos, mut fives) = (0, 0);
+        while denominator.is_even() {
+            denominator /= 2u32;
+            twos += 1;
+        }
+        while (&denominator % 5u32).is_zero() {
+            denominator /= 5u32;
+            fives += 1;
+        }
+        if denominator.is_one() {
+            let shift: u32 = twos.max(fives);
+            let coefficient = &self.coefficient * BigInt::from(10).pow(shift) / &other.coefficient;
+            let quotient = Decimal { coefficient, exponent: exponent - shift as i32 };
+            return Some((quotient.trim(), true));
+        }
+
+        // Work out one more digit than is needed and round it off. The digits that are cut off
+        // never stop, so the quotient can never be exactly halfway and the rounding is right.
+        let figures = figures.max(1);
+        let digits = |n: &BigInt| n.magnitude().to_string().len() as u32;
+        let shift = (figures + 1 + digits(&other.coefficient)).saturating_sub(digits(&self.coefficient));
+        let coefficient = &self.coefficient * BigInt::from(10).pow(shift) / &other.coefficient;
+        let quotient = Decimal { coefficient, exponent: exponent - shift as i32 };
+        let leading = quotient.leading_digit_position()?;
+        let step = Decimal::power_of_ten(leading - figures as i32 + 1);
+        Some((quotient.round_to_multiple(&step, RoundingMode::HalfUp), false))
+    }
+
+    /// The number multiplied by `10^places`, which moves the decimal point `places` places to
+    /// the right, or to the left if `places` is negative. The digits are kept as they are, so
+    /// 3.20 shifted by 2 is `320`.
+    pub fn shifted(&self, places: i32) -> Decimal {
+        Decimal { coefficient: self.coefficient.clone(), exponent: self.exponent + places }
+    }
+
+    /// Half of the number, exactly: dividing by 2 is the same as multiplying by 5 and dividing
+    /// by 10.
+    pub fn half(&self) -> Decimal {
+        Decimal { coefficient: &self.coefficient * 5, exponent: self.exponent - 1 }
+    }
+
+    /// Returns `true` if the number is written with significant zeros at the end of a whole
+    /// number, such as 50000 to 2 s.f. or 100 to 3 s.f. Written out in full, these zeros look
+    /// the same as zeros that only hold the place value, so they need
+    /// [`StandardForm`](crate::standard_form::StandardForm) to show them.
+    pub fn has_significant_trailing_zeros(&self) -> bool {
+        self.exponent >= 0 && !self.is_zero() && (&self.coefficient % 10u32).is_zero()
+    }
+
+    /// Rounds the number to a multiple of `step`, which must be greater than zero, using the
+    /// given rule. With [`RoundingMode::HalfUp`], 2.675 to the nearest 0.01 gives 2.68 and -2.5
+    /// to the nearest 1 gives -3.
+    ///
+    /// The answer is written to the precision of `step`, so rounding 2.5 to the nearest 0.01
+    /// gives `2.50`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is zero or negative.
+    pub fn round_to_multiple(&self, step: &Decimal, mode: RoundingMode) -> Decimal {
+        assert!(step.is_positive(), "can only round to a positive step");
+        let (number, step_size, _) = self.aligned(step);
+        let (mut multiples, remainder) = number.magnitude().div_rem(step_size.magnitude());
+        if mode.rounds_away_from_zero(&multiples, &remainder, step_size.magnitude(), self.is_negative()) {
+            multiples += 1u32;
+        }
+        let sign = if self.is_negative() { Sign::Minus } else { Sign::Plus };
+        Decimal {
+            coefficient: BigInt::from_biguint(sign, multiples) * &step.coefficient,
+            exponent: step.exponent,
+        }
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(value: i64) -> Self {
+        Decimal { coefficient: BigInt::from(value), exponent: 0 }
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b, _) = self.aligned(other);
+        a.cmp(&b)
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = MathError;
+
+    /// Reads a number such as `2.675`, `-0.004`, `.5` or `1.5e6`, keeping every digit as typed,
+    /// including trailing zeros. A power of ten after the `e` beyond [`LARGEST_EXPONENT`] either
+    /// way is an overflow.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let s = input.trim();
+        if s.is_empty() {
+            return Err(MathError::EmptyInput);
+        }
+        let invalid = || MathError::InvalidToken { token: s.to_string(), position: Span::of(s, input) };
+
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let (mantissa, power) = match unsigned.split_once(['e', 'E']) {
+            Some((mantissa, power)) => (
+                mantissa,
+                power.parse::<i32>().map_err(|e| match e.kind() {
+                    IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => MathError::Overflow,
+                    _ => invalid(),
+                })?,
+            ),
+            None => (unsigned, 0),
+        };
+        if power.unsigned_abs() > LARGEST_EXPONENT.unsigned_abs() {
+            return Err(MathError::Overflow);
+        }
+        let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if whole.is_empty() && fraction.is_empty()
+            || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+
+        let digits = format!("{}{}", whole, fraction);
+        let magnitude: BigInt = digits.parse().map_err(|_| invalid())?;
+        let exponent = i32::try_from(fraction.len())
+            .ok()
+            .and_then(|places| power.checked_sub(places))
+            .ok_or(MathError::Overflow)?;
+        Ok(Decimal { coefficient: if negative { -magnitude } else { magnitude }, exponent })
+    }
+}
+
+impl fmt::Display for Decimal {
+    /// Writes the number in full, without an exponent, to the precision it holds: `2.50` stays
+    /// `2.50` and 5 × 10³ is written `5000`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.is_negative() { "-" } else { "" };
+        let digits = self.coefficient.magnitude().to_string();
+        if self.exponent >= 0 {
+            let zeros = if self.is_zero() { 0 } else { self.exponent as usize };
+            return write!(f, "{}{}{}", sign, digits, "0".repeat(zeros));
+        }
+        let places = self.exponent.unsigned_abs() as usize;
+        let digits = format!("{:0>width$}", digits, width = places + 1);
+        let (whole, fraction) = digits.split_at(digits.len() - places);
+        write!(f, "{}{}.{}", sign, whole, fraction)
+    }
+}
+
+impl Serialize for Decimal {
+    /// Decimals are written as strings, such as `"2.68"`, so that no digits are lost.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl ToPrimitive for Decimal {
+    fn to_i64(&self) -> Option<i64> {
+        let trimmed = self.trim();
+        if trimmed.exponent < 0 {
+            return None;
+        }
+        trimmed.scaled_to(0).to_i64()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.to_i64().and_then(|value| u64::try_from(value).ok())
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        Some(Decimal::to_f64(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn keeps_the_digits_as_typed() {
+        assert_eq!(dec("2.675").to_string(), "2.675");
+        assert_eq!(dec("2.50").to_string(), "2.50");
+        assert_eq!(dec("-0.004").to_string(), "-0.004");
+        assert_eq!(dec(".5").to_string(), "0.5");
+        assert_eq!(dec("1.5e3").to_string(), "1500");
+        assert_eq!(dec("12e-4").to_string(), "0.0012");
+        assert_eq!(dec("2.50"), dec("2.5"));
+        assert!(dec("-3") < dec("0.1"));
+    }
+
+    #[test]
+    fn rejects_invalid_numbers() {
+        assert_eq!("".parse::<Decimal>(), Err(MathError::EmptyInput));
+        assert_eq!(
+            " 1.2.3".parse::<Decimal>(),
+            Err(MathError::InvalidToken { token: "1.2.3".to_string(), position: Span::new(1, 6) })
+        );
+        assert!("inf".parse::<Decimal>().is_err());
+        assert!("-".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn rejects_huge_exponents() {
+        assert_eq!("1e2147483647".parse::<Decimal>(), Err(MathError::Overflow));
+        assert_eq!("3.2e-1001".parse::<Decimal>(), Err(MathError::Overflow));
+        assert_eq!("1e99999999999".parse::<Decimal>(), Err(MathError::Overflow));
+        assert_eq!(dec("1e1000").leading_digit_position(), Some(1000));
+        assert!(dec("1e-1000") < dec("1e1000"));
+    }
+
+    #[test]
+    fn rounds_halves_away_from_zero() {
+        let round = |n: &str, step: &Decimal| dec(n).round_to_multiple(step, RoundingMode::HalfUp).to_string();
+        let hundredth = Decimal::power_of_ten(-2);
+        assert_eq!(round("2.675", &hundredth), "2.68");
+        assert_eq!(round("1.005", &hundredth), "1.01");
+        assert_eq!(round("-2.5", &Decimal::power_of_ten(0)), "-3");
+        assert_eq!(round("2.5", &hundredth), "2.50");
+        assert_eq!(round("2.3", &dec("0.5")), "2.5");
+        assert_eq!(round("5468.9", &Decimal::power_of_ten(1)), "5470");
+    }
+
+    #[test]
+    fn rounds_with_each_mode() {
+        let one = Decimal::power_of_ten(0);
+        let round = |n: &str, mode| dec(n).round_to_multiple(&one, mode).to_string();
+        assert_eq!(round("2.5", RoundingMode::HalfEven), "2");
+        assert_eq!(round("3.5", RoundingMode::HalfEven), "4");
+        assert_eq!(round("2.51", RoundingMode::HalfEven), "3");
+        assert_eq!(round("2.9", RoundingMode::Truncate), "2");
+        assert_eq!(round("-2.9", RoundingMode::Truncate), "-2");
+        assert_eq!(round("-2.1", RoundingMode::Floor), "-3");
+        assert_eq!(round("2.1", RoundingMode::Ceiling), "3");
+        assert_eq!(round("-2.9", RoundingMode::Ceiling), "-2");
+        assert_eq!(round("3", RoundingMode::Ceiling), "3");
+    }
+
+    #[test]
+    fn reads_rounding_modes() {
+        assert_eq!(RoundingMode::from_name("half-even"), Some(RoundingMode::HalfEven));
+        assert_eq!(RoundingMode::from_name("HALF_UP"), Some(RoundingMode::HalfUp));
+        assert_eq!("truncate".parse(), Ok(RoundingMode::Truncate));
+        assert_eq!(RoundingMode::from_name("nearest"), None);
+    }
+
+    #[test]
+    fn finds_significant_trailing_zeros() {
+        let fifty_thousand = dec("52691").round_to_multiple(&Decimal::power_of_ten(3), RoundingMode::HalfUp);
+        assert_eq!(fifty_thousand.to_string(), "53000");
+        assert!(!fifty_thousand.has_significant_trailing_zeros());
+
+        let fifty_thousand = dec("50012").round_to_multiple(&Decimal::power_of_ten(3), RoundingMode::HalfUp);
+        assert_eq!(fifty_thousand.to_string(), "50000");
+        assert!(fifty_thousand.has_significant_trailing_zeros());
+        assert!(!dec("5.00").has_significant_trailing_zeros());
+    }
+
+    #[test]
+    fn multiplies_and_divides() {
+        assert_eq!(dec("3.2").mul(&dec("-4")).to_string(), "-12.8");
+        assert_eq!(dec("2.5").mul(&dec("4")), dec("10"));
+        assert_eq!(dec("3.20").shifted(2).to_string(), "320");
+        assert_eq!(dec("3.2").shifted(-4).to_string(), "0.00032");
+        assert_eq!(dec("320000").trim_whole_number_zeros(), dec("320000"));
+        assert_eq!(dec("320000").trim_whole_number_zeros().shifted(-5).to_string(), "3.2");
+        assert_eq!(dec("3200.0").trim_whole_number_zeros().to_string(), "3200.0");
+
+        let exact = |a: &str, b: &str| dec(a).div(&dec(b), 10).map(|(q, exact)| (q.to_string(), exact));
+        assert_eq!(exact("6.3", "2"), Some(("3.15".to_string(), true)));
+        assert_eq!(exact("1", "8"), Some(("0.125".to_string(), true)));
+        assert_eq!(exact("-12", "0.4"), Some(("-30".to_string(), true)));
+        assert_eq!(exact("1", "3"), Some(("0.3333333333".to_string(), false)));
+        assert_eq!(exact("2", "3"), Some(("0.6666666667".to_string(), false)));
+        assert_eq!(exact("-20", "7"), Some(("-2.857142857".to_string(), false)));
+        assert_eq!(exact("1", "0"), None);
+    }
+
+    #[test]
+    fn calculates_exactly() {
+        assert_eq!(dec("0.1").add(&dec("0.2")).to_string(), "0.3");
+        assert_eq!(dec("6.5").sub(&dec("0.05")).to_string(), "6.45");
+        assert_eq!(dec("0.1").half().to_string(), "0.05");
+        assert_eq!(dec("2.500").trim().to_string(), "2.5");
+        assert_eq!(dec("0.00097151").leading_digit_position(), Some(-4));
+        assert!(dec("1000").is_power_of_ten() && dec("-0.010").is_power_of_ten());
+        assert!(!dec("2000").is_power_of_ten() && !dec("0").is_power_of_ten());
+        assert_eq!(dec("2.50").decimal_places(), 2);
+        assert_eq!(dec("1.5e3").decimal_places(), 0);
+        assert_eq!(Decimal::from_f64(0.1 + 0.2), Some(dec("0.30000000000000004")));
+    }
+}
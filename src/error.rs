@@ -0,0 +1,187 @@
+//! The errors reported by every calculator.
+//!
+//! Errors caused by a particular part of the input carry a [`Span`] pointing at it, so the CLI,
+//! the GUI and the JSON output can all show the user exactly what went wrong and where.
+
+use std::fmt;
+
+use serde::Serialize;
+
+/// A range of byte offsets into the input, marking the part that caused an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+
+    /// The span of `part` within `input`, where `part` is a slice taken from `input` (for
+    /// example by `split` or `trim`).
+    pub fn of(part: &str, input: &str) -> Span {
+        let start = part.as_ptr() as usize - input.as_ptr() as usize;
+        debug_assert!(start + part.len() <= input.len(), "part is not a slice of input");
+        Span { start, end: start + part.len() }
+    }
+
+    /// Moves the span along by `offset` bytes, for when a slice of the input was parsed on its
+    /// own.
+    pub fn shifted(self, offset: usize) -> Span {
+        Span { start: self.start + offset, end: self.end + offset }
+    }
+}
+
+/// Everything that can go wrong when reading or working out a calculation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MathError {
+    /// Nothing was entered.
+    EmptyInput,
+    /// Part of the input is not a valid number or symbol.
+    InvalidToken { token: String, position: Span },
+    /// A fraction was written with a denominator of zero.
+    ZeroDenominator { position: Option<Span> },
+    /// The calculation needs a division by zero.
+    DivisionByZero,
+    /// A value is too large to work with.
+    Overflow,
+    /// An equation has no unknown value to find.
+    NoUnknown,
+    /// An equation has more than one unknown value.
+    MultipleUnknowns { positions: Vec<Span> },
+    /// A calculation did not have two values separated by an operator.
+    MissingOperator,
+    /// An equation was not written as two fractions separated by `=`.
+    NotAnEquation,
+    /// A number that must be greater than zero was not.
+    NotPositive,
+    /// A number was below the smallest value the calculator accepts.
+    TooSmall { minimum: i64 },
+    /// A number was above the largest value the calculator accepts.
+    TooLarge { maximum: u64 },
+    /// A square root of a negative number was asked for.
+    NoRealSquareRoot,
+    /// An expression could not be evaluated.
+    InvalidExpression { message: String },
+}
+
+impl MathError {
+    /// The parts of the input that caused the error, if known.
+    pub fn positions(&self) -> Vec<Span> {
+        match self {
+            MathError::InvalidToken { position, .. } => vec![*position],
+            MathError::ZeroDenominator { position: Some(position) } => vec![*position],
+            MathError::MultipleUnknowns { positions } => positions.clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Moves every span along by `offset` bytes, for when a slice of the input was parsed on its
+    /// own and the error should point into the whole input.
+    pub fn shifted(self, offset: usize) -> MathError {
+        match self {
+            MathError::InvalidToken { token, position } => {
+                MathError::InvalidToken { token, position: position.shifted(offset) }
+            }
+            MathError::ZeroDenominator { position } => {
+                MathError::ZeroDenominator { position: position.map(|span| span.shifted(offset)) }
+            }
+            MathError::MultipleUnknowns { positions } => MathError::MultipleUnknowns {
+                positions: positions.into_iter().map(|span| span.shifted(offset)).collect(),
+            },
+            other => other,
+        }
+    }
+
+    /// Describes the error and, when its position is known, repeats the input with the
+    /// offending part underlined:
+    ///
+    /// ```text
+    /// could not read '3/4x'
+    ///   12/30 = 3/4x
+    ///           ^^^^
+    /// ```
+    pub fn report(&self, input: &str) -> String {
+        let positions = self.positions();
+        let mut report = self.to_string();
+        if positions.is_empty() || positions.iter().any(|span| span.end > input.len()) {
+            return report;
+        }
+
+        // Work in characters rather than bytes so that symbols such as × line up.
+        let mut underline = String::new();
+        for (i, _) in input.char_indices() {
+            if positions.iter().any(|span| span.start <= i && i < span.end) {
+                underline.push('^');
+            } else if positions.iter().any(|span| span.start > i) {
+                underline.push(' ');
+            }
+        }
+        report.push_str(&format!("\n  {}\n  {}", input, underline.trim_end()));
+        report
+    }
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MathError::EmptyInput => write!(f, "nothing was entered"),
+            MathError::InvalidToken { token, .. } => write!(f, "could not read '{}'", token),
+            MathError::ZeroDenominator { .. } => write!(f, "the denominator cannot be zero"),
+            MathError::DivisionByZero => write!(f, "you cannot divide by zero"),
+            MathError::Overflow => write!(f, "the numbers are too large to calculate with"),
+            MathError::NoUnknown => write!(f, "there is no unknown to find; write the missing value as x"),
+            MathError::MultipleUnknowns { positions } if positions.len() > 1 => {
+                write!(f, "there are {} unknowns, but only one value can be found", positions.len())
+            }
+            MathError::MultipleUnknowns { .. } => write!(f, "only one value can be found at a time"),
+            MathError::MissingOperator => write!(f, "expected two fractions separated by +, -, × or ÷"),
+            MathError::NotAnEquation => write!(f, "expected an equation in the format a/b = c/d"),
+            MathError::NotPositive => write!(f, "the number must be greater than 0"),
+            MathError::TooSmall { minimum } => write!(f, "the number must be at least {}", minimum),
+            MathError::TooLarge { maximum } => write!(f, "the number must be at most {}", maximum),
+            MathError::NoRealSquareRoot => write!(f, "negative numbers do not have a square root"),
+            MathError::InvalidExpression { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for MathError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_spans_of_slices() {
+        let input = "12/30 = 4/x";
+        let (_, right) = input.split_once('=').unwrap();
+        assert_eq!(Span::of(right.trim(), input), Span::new(8, 11));
+        assert_eq!(Span::new(0, 2).shifted(8), Span::new(8, 10));
+    }
+
+    #[test]
+    fn underlines_the_offending_input() {
+        let error = MathError::InvalidToken { token: "3/4x".to_string(), position: Span::new(8, 12) };
+        assert_eq!(error.report("12/30 = 3/4x"), "could not read '3/4x'\n  12/30 = 3/4x\n          ^^^^");
+        assert_eq!(MathError::Overflow.report("anything"), "the numbers are too large to calculate with");
+    }
+
+    #[test]
+    fn underlines_every_unknown() {
+        let error = MathError::MultipleUnknowns { positions: vec![Span::new(0, 1), Span::new(2, 3)] };
+        assert_eq!(error.report("a/b = 4/8"), "there are 2 unknowns, but only one value can be found\n  a/b = 4/8\n  ^ ^");
+    }
+
+    #[test]
+    fn serialises_with_kind() {
+        let error = MathError::ZeroDenominator { position: Some(Span::new(2, 3)) };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({"kind": "zero_denominator", "position": {"start": 2, "end": 3}})
+        );
+    }
+}
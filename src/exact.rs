@@ -0,0 +1,320 @@
+//! Exact answers to expressions, as fractions and surds.
+//!
+//! Whole numbers, decimals and fractions are worked with as [`Fraction`]s, and square roots
+//! that do not come out exactly are kept as surds in their simplest form, so `1/3 + 1/6` is
+//! `1/2`, `2^-3` is `1/8` and `√8 + √2` is `3√2`, as a non-calculator paper expects. Anything
+//! without an exact form, such as `π` or `sin(1)`, is left to the decimal evaluation.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use num_traits::ToPrimitive;
+use serde::{Serialize, Serializer};
+
+use crate::decimal::Decimal;
+use crate::error::MathError;
+use crate::expressions::evaluate_expr;
+use crate::fractions::Fraction;
+use crate::parser::{BinaryOp, Expr, ExprKind};
+use crate::primes::prime_factorization;
+
+/// Whole-number powers up to this size are worked out exactly, here and in the BODMAS working.
+pub const LARGEST_EXACT_EXPONENT: u64 = 100;
+
+/// An exact number of the form `a + b√m + c√n + …`, where `a`, `b`, `c` are fractions and
+/// `m`, `n` are square-free whole numbers greater than 1.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Exact {
+    /// The coefficient of each root, keyed by the number under it, with 1 for the rational
+    /// part. Terms with a coefficient of zero are left out.
+    terms: BTreeMap<u64, Fraction>,
+}
+
+impl Exact {
+    /// The number `√radicand × coefficient`, where `radicand` is already square-free.
+    fn term(radicand: u64, coefficient: Fraction) -> Exact {
+        let mut terms = BTreeMap::new();
+        if !coefficient.is_zero() {
+            terms.insert(radicand, coefficient);
+        }
+        Exact { terms }
+    }
+
+    /// The value as a fraction, or `None` if it has a surd part.
+    pub fn to_fraction(&self) -> Option<Fraction> {
+        match self.terms.iter().next() {
+            None => Some(Fraction::ZERO),
+            Some((1, coefficient)) if self.terms.len() == 1 => Some(*coefficient),
+            _ => None,
+        }
+    }
+
+    /// The value as a decimal, or `None` if it is a surd or a fraction whose decimal does not end,
+    /// such as 1/3.
+    pub fn to_decimal(&self) -> Option<Decimal> {
+        let (decimal, ends) = self.to_fraction()?.to_decimal();
+        ends.then_some(decimal)
+    }
+
+    /// The nearest `f64` to the value.
+    pub fn to_f64(&self) -> f64 {
+        self.terms.iter().map(|(radicand, coefficient)| coefficient.to_f64() * (*radicand as f64).sqrt()).sum()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Adds two numbers, returning `None` on overflow.
+    pub fn checked_add(&self, other: &Exact) -> Option<Exact> {
+        let mut terms = self.terms.clone();
+        for (radicand, coefficient) in &other.terms {
+            let sum = terms.get(radicand).copied().unwrap_or(Fraction::ZERO).checked_add(*coefficient)?;
+            if sum.is_zero() {
+                terms.remove(radicand);
+            } else {
+                terms.insert(*radicand, sum);
+            }
+        }
+        Some(Exact { terms })
+    }
+
+    /// Subtracts `other` from this number, returning `None` on overflow.
+    pub fn checked_sub(&self, other: &Exact) -> Option<Exact> {
+        self.checked_add(&other.checked_neg()?)
+    }
+
+    /// The number with its sign changed, or `None` on overflow.
+    pub fn checked_neg(&self) -> Option<Exact> {
+        let terms = self.terms.iter().map(|(radicand, coefficient)| Some((*radicand, coefficient.checked_neg()?)));
+        Some(Exact { terms: terms.collect::<Option<_>>()? })
+    }
+
+    /// Multiplies two numbers, simplifying any new surds, so `√2 × √6` is `2√3`. Returns `None`
+    /// on overflow.
+    pub fn checked_mul(&self, other: &Exact) -> Option<Exact> {
+        let mut product = Exact::from(Fraction::ZERO);
+        for (m, a) in &self.terms {
+            for (n, b) in &other.terms {
+                let (outside, inside) = simplify_root(m.checked_mul(*n)?)?;
+                let outside = Fraction::from_integer(outside.try_into().ok()?);
+                let coefficient = a.checked_mul(*b)?.checked_mul(outside)?;
+                product = product.checked_add(&Exact::term(inside, coefficient))?;
+            }
+        }
+        Some(product)
+    }
+
+    /// Divides this number by `other`, rationalising the denominator, so `1 ÷ √2` is `√2/2`.
+    /// Returns `None` if `other` is zero, has more than one surd, or on overflow.
+    pub fn checked_div(&self, other: &Exact) -> Option<Exact> {
+        self.checked_mul(&other.recip()?)
+    }
+
+    /// One divided by the number. A denominator `a + b√n` is rationalised by multiplying top
+    /// and bottom by `a - b√n`, since `(a + b√n)(a - b√n) = a² - b²n` has no surd.
+    fn recip(&self) -> Option<Exact> {
+        let surds: Vec<(u64, Fraction)> =
+            self.terms.iter().filter(|(radicand, _)| **radicand != 1).map(|(radicand, b)| (*radicand, *b)).collect();
+        let rational = self.terms.get(&1).copied().unwrap_or(Fraction::ZERO);
+        match surds.as_slice() {
+            [] => Some(Exact::from(rational.recip()?)),
+            [(n, b)] => {
+                let conjugate = Exact::from(rational).checked_sub(&Exact::term(*n, *b))?;
+                let denominator = rational.checked_mul(rational)?.checked_sub(
+                    b.checked_mul(*b)?.checked_mul(Fraction::from_integer((*n).try_into().ok()?))?,
+                )?;
+                conjugate.checked_mul(&Exact::from(denominator.recip()?))
+            }
+            _ => None,
+        }
+    }
+
+    /// The number to a power. Whole-number powers are worked out by repeated multiplication,
+    /// and a power with a denominator of 2 takes the square root first, so `4^(3/2)` is 8.
+    /// Returns `None` for any other power or on overflow.
+    pub fn checked_pow(&self, exponent: &Exact) -> Option<Exact> {
+        let exponent = exponent.to_fraction()?;
+        let (base, power) = match exponent.denominator() {
+            1 => (self.clone(), exponent.numerator()),
+            2 => (self.sqrt()?, exponent.numerator()),
+            _ => return None,
+        };
+        if power.unsigned_abs() > LARGEST_EXACT_EXPONENT {
+            return None;
+        }
+        let base = if power < 0 { base.recip()? } else { base };
+        let mut answer = Exact::from(Fraction::ONE);
+        for _ in 0..power.unsigned_abs() {
+            answer = answer.checked_mul(&base)?;
+        }
+        Some(answer)
+    }
+
+    /// The square root of a fraction, as a surd in its simplest form: `√(p/q)` is written
+    /// `√(pq)/q`, then square factors are taken out, so `√(1/2)` is `√2/2` and `√12` is `2√3`.
+    /// Returns `None` for a number that is not a fraction or is negative, or on overflow.
+    pub fn sqrt(&self) -> Option<Exact> {
+        let fraction = self.to_fraction()?;
+        let p = u64::try_from(fraction.numerator()).ok()?;
+        let q = u64::try_from(fraction.denominator()).ok()?;
+        let (outside, inside) = simplify_root(p.checked_mul(q)?)?;
+        let coefficient = Fraction::new(outside.try_into().ok()?, q.try_into().ok()?)?;
+        Some(Exact::term(inside, coefficient))
+    }
+}
+
+impl From<Fraction> for Exact {
+    fn from(fraction: Fraction) -> Self {
+        Exact::term(1, fraction)
+    }
+}
+
+impl fmt::Display for Exact {
+    /// Writes the number with the whole or fractional part first, then each surd, e.g.
+    /// `1/2`, `3√2/4` or `1 - √3`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.terms.is_empty() {
+            return write!(f, "0");
+        }
+        for (i, (radicand, coefficient)) in self.terms.iter().enumerate() {
+            match (i, coefficient.is_negative()) {
+                (0, true) => write!(f, "-")?,
+                (0, false) => {}
+                (_, true) => write!(f, " - ")?,
+                (_, false) => write!(f, " + ")?,
+            }
+            // The sign has been written, so only the size of the numerator is needed. It is
+            // taken as a `u64` because the size of `i64::MIN` does not fit in an `i64`.
+            let numerator = coefficient.numerator().unsigned_abs();
+            if *radicand == 1 {
+                write!(f, "{}", numerator)?;
+            } else {
+                if numerator != 1 {
+                    write!(f, "{}", numerator)?;
+                }
+                write!(f, "√{}", radicand)?;
+            }
+            if coefficient.denominator() != 1 {
+                write!(f, "/{}", coefficient.denominator())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Exact {
+    /// Exact answers are written as strings, such as `"3√2/4"`.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Writes `√n` as `a√b` with `b` square-free, returning `(a, b)`, so 12 gives `(2, 3)`. Returns
+/// `None` if `a` does not fit in an `i64`.
+fn simplify_root(n: u64) -> Option<(u64, u64)> {
+    if n == 0 {
+        return Some((0, 1));
+    }
+    let (mut outside, mut inside) = (1_u64, 1_u64);
+    for (prime, exponent) in prime_factorization(n) {
+        let prime = u64::try_from(prime).ok()?;
+        outside = outside.checked_mul(prime.checked_pow(exponent / 2)?)?;
+        inside = inside.checked_mul(prime.checked_pow(exponent % 2)?)?;
+    }
+    i64::try_from(outside).ok()?;
+    Some((outside, inside))
+}
+
+/// The fraction a decimal stands for, such as 1/4 for `0.25`, or `None` if it is too large.
+fn fraction_of(number: &Decimal) -> Option<Fraction> {
+    let places = number.decimal_places();
+    let numerator = number.shifted(i32::try_from(places).ok()?).to_i64()?;
+    Fraction::new(numerator, 10_i64.checked_pow(places)?)
+}
+
+/// Works out an expression exactly, returning `None` if it has no exact form that can be found,
+/// for example because it uses `π`, a function such as `sin` or a cube root.
+///
+/// Errors such as division by zero or an unknown name are found by the ordinary evaluation, so
+/// an expression is only worked out exactly once it is known to have an answer.
+pub fn evaluate_exact(expr: &Expr) -> Result<Option<Exact>, MathError> {
+    evaluate_expr(expr)?;
+    Ok(exact(expr))
+}
+
+fn exact(expr: &Expr) -> Option<Exact> {
+    match &expr.kind {
+        ExprKind::Number(number) => fraction_of(number).map(Exact::from),
+        ExprKind::Negate(operand) => exact(operand)?.checked_neg(),
+        ExprKind::Brackets(inner) => exact(inner),
+        ExprKind::Binary { op, left, right, .. } => {
+            let (left, right) = (exact(left)?, exact(right)?);
+            match op {
+                BinaryOp::Add => left.checked_add(&right),
+                BinaryOp::Subtract => left.checked_sub(&right),
+                BinaryOp::Multiply => left.checked_mul(&right),
+                BinaryOp::Divide => left.checked_div(&right),
+                BinaryOp::Power => left.checked_pow(&right),
+                BinaryOp::Remainder => None,
+            }
+        }
+        ExprKind::Index(base, power) => {
+            exact(base)?.checked_pow(&Exact::from(Fraction::from_integer(i64::from(*power))))
+        }
+        ExprKind::SquareRoot(operand) => exact(operand)?.sqrt(),
+        ExprKind::Call { name, arguments } if name == "sqrt" && arguments.len() == 1 => exact(&arguments[0])?.sqrt(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn exactly(expression: &str) -> Option<String> {
+        evaluate_exact(&parse(expression).unwrap()).unwrap().map(|exact| exact.to_string())
+    }
+
+    #[test]
+    fn keeps_fractions_exact() {
+        assert_eq!(exactly("1/3 + 1/6"), Some("1/2".to_string()));
+        assert_eq!(exactly("2^-3"), Some("1/8".to_string()));
+        assert_eq!(exactly("0.1 + 0.2"), Some("3/10".to_string()));
+        assert_eq!(exactly("(2/3)² ÷ 4 - 1"), Some("-8/9".to_string()));
+        assert_eq!(exactly("4^(3/2)"), Some("8".to_string()));
+        let smallest = "-4611686018427387904 - 4611686018427387904";
+        assert_eq!(exactly(smallest), Some(i64::MIN.to_string()));
+        assert_eq!(exactly(&format!("-({})", smallest)), None);
+    }
+
+    #[test]
+    fn writes_fractions_that_end_as_decimals() {
+        let decimal = |expression: &str| evaluate_exact(&parse(expression).unwrap()).unwrap().unwrap().to_decimal();
+        assert_eq!(decimal("7/2"), Some("3.5".parse().unwrap()));
+        assert_eq!(decimal("-3/8"), Some("-0.375".parse().unwrap()));
+        assert_eq!(decimal("1/2^20"), Some("0.00000095367431640625".parse().unwrap()));
+        assert_eq!(decimal("123456789/1024"), Some("120563.2705078125".parse().unwrap()));
+        assert_eq!(decimal("1/3"), None);
+        assert_eq!(decimal("√2"), None);
+    }
+
+    #[test]
+    fn simplifies_surds() {
+        assert_eq!(exactly("√8 + √2"), Some("3√2".to_string()));
+        assert_eq!(exactly("√2 × √6"), Some("2√3".to_string()));
+        assert_eq!(exactly("3/√2"), Some("3√2/2".to_string()));
+        assert_eq!(exactly("(1 + √3)²"), Some("4 + 2√3".to_string()));
+        assert_eq!(exactly("1 / (1 + √2)"), Some("-1 + √2".to_string()));
+        assert_eq!(exactly("sqrt(1/2)"), Some("√2/2".to_string()));
+    }
+
+    #[test]
+    fn leaves_inexact_values_to_decimals() {
+        assert_eq!(exactly("2pi"), None);
+        assert_eq!(exactly("8^(1/3)"), None);
+        assert_eq!(exactly("1 / (√2 + √3 + 1)"), None);
+        assert_eq!(evaluate_exact(&parse("1/0").unwrap()), Err(MathError::DivisionByZero));
+    }
+}
@@ -0,0 +1,421 @@
+//! Expression evaluation for the BODMAS and estimation calculators.
+//!
+//! Expressions are read by [`crate::parser`], so every calculator accepts the same symbols and
+//! reports the same errors.
+
+use std::f64::consts::{E, PI};
+
+use crate::bounds::Accuracy;
+use crate::decimal::{Decimal, RoundingMode};
+use crate::error::{MathError, Span};
+use crate::parser::{parse, BinaryOp, Expr, ExprKind};
+use crate::rounding::round_to_significant_figures;
+use crate::standard_form::{StandardForm, DIVISION_FIGURES};
+
+/// The constants [`evaluate_expr`] knows.
+pub const CONSTANTS: [&str; 3] = ["pi", "π", "e"];
+
+/// The functions [`evaluate_expr`] knows.
+pub const FUNCTIONS: [&str; 17] = [
+    "sqrt", "cbrt", "abs", "exp", "ln", "log", "sin", "cos", "tan", "asin", "acos", "atan", "floor", "ceil", "round",
+    "min", "max",
+];
+
+/// Evaluates a mathematical expression, respecting the BODMAS order of operations.
+pub fn evaluate(expression: &str) -> Result<f64, MathError> {
+    evaluate_expr(&parse(expression)?)
+}
+
+/// Works out the value of an expression that has already been read.
+///
+/// The constants `pi` (or `π`) and `e` are known, along with the functions `sqrt`, `cbrt`,
+/// `abs`, `exp`, `ln`, `log` (to base 10), `sin`, `cos`, `tan`, `asin`, `acos`, `atan` (in
+/// radians), `floor`, `ceil`, `round`, `min` and `max`.
+pub fn evaluate_expr(expr: &Expr) -> Result<f64, MathError> {
+    let value = match &expr.kind {
+        ExprKind::Number(number) => number.to_f64(),
+        ExprKind::Name(name) => match name.as_str() {
+            "pi" | "π" => PI,
+            "e" => E,
+            _ => return Err(MathError::InvalidToken { token: name.clone(), position: expr.span }),
+        },
+        ExprKind::Negate(operand) => -evaluate_expr(operand)?,
+        ExprKind::Binary { op, left, right, .. } => apply(*op, evaluate_expr(left)?, evaluate_expr(right)?)?,
+        ExprKind::Index(base, power) => evaluate_expr(base)?.powf(f64::from(*power)),
+        ExprKind::SquareRoot(operand) => square_root(evaluate_expr(operand)?)?,
+        ExprKind::Call { name, arguments } => {
+            let arguments = arguments.iter().map(evaluate_expr).collect::<Result<Vec<f64>, MathError>>()?;
+            call(name, &arguments, expr.span)?
+        }
+        ExprKind::Brackets(inner) => evaluate_expr(inner)?,
+    };
+    if value.is_nan() {
+        Err(MathError::InvalidExpression { message: "the answer is not a real number".to_string() })
+    } else if value.is_infinite() {
+        Err(MathError::Overflow)
+    } else {
+        Ok(value)
+    }
+}
+
+/// Carries out one operation. A negative power of zero is a division by zero, as 0^-1 is 1/0.
+fn apply(op: BinaryOp, left: f64, right: f64) -> Result<f64, MathError> {
+    match op {
+        BinaryOp::Add => Ok(left + right),
+        BinaryOp::Subtract => Ok(left - right),
+        BinaryOp::Multiply => Ok(left * right),
+        BinaryOp::Divide | BinaryOp::Remainder if right == 0.0 => Err(MathError::DivisionByZero),
+        BinaryOp::Power if left == 0.0 && right < 0.0 => Err(MathError::DivisionByZero),
+        BinaryOp::Divide => Ok(left / right),
+        BinaryOp::Remainder => Ok(left % right),
+        BinaryOp::Power => Ok(left.powf(right)),
+    }
+}
+
+fn square_root(value: f64) -> Result<f64, MathError> {
+    if value < 0.0 { Err(MathError::NoRealSquareRoot) } else { Ok(value.sqrt()) }
+}
+
+/// Calls one of the built-in functions, where `span` covers the call as written.
+fn call(name: &str, arguments: &[f64], span: Span) -> Result<f64, MathError> {
+    let function: fn(f64) -> f64 = match name {
+        "sqrt" => return one_argument(name, arguments).and_then(square_root),
+        "min" | "max" => {
+            let (first, rest) = arguments.split_first().expect("a call has at least one argument");
+            let pick = if name == "min" { f64::min } else { f64::max };
+            return Ok(rest.iter().copied().fold(*first, pick));
+        }
+        "cbrt" => f64::cbrt,
+        "abs" => f64::abs,
+        "exp" => f64::exp,
+        "ln" => f64::ln,
+        "log" => f64::log10,
+        "sin" => f64::sin,
+        "cos" => f64::cos,
+        "tan" => f64::tan,
+        "asin" => f64::asin,
+        "acos" => f64::acos,
+        "atan" => f64::atan,
+        "floor" => f64::floor,
+        "ceil" => f64::ceil,
+        "round" => f64::round,
+        _ => {
+            let position = Span::new(span.start, span.start + name.len());
+            return Err(MathError::InvalidToken { token: name.to_string(), position });
+        }
+    };
+    one_argument(name, arguments).map(function)
+}
+
+fn one_argument(name: &str, arguments: &[f64]) -> Result<f64, MathError> {
+    match arguments {
+        [value] => Ok(*value),
+        _ => Err(MathError::InvalidExpression { message: format!("{} takes one value, e.g. {}(2)", name, name) }),
+    }
+}
+
+/// Formats a result without a trailing `.0` when it is a whole number. Whole numbers too large for
+/// an `i64` are given as [`format_approximate`] gives them, since an `f64` only holds the first
+/// 16 or so of their digits.
+pub fn format_result(result: f64) -> String {
+    if result.fract() != 0.0 {
+        result.to_string()
+    } else if result.abs() < I64_BOUND {
+        (result as i64).to_string()
+    } else {
+        format_approximate(result)
+    }
+}
+
+/// 2^63, the first whole number an `i64` cannot hold.
+const I64_BOUND: f64 = 9_223_372_036_854_775_808.0;
+
+/// Formats a result to 10 significant figures, which hides the error `f64` arithmetic leaves in
+/// answers such as 970.0000000000001. Whole numbers too large for an `i64` are written in
+/// standard form, so the zeros after those figures are not taken for digits: 2^100 is
+/// `1.2676506 × 10³⁰`.
+pub fn format_approximate(result: f64) -> String {
+    match rounded_decimal(result) {
+        Some(rounded) if result.abs() >= I64_BOUND => {
+            StandardForm { mantissa: rounded, power: 0 }.normalised().to_string()
+        }
+        Some(rounded) => rounded.to_string(),
+        None => result.to_string(),
+    }
+}
+
+/// A result rounded to 10 significant figures as [`format_approximate`] writes it, or `None` if it
+/// is not finite.
+pub fn rounded_decimal(result: f64) -> Option<Decimal> {
+    Decimal::from_f64(result)
+        .and_then(|result| round_to_significant_figures(&result, DIVISION_FIGURES, RoundingMode::HalfUp).ok())
+        .map(|result| result.trim())
+}
+
+/// An expression with every number rounded, worked out next to the answer from the numbers as
+/// written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    /// The expression with the rounded numbers substituted in.
+    pub rounded_expression: String,
+    /// Each number as written in the original expression, paired with its rounded value.
+    pub roundings: Vec<(String, Decimal)>,
+    /// The answer from the rounded numbers, or `None` when rounding left a calculation that
+    /// cannot be done, such as dividing by a number that rounded to 0.
+    pub estimate: Option<f64>,
+    /// The answer from the numbers as written.
+    pub exact: f64,
+    /// Anything about the rounding that makes the estimate misleading.
+    pub warnings: Vec<String>,
+}
+
+impl Estimate {
+    /// How far the estimate is from the exact answer, as a percentage of the exact answer. There
+    /// is none when the exact answer is 0 or the estimate could not be worked out.
+    pub fn percentage_error(&self) -> Option<f64> {
+        match self.estimate {
+            Some(estimate) if self.exact != 0.0 => Some((estimate - self.exact).abs() / self.exact.abs() * 100.0),
+            _ => None,
+        }
+    }
+}
+
+/// Rounds every number in an expression to one significant figure, using the given rule, and
+/// works out the estimate.
+pub fn round_expression(expression: &str, mode: RoundingMode) -> Result<Estimate, MathError> {
+    estimate(expression, &Accuracy::SignificantFigures(1), mode)
+}
+
+/// Rounds every number in an expression to the given accuracy, then works out the rounded
+/// expression alongside the original one.
+///
+/// Each number is rounded where it sits in the expression, so the order of operations is kept
+/// and a number is never confused with another that shares its digits. The rounded numbers are
+/// also substituted where they were written, so the rest of the expression reads exactly as
+/// typed. Errors in the original expression are reported; a rounded expression that cannot be
+/// worked out is given a warning instead.
+pub fn estimate(expression: &str, accuracy: &Accuracy, mode: RoundingMode) -> Result<Estimate, MathError> {
+    let expression = expression.trim();
+    let expr = parse(expression)?;
+    let exact = evaluate_expr(&expr)?;
+
+    let mut rounded = expr.clone();
+    round_numbers(&mut rounded, accuracy, mode)?;
+
+    let mut rounded_expression = expression.to_string();
+    let mut roundings = Vec::new();
+    // Replacing from the right leaves the positions of the numbers still to be replaced alone.
+    for (number, span) in expr.numbers().iter().rev() {
+        let rounded_number = round_number(number, accuracy, mode)?;
+        rounded_expression.replace_range(span.start..span.end, &rounded_number.to_string());
+        roundings.push((expression[span.start..span.end].to_string(), rounded_number));
+    }
+    roundings.reverse();
+
+    let mut warnings = Vec::new();
+    check_rounding(expression, &expr, &rounded, &mut warnings);
+    let estimate = match evaluate_expr(&rounded) {
+        Ok(estimate) => Some(estimate),
+        // The divisor that rounded to 0 has already been pointed out.
+        Err(MathError::DivisionByZero) => None,
+        Err(e) => {
+            warnings.push(format!("the estimate cannot be worked out: {}", e));
+            None
+        }
+    };
+
+    Ok(Estimate {
+        rounded_expression,
+        roundings,
+        estimate,
+        exact,
+        warnings,
+    })
+}
+
+/// Rounds a number to the accuracy. Significant figures are counted from each number's own
+/// first digit, so 9.7 and 326 both round to one digit.
+fn round_number(n: &Decimal, accuracy: &Accuracy, mode: RoundingMode) -> Result<Decimal, MathError> {
+    match accuracy {
+        Accuracy::SignificantFigures(figures) => round_to_significant_figures(n, *figures, mode),
+        _ => Ok(n.round_to_multiple(&accuracy.step(n)?, mode)),
+    }
+}
+
+/// Rounds every number in an expression in place.
+fn round_numbers(expr: &mut Expr, accuracy: &Accuracy, mode: RoundingMode) -> Result<(), MathError> {
+    if let ExprKind::Number(number) = &mut expr.kind {
+        *number = round_number(number, accuracy, mode)?;
+    }
+    for child in expr.children_mut() {
+        round_numbers(child, accuracy, mode)?;
+    }
+    Ok(())
+}
+
+/// Looks for roundings that spoil an estimate: a number rounding to 0, a divisor rounding to 0,
+/// and the two sides of a subtraction rounding to the same value. `rounded` is `original` with
+/// its numbers rounded, so the two are walked together.
+fn check_rounding(input: &str, original: &Expr, rounded: &Expr, warnings: &mut Vec<String>) {
+    let written = |expr: &Expr| input[expr.span.start..expr.span.end].to_string();
+    let value = |expr: &Expr| evaluate_expr(expr).ok();
+
+    match (&original.kind, &rounded.kind) {
+        (ExprKind::Number(number), ExprKind::Number(rounded_number))
+            if rounded_number.is_zero() && !number.is_zero() =>
+        {
+            warnings.push(format!("{} rounds to 0", written(original)));
+        }
+        (
+            ExprKind::Binary { op: BinaryOp::Divide | BinaryOp::Remainder, left, right, .. },
+            ExprKind::Binary { left: rounded_left, right: rounded_right, .. },
+        ) if value(rounded_right) == Some(0.0) && value(right) != Some(0.0) => {
+            warnings.push(format!("the divisor {} rounds to 0, so there is no estimate", written(right)));
+            // A divisor that is a single number would otherwise be reported a second time.
+            if matches!(right.kind, ExprKind::Number(_)) {
+                check_rounding(input, left, rounded_left, warnings);
+                return;
+            }
+        }
+        (
+            ExprKind::Binary { op: BinaryOp::Subtract, left, right, .. },
+            ExprKind::Binary { left: rounded_left, right: rounded_right, .. },
+        ) => {
+            let difference = value(rounded_left).zip(value(rounded_right));
+            if let Some((left_value, right_value)) = difference
+                && left_value == right_value
+                && value(left) != value(right)
+            {
+                warnings.push(format!(
+                    "{} and {} both round to {}, so the difference between them is lost",
+                    written(left),
+                    written(right),
+                    format_result(left_value)
+                ));
+            }
+        }
+        _ => {}
+    }
+
+    for (child, rounded_child) in original.children().into_iter().zip(rounded.children()) {
+        check_rounding(input, child, rounded_child, warnings);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluates_with_bodmas() {
+        assert_eq!(evaluate("20 - 3 * 4").unwrap(), 8.0);
+        assert_eq!(evaluate("30 / (15 - 12)").unwrap(), 10.0);
+        assert!(evaluate("2 +").is_err());
+    }
+
+    #[test]
+    fn points_at_unknown_symbols() {
+        assert_eq!(
+            evaluate("2 + 3 $ 4"),
+            Err(MathError::InvalidToken { token: "$".to_string(), position: Span::new(6, 7) })
+        );
+        assert_eq!(
+            evaluate("2 * width"),
+            Err(MathError::InvalidToken { token: "width".to_string(), position: Span::new(4, 9) })
+        );
+    }
+
+    #[test]
+    fn accepts_written_symbols() {
+        assert_eq!(evaluate("2(3+4)").unwrap(), 14.0);
+        assert_eq!(evaluate("6 × 3² ÷ √9 − 1").unwrap(), 17.0);
+        assert_eq!(evaluate("2^3^2").unwrap(), 512.0);
+        assert_eq!(evaluate("-2^2").unwrap(), -4.0);
+        assert_eq!(evaluate("max(1, 7, 3) + 2pi").unwrap(), 7.0 + 2.0 * PI);
+    }
+
+    #[test]
+    fn reports_impossible_calculations() {
+        assert_eq!(evaluate("1 / (2 - 2)"), Err(MathError::DivisionByZero));
+        assert_eq!(evaluate("0^-1"), Err(MathError::DivisionByZero));
+        assert_eq!(evaluate("(1 - 1)^-0.5"), Err(MathError::DivisionByZero));
+        assert_eq!(evaluate("0^0.5"), Ok(0.0));
+        assert_eq!(evaluate("√-4"), Err(MathError::NoRealSquareRoot));
+        assert_eq!(
+            evaluate("1 + area(2)"),
+            Err(MathError::InvalidToken { token: "area".to_string(), position: Span::new(4, 8) })
+        );
+        assert!(matches!(evaluate("sin(1, 2)"), Err(MathError::InvalidExpression { .. })));
+    }
+
+    #[test]
+    fn formats_whole_results_without_decimal_point() {
+        assert_eq!(format_result(8.0), "8");
+        assert_eq!(format_result(2.5), "2.5");
+        assert_eq!(format_result(-4.0), "-4");
+        assert_eq!(format_result(2_f64.powi(62)), "4611686018427387904");
+        assert_eq!(format_result(2_f64.powi(63)), "9.223372037 × 10¹⁸");
+        assert_eq!(format_result(2_f64.powi(100)), "1.2676506 × 10³⁰");
+        assert_eq!(format_result(-(2_f64.powi(70))), "-1.180591621 × 10²¹");
+        assert_eq!(format_result(1e30), "1 × 10³⁰");
+        assert_eq!(format_approximate(9.7 * 326.0 / 3.26), "970");
+        assert_eq!(format_approximate(2.0 / 3.0), "0.6666666667");
+    }
+
+    #[test]
+    fn rounds_each_number_where_it_is_written() {
+        // 3.26 shares its digits with 326, and must not be changed along with it.
+        let estimate = round_expression("(9.7*326)/(3.26)", RoundingMode::HalfUp).unwrap();
+        assert_eq!(estimate.rounded_expression, "(10*300)/(3)");
+        assert_eq!(estimate.estimate, Some(1000.0));
+        assert!((estimate.exact - 970.0).abs() < 1e-9);
+        assert!((estimate.percentage_error().unwrap() - 3.092_783_505).abs() < 1e-6);
+
+        let estimate = estimate_expression("2.46 + 13.7", &Accuracy::DecimalPlaces(0));
+        assert_eq!(estimate.rounded_expression, "2 + 14");
+        let estimate = estimate_expression("2.46 + 13.7", &Accuracy::SignificantFigures(2));
+        assert_eq!(estimate.rounded_expression, "2.5 + 14");
+        assert!(estimate.warnings.is_empty());
+    }
+
+    #[test]
+    fn warns_about_misleading_roundings() {
+        let estimate = estimate_expression("7.2 / 0.04", &Accuracy::DecimalPlaces(1));
+        assert_eq!(estimate.estimate, None);
+        assert_eq!(estimate.warnings, vec!["the divisor 0.04 rounds to 0, so there is no estimate"]);
+        assert_eq!(estimate.percentage_error(), None);
+
+        let estimate = estimate_expression("6 / (2.1 - 1.9)", &Accuracy::SignificantFigures(1));
+        assert_eq!(
+            estimate.warnings,
+            vec![
+                "the divisor (2.1 - 1.9) rounds to 0, so there is no estimate",
+                "2.1 and 1.9 both round to 2, so the difference between them is lost",
+            ]
+        );
+
+        let estimate = estimate_expression("0.3 + 5", &Accuracy::Nearest(Decimal::from(1)));
+        assert_eq!(estimate.warnings, vec!["0.3 rounds to 0"]);
+        assert_eq!(estimate.estimate, Some(5.0));
+    }
+
+    fn estimate_expression(expression: &str, accuracy: &Accuracy) -> Estimate {
+        estimate(expression, accuracy, RoundingMode::HalfUp).unwrap()
+    }
+
+    #[test]
+    fn rounds_each_number_to_one_sf() {
+        let estimate = round_expression("(9.7*326)/(1.823*5.325)", RoundingMode::HalfUp).unwrap();
+        assert_eq!(estimate.rounded_expression, "(10*300)/(2*5)");
+        assert_eq!(
+            estimate.roundings,
+            vec![
+                ("9.7".to_string(), Decimal::from(10)),
+                ("326".to_string(), Decimal::from(300)),
+                ("1.823".to_string(), Decimal::from(2)),
+                ("5.325".to_string(), Decimal::from(5)),
+            ]
+        );
+        assert_eq!(evaluate(&estimate.rounded_expression).unwrap(), 300.0);
+    }
+}
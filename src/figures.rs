@@ -0,0 +1,196 @@
+//! Counting the significant figures and decimal places of a number as it is written.
+//!
+//! This answers the reverse of rounding to significant figures: given `0.003040` or `12000`,
+//! how many of the digits are significant? Only the written digits can tell, so the number is
+//! read as a string rather than as a value.
+
+use serde::Serialize;
+
+use crate::decimal::Decimal;
+use crate::error::MathError;
+
+/// The part a digit plays in the significant figures of a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DigitRole {
+    /// A non-zero digit, or a zero between two non-zero digits.
+    Significant,
+    /// A zero before the first non-zero digit, which only holds the place value.
+    LeadingZero,
+    /// A zero at the end of a number after the decimal point, which is significant.
+    TrailingZero,
+    /// A zero at the end of a whole number, which is significant only if the number was
+    /// rounded to it, so cannot be decided from the digits alone.
+    AmbiguousZero,
+}
+
+impl DigitRole {
+    /// Whether the digit definitely counts as a significant figure.
+    pub fn is_significant(&self) -> bool {
+        matches!(self, DigitRole::Significant | DigitRole::TrailingZero)
+    }
+
+    /// The letter marking the role under each digit: `s`, `l`, `t` or `?`.
+    pub fn marker(&self) -> char {
+        match self {
+            DigitRole::Significant => 's',
+            DigitRole::LeadingZero => 'l',
+            DigitRole::TrailingZero => 't',
+            DigitRole::AmbiguousZero => '?',
+        }
+    }
+}
+
+/// One digit of a number and the part it plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Digit {
+    pub digit: char,
+    pub role: DigitRole,
+}
+
+/// The significant figures and decimal places of a number, digit by digit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FigureCount {
+    /// The number as it was written.
+    pub number: String,
+    /// Every digit of the number, in order, leaving out the sign and the decimal point.
+    pub digits: Vec<Digit>,
+    /// The number of digits that are certainly significant.
+    pub significant_figures: usize,
+    /// The number of digits that may be significant, counting any ambiguous zeros. This is the
+    /// same as `significant_figures` unless the number is a whole number ending in zeros.
+    pub maximum_significant_figures: usize,
+    pub decimal_places: u32,
+}
+
+impl FigureCount {
+    /// Counts the figures in a number such as `0.003040`, `-12000` or `1.50e3`. In standard
+    /// form only the digits before the `e` are counted.
+    pub fn count(input: &str) -> Result<FigureCount, MathError> {
+        let value: Decimal = input.parse()?;
+        let number = input.trim();
+        let mantissa = number.split(['e', 'E']).next().unwrap_or(number);
+        let has_point = mantissa.contains('.');
+        let digits: Vec<char> = mantissa.chars().filter(char::is_ascii_digit).collect();
+
+        // The significant figures run from the first non-zero digit. Zeros after the last
+        // non-zero digit are significant after a decimal point, and ambiguous without one.
+        let first = digits.iter().position(|&d| d != '0');
+        let last = digits.iter().rposition(|&d| d != '0');
+        let digits: Vec<Digit> = digits
+            .iter()
+            .enumerate()
+            .map(|(i, &digit)| {
+                let role = match (first, last) {
+                    (Some(first), _) if i < first => DigitRole::LeadingZero,
+                    (Some(_), Some(last)) if i > last && has_point => DigitRole::TrailingZero,
+                    (Some(_), Some(last)) if i > last => DigitRole::AmbiguousZero,
+                    (Some(_), _) => DigitRole::Significant,
+                    // Zero itself has no significant figures.
+                    (None, _) => DigitRole::LeadingZero,
+                };
+                Digit { digit, role }
+            })
+            .collect();
+
+        let significant_figures = digits.iter().filter(|d| d.role.is_significant()).count();
+        let ambiguous = digits.iter().filter(|d| d.role == DigitRole::AmbiguousZero).count();
+        Ok(FigureCount {
+            number: number.to_string(),
+            digits,
+            significant_figures,
+            maximum_significant_figures: significant_figures + ambiguous,
+            decimal_places: value.decimal_places(),
+        })
+    }
+
+    /// The number of digits with the given role.
+    pub fn count_of(&self, role: DigitRole) -> usize {
+        self.digits.iter().filter(|d| d.role == role).count()
+    }
+
+    /// Marks the role of each digit underneath the number, keeping the sign and the decimal
+    /// point in line and stopping at any `e`:
+    ///
+    /// ```text
+    /// 0.003040
+    /// l.llssst
+    /// ```
+    pub fn markers(&self) -> String {
+        let mut roles = self.digits.iter().map(|d| d.role.marker());
+        let mut markers = String::new();
+        for c in self.number.chars() {
+            match c {
+                'e' | 'E' => break,
+                c if c.is_ascii_digit() => markers.push(roles.next().unwrap_or(' ')),
+                '.' => markers.push('.'),
+                _ => markers.push(' '),
+            }
+        }
+        markers.trim_end().to_string()
+    }
+
+    /// The number of significant figures, as a range when some zeros are ambiguous: `4` or
+    /// `2 to 5`.
+    pub fn significant_figures_text(&self) -> String {
+        if self.maximum_significant_figures == self.significant_figures {
+            self.significant_figures.to_string()
+        } else {
+            format!("{} to {}", self.significant_figures, self.maximum_significant_figures)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roles(count: &FigureCount) -> String {
+        count.digits.iter().map(|d| d.role.marker()).collect()
+    }
+
+    #[test]
+    fn counts_leading_and_trailing_zeros() {
+        let count = FigureCount::count("0.003040").unwrap();
+        assert_eq!(roles(&count), "lllssst");
+        assert_eq!(count.significant_figures, 4);
+        assert_eq!(count.maximum_significant_figures, 4);
+        assert_eq!(count.decimal_places, 6);
+        assert_eq!(count.markers(), "l.llssst");
+    }
+
+    #[test]
+    fn finds_ambiguous_zeros_in_whole_numbers() {
+        let count = FigureCount::count("12000").unwrap();
+        assert_eq!(roles(&count), "ss???");
+        assert_eq!(count.significant_figures_text(), "2 to 5");
+        assert_eq!(count.decimal_places, 0);
+
+        let count = FigureCount::count("12000.").unwrap();
+        assert_eq!(roles(&count), "ssttt");
+        assert_eq!(count.significant_figures_text(), "5");
+    }
+
+    #[test]
+    fn counts_zeros_between_digits() {
+        let count = FigureCount::count("-4005.20").unwrap();
+        assert_eq!(roles(&count), "ssssst");
+        assert_eq!(count.significant_figures, 6);
+        assert_eq!(count.decimal_places, 2);
+        assert_eq!(count.markers(), " ssss.st");
+    }
+
+    #[test]
+    fn counts_standard_form_from_the_digits_before_the_e() {
+        let count = FigureCount::count("1.50e3").unwrap();
+        assert_eq!(count.significant_figures, 3);
+        assert_eq!(count.decimal_places, 0);
+        assert_eq!(count.markers(), "s.st");
+    }
+
+    #[test]
+    fn rejects_invalid_numbers() {
+        assert!(FigureCount::count("12a").is_err());
+        assert_eq!(FigureCount::count("0").unwrap().significant_figures, 0);
+    }
+}
@@ -0,0 +1,1096 @@
+//! Fraction routines built on the exact [`Fraction`] type: mixed numbers, common denominators,
+//! equivalent fractions, ordering and fraction arithmetic.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::decimal::Decimal;
+use crate::error::{MathError, Span};
+use crate::number::hcf;
+use crate::standard_form::DIVISION_FIGURES;
+
+/// An exact signed fraction, always stored in its lowest terms with a positive denominator.
+///
+/// Because every `Fraction` is normalised when it is created, two fractions with the same value
+/// always compare equal: `2/4`, `1/2` and `-3/-6` are all stored as `1/2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct Fraction {
+    numerator: i64,
+    denominator: i64,
+}
+
+impl Fraction {
+    /// The fraction 0/1.
+    pub const ZERO: Fraction = Fraction { numerator: 0, denominator: 1 };
+    /// The fraction 1/1.
+    pub const ONE: Fraction = Fraction { numerator: 1, denominator: 1 };
+
+    /// Creates a fraction in its lowest terms, or `None` if the denominator is zero.
+    pub fn new(numerator: i64, denominator: i64) -> Option<Fraction> {
+        Fraction::reduce(numerator as i128, denominator as i128)
+    }
+
+    /// Creates a whole-number fraction such as 5/1.
+    pub fn from_integer(n: i64) -> Fraction {
+        Fraction { numerator: n, denominator: 1 }
+    }
+
+    /// Creates a fraction from a mixed number such as 2 1/3 (giving 7/3).
+    ///
+    /// A negative whole part makes the whole mixed number negative, so -2 1/3 gives -7/3.
+    /// Returns `None` if the denominator is zero or on overflow.
+    pub fn from_mixed(whole: i64, numerator: i64, denominator: i64) -> Option<Fraction> {
+        let part = Fraction::new(numerator, denominator)?.checked_abs()?;
+        let whole = Fraction::from_integer(whole);
+        if whole.numerator < 0 {
+            whole.checked_sub(part)
+        } else {
+            whole.checked_add(part)
+        }
+    }
+
+    /// Reduces a wide numerator and denominator to lowest terms with a positive denominator.
+    fn reduce(numerator: i128, denominator: i128) -> Option<Fraction> {
+        if denominator == 0 {
+            return None;
+        }
+        let common_factor = hcf(numerator.unsigned_abs(), denominator.unsigned_abs()) as i128;
+        let sign = if denominator < 0 { -1 } else { 1 };
+        let numerator = sign * numerator / common_factor;
+        let denominator = sign * denominator / common_factor;
+        Some(Fraction {
+            numerator: i64::try_from(numerator).ok()?,
+            denominator: i64::try_from(denominator).ok()?,
+        })
+    }
+
+    /// The numerator, which carries the sign of the fraction.
+    pub fn numerator(&self) -> i64 {
+        self.numerator
+    }
+
+    /// The denominator, which is always positive.
+    pub fn denominator(&self) -> i64 {
+        self.denominator
+    }
+
+    /// Returns `true` if the fraction is a whole number.
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    /// Returns `true` if the fraction is zero.
+    pub fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+
+    /// Returns `true` if the fraction is less than zero.
+    pub fn is_negative(&self) -> bool {
+        self.numerator < 0
+    }
+
+    /// The absolute value of the fraction.
+    ///
+    /// Panics if the numerator is `i64::MIN`, whose size does not fit in an `i64`; use
+    /// [`Fraction::checked_abs`] where that can happen.
+    pub fn abs(&self) -> Fraction {
+        self.checked_abs().expect("attempt to take the absolute value of a fraction with overflow")
+    }
+
+    /// The absolute value of the fraction, or `None` if the numerator is `i64::MIN`.
+    pub fn checked_abs(&self) -> Option<Fraction> {
+        if self.is_negative() { self.checked_neg() } else { Some(*self) }
+    }
+
+    /// The reciprocal of the fraction, or `None` if it is zero.
+    pub fn recip(&self) -> Option<Fraction> {
+        Fraction::new(self.denominator, self.numerator)
+    }
+
+    /// The value of the fraction as a decimal.
+    pub fn to_f64(&self) -> f64 {
+        self.numerator as f64 / self.denominator as f64
+    }
+
+    /// The value of the fraction as an exact decimal, worked out by dividing the numerator by
+    /// the denominator. A decimal that ends is exact and comes with `true`; one that recurs, such
+    /// as 1/3, is given to [`DIVISION_FIGURES`] significant figures and comes with `false`.
+    pub fn to_decimal(&self) -> (Decimal, bool) {
+        Decimal::from(self.numerator)
+            .div(&Decimal::from(self.denominator), DIVISION_FIGURES)
+            .expect("a fraction's denominator is never zero")
+    }
+
+    /// Splits the fraction into a whole part and a proper fractional part with the same sign,
+    /// so 7/3 gives `(2, 1/3)` and -7/3 gives `(-2, -1/3)`.
+    pub fn to_mixed(&self) -> (i64, Fraction) {
+        let whole = self.numerator / self.denominator;
+        let remainder = Fraction {
+            numerator: self.numerator % self.denominator,
+            denominator: self.denominator,
+        };
+        (whole, remainder)
+    }
+
+    /// Formats the fraction as a mixed number such as `2 1/3` or `-2 1/3`.
+    ///
+    /// Whole numbers are shown without a fraction part and proper fractions without a whole part.
+    pub fn to_mixed_string(&self) -> String {
+        let (whole, remainder) = self.to_mixed();
+        if remainder.is_zero() {
+            whole.to_string()
+        } else if whole == 0 {
+            remainder.to_string()
+        } else {
+            format!("{} {}", whole, remainder.abs())
+        }
+    }
+
+    /// Adds two fractions, returning `None` on overflow.
+    pub fn checked_add(self, rhs: Fraction) -> Option<Fraction> {
+        let (a, b) = (self.numerator as i128, self.denominator as i128);
+        let (c, d) = (rhs.numerator as i128, rhs.denominator as i128);
+        Fraction::reduce(a * d + c * b, b * d)
+    }
+
+    /// Subtracts two fractions, returning `None` on overflow.
+    pub fn checked_sub(self, rhs: Fraction) -> Option<Fraction> {
+        let (a, b) = (self.numerator as i128, self.denominator as i128);
+        let (c, d) = (rhs.numerator as i128, rhs.denominator as i128);
+        Fraction::reduce(a * d - c * b, b * d)
+    }
+
+    /// The fraction with its sign changed, or `None` if the numerator is `i64::MIN`.
+    pub fn checked_neg(&self) -> Option<Fraction> {
+        Some(Fraction { numerator: self.numerator.checked_neg()?, denominator: self.denominator })
+    }
+
+    /// Multiplies two fractions, returning `None` on overflow.
+    pub fn checked_mul(self, rhs: Fraction) -> Option<Fraction> {
+        let (a, b) = (self.numerator as i128, self.denominator as i128);
+        let (c, d) = (rhs.numerator as i128, rhs.denominator as i128);
+        Fraction::reduce(a * c, b * d)
+    }
+
+    /// Divides two fractions, returning `None` if `rhs` is zero or on overflow.
+    pub fn checked_div(self, rhs: Fraction) -> Option<Fraction> {
+        let (a, b) = (self.numerator as i128, self.denominator as i128);
+        let (c, d) = (rhs.numerator as i128, rhs.denominator as i128);
+        Fraction::reduce(a * d, b * c)
+    }
+}
+
+impl Default for Fraction {
+    fn default() -> Self {
+        Fraction::ZERO
+    }
+}
+
+impl From<i64> for Fraction {
+    fn from(n: i64) -> Self {
+        Fraction::from_integer(n)
+    }
+}
+
+impl fmt::Display for Fraction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+impl PartialOrd for Fraction {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Fraction {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Both denominators are positive, so cross-multiplying keeps the order.
+        let left = self.numerator as i128 * other.denominator as i128;
+        let right = other.numerator as i128 * self.denominator as i128;
+        left.cmp(&right)
+    }
+}
+
+impl Neg for Fraction {
+    type Output = Fraction;
+
+    fn neg(self) -> Fraction {
+        self.checked_neg().expect("attempt to negate a fraction with overflow")
+    }
+}
+
+impl Add for Fraction {
+    type Output = Fraction;
+
+    fn add(self, rhs: Fraction) -> Fraction {
+        self.checked_add(rhs).expect("attempt to add fractions with overflow")
+    }
+}
+
+impl Sub for Fraction {
+    type Output = Fraction;
+
+    fn sub(self, rhs: Fraction) -> Fraction {
+        self.checked_sub(rhs).expect("attempt to subtract fractions with overflow")
+    }
+}
+
+impl Mul for Fraction {
+    type Output = Fraction;
+
+    fn mul(self, rhs: Fraction) -> Fraction {
+        self.checked_mul(rhs).expect("attempt to multiply fractions with overflow")
+    }
+}
+
+impl Div for Fraction {
+    type Output = Fraction;
+
+    fn div(self, rhs: Fraction) -> Fraction {
+        assert!(!rhs.is_zero(), "attempt to divide a fraction by zero");
+        self.checked_div(rhs).expect("attempt to divide fractions with overflow")
+    }
+}
+
+/// Returns a function that moves an error found while parsing `part` on its own so that it
+/// points into the whole `input`.
+fn shift_into(part: &str, input: &str) -> impl Fn(MathError) -> MathError {
+    let offset = Span::of(part, input).start;
+    move |error| error.shifted(offset)
+}
+
+/// Parses a whole number or a decimal such as `-1.25` into an exact fraction.
+fn parse_decimal(s: &str) -> Result<Fraction, MathError> {
+    let invalid = || MathError::InvalidToken { token: s.to_string(), position: Span::new(0, s.len()) };
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let (whole, decimals) = digits.split_once('.').unwrap_or((digits, ""));
+    if whole.is_empty() && decimals.is_empty()
+        || !whole.chars().chain(decimals.chars()).all(|c| c.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+
+    let scale = 10_i64.checked_pow(decimals.len() as u32).ok_or(MathError::Overflow)?;
+    let numerator: i64 = format!("{}{}", whole, decimals).parse().map_err(|_| MathError::Overflow)?;
+    let value = Fraction::new(numerator, scale).ok_or(MathError::Overflow)?;
+    if negative { value.checked_neg().ok_or(MathError::Overflow) } else { Ok(value) }
+}
+
+impl FromStr for Fraction {
+    type Err = MathError;
+
+    /// Reads a fraction written as `3/4`, `-3/4`, a mixed number such as `2 1/3`, a whole number
+    /// or a decimal such as `0.4`.
+    ///
+    /// The position in any error is relative to the string as given, including leading spaces.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let s = input.trim();
+        if s.is_empty() {
+            return Err(MathError::EmptyInput);
+        }
+
+        // A mixed number has a whole part, a space, then a fraction.
+        if let Some((whole_part, fraction)) = s.split_once(char::is_whitespace) {
+            let whole: i64 = whole_part.parse().map_err(|_| MathError::InvalidToken {
+                token: whole_part.to_string(),
+                position: Span::of(whole_part, input),
+            })?;
+            let fraction = fraction.trim();
+            let part: Fraction = fraction.parse().map_err(shift_into(fraction, input))?;
+            // The fraction part must be proper, so 2 3/2 is not a mixed number.
+            if part <= Fraction::ZERO || part >= Fraction::ONE {
+                return Err(MathError::InvalidToken {
+                    token: fraction.to_string(),
+                    position: Span::of(fraction, input),
+                });
+            }
+            let sign = if s.starts_with('-') { -1 } else { 1 };
+            return Fraction::from_mixed(whole, part.numerator, part.denominator)
+                .and_then(|value| if whole == 0 && sign < 0 { value.checked_neg() } else { Some(value) })
+                .ok_or(MathError::Overflow);
+        }
+
+        match s.split_once('/') {
+            Some((numerator, denominator)) => {
+                let (numerator, denominator) = (numerator.trim(), denominator.trim());
+                let top = parse_decimal(numerator).map_err(shift_into(numerator, input))?;
+                let bottom = parse_decimal(denominator).map_err(shift_into(denominator, input))?;
+                if bottom.is_zero() {
+                    return Err(MathError::ZeroDenominator { position: Some(Span::of(denominator, input)) });
+                }
+                top.checked_div(bottom).ok_or(MathError::Overflow)
+            }
+            None => parse_decimal(s).map_err(shift_into(s, input)),
+        }
+    }
+}
+
+/// Finds the lowest common denominator of a list of fractions and rewrites each fraction over it.
+///
+/// Returns the common denominator (the LCM of the denominators) together with the numerator of
+/// each fraction over it, in the same order as the input, or `None` if the numbers overflow.
+pub fn common_denominator(fractions: &[Fraction]) -> Option<(i64, Vec<i64>)> {
+    let mut common: i64 = 1;
+    for fraction in fractions {
+        let den = fraction.denominator();
+        let factor = hcf(common as u64, den as u64) as i64;
+        common = (common / factor).checked_mul(den)?;
+    }
+    let numerators = fractions
+        .iter()
+        .map(|fraction| fraction.numerator().checked_mul(common / fraction.denominator()))
+        .collect::<Option<Vec<i64>>>()?;
+    Some((common, numerators))
+}
+
+/// Writes `a/b = c/d` with a `?` for each unknown, along with the span of each of the four
+/// values.
+fn write_equivalent(values: [Option<Fraction>; 4]) -> (String, [Span; 4]) {
+    let mut equation = String::new();
+    let mut spans = [Span::new(0, 0); 4];
+    for ((value, separator), span) in values.into_iter().zip(["/", " = ", "/", ""]).zip(&mut spans) {
+        let start = equation.len();
+        match value {
+            Some(value) => equation.push_str(&value.to_string()),
+            None => equation.push('?'),
+        }
+        *span = Span::new(start, equation.len());
+        equation.push_str(separator);
+    }
+    (equation, spans)
+}
+
+/// Solves `a/b = c/d` for the single unknown value, given as `None`.
+///
+/// The rule used is cross-multiplication: if a/b = c/d then a × d = b × c. Fails if a denominator
+/// that is given is zero, if there is not exactly one unknown, if solving would divide by zero or
+/// if the numbers overflow. The position in an error points into the equation written as
+/// `?/? = 3/4`: at the zero denominator, or at each `?` when there are several unknowns.
+pub fn solve_equivalent(
+    a: Option<Fraction>,
+    b: Option<Fraction>,
+    c: Option<Fraction>,
+    d: Option<Fraction>,
+) -> Result<Fraction, MathError> {
+    let (_, spans) = write_equivalent([a, b, c, d]);
+    for (denominator, span) in [(b, spans[1]), (d, spans[3])] {
+        if denominator.is_some_and(|denominator| denominator.is_zero()) {
+            return Err(MathError::ZeroDenominator { position: Some(span) });
+        }
+    }
+    let (product, divisor) = match (a, b, c, d) {
+        (None, Some(b), Some(c), Some(d)) => (b.checked_mul(c), d),
+        (Some(a), None, Some(c), Some(d)) => (a.checked_mul(d), c),
+        (Some(a), Some(b), None, Some(d)) => (a.checked_mul(d), b),
+        (Some(a), Some(b), Some(c), None) => (b.checked_mul(c), a),
+        (Some(_), Some(_), Some(_), Some(_)) => return Err(MathError::NoUnknown),
+        _ => {
+            let unknowns = [a, b, c, d].into_iter().zip(spans).filter(|(value, _)| value.is_none());
+            return Err(MathError::MultipleUnknowns { positions: unknowns.map(|(_, span)| span).collect() });
+        }
+    };
+    if divisor.is_zero() {
+        return Err(MathError::DivisionByZero);
+    }
+    product.and_then(|product| product.checked_div(divisor)).ok_or(MathError::Overflow)
+}
+
+/// Reads a fraction, mixed number or decimal, or a percentage such as `35%`.
+pub fn parse_fraction_or_percentage(s: &str) -> Result<Fraction, MathError> {
+    match s.trim().strip_suffix('%') {
+        Some(percentage) => {
+            let value: Fraction = percentage.parse().map_err(shift_into(percentage, s))?;
+            value.checked_div(Fraction::from_integer(100)).ok_or(MathError::Overflow)
+        }
+        None => s.parse(),
+    }
+}
+
+/// Reads a comma-separated list such as `3/8, 0.4, 35%, 1 1/4`, keeping each value as it was
+/// written alongside its fraction. Empty items, e.g. from a trailing comma, are skipped.
+pub fn parse_fraction_list(input: &str) -> Result<Vec<(&str, Fraction)>, MathError> {
+    let values = input
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            parse_fraction_or_percentage(s)
+                .map(|fraction| (s, fraction))
+                .map_err(shift_into(s, input))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if values.is_empty() {
+        return Err(MathError::EmptyInput);
+    }
+    Ok(values)
+}
+
+/// Reads one value of an equivalent fraction equation, where a blank, `?` or a single letter
+/// such as `x` marks the unknown and is returned as `None`.
+pub fn parse_unknown(input: &str) -> Result<Option<Fraction>, MathError> {
+    let s = input.trim();
+    let mut chars = s.chars();
+    let is_letter = matches!((chars.next(), chars.next()), (Some(c), None) if c.is_alphabetic());
+    if s.is_empty() || s == "?" || is_letter {
+        Ok(None)
+    } else {
+        s.parse().map(Some).map_err(shift_into(s, input))
+    }
+}
+
+/// Reads a pair of equivalent fractions written as `a/b = c/d`, such as `12/30 = 4/x`.
+///
+/// The unknown, read by [`parse_unknown`], is returned as `None` ready for
+/// [`solve_equivalent`]. There must be exactly one unknown, and neither denominator may be zero.
+pub fn parse_equivalent(input: &str) -> Result<[Option<Fraction>; 4], MathError> {
+    let (first, second) = input.split_once('=').ok_or(MathError::NotAnEquation)?;
+    let (Some((a, b)), Some((c, d))) = (first.split_once('/'), second.split_once('/')) else {
+        return Err(MathError::NotAnEquation);
+    };
+    if second.contains('=') {
+        return Err(MathError::NotAnEquation);
+    }
+
+    let parts = [a, b, c, d];
+    let mut values = [None; 4];
+    let mut unknowns = Vec::new();
+    for (i, (value, part)) in values.iter_mut().zip(parts).enumerate() {
+        *value = parse_unknown(part).map_err(shift_into(part, input))?;
+        match value {
+            None => unknowns.push(Span::of(part.trim(), input)),
+            // The second and fourth values are the denominators.
+            Some(value) if i % 2 == 1 && value.is_zero() => {
+                return Err(MathError::ZeroDenominator { position: Some(Span::of(part.trim(), input)) });
+            }
+            Some(_) => {}
+        }
+    }
+    match unknowns.len() {
+        0 => Err(MathError::NoUnknown),
+        1 => Ok(values),
+        _ => Err(MathError::MultipleUnknowns { positions: unknowns }),
+    }
+}
+
+/// The direction to put a list of fractions in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// Smallest first.
+    #[default]
+    Ascending,
+    /// Largest first.
+    Descending,
+}
+
+impl SortOrder {
+    /// A list of both orders.
+    pub const ALL: [SortOrder; 2] = [SortOrder::Ascending, SortOrder::Descending];
+
+    /// Reads `ascending`/`descending`, or an abbreviation such as `asc` or `d`.
+    pub fn from_name(name: &str) -> Option<SortOrder> {
+        let name = name.trim().to_lowercase();
+        if name.is_empty() {
+            None
+        } else if "ascending".starts_with(&name) {
+            Some(SortOrder::Ascending)
+        } else if "descending".starts_with(&name) {
+            Some(SortOrder::Descending)
+        } else {
+            None
+        }
+    }
+
+    /// The symbol placed between two unequal values in this order.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            SortOrder::Ascending => "<",
+            SortOrder::Descending => ">",
+        }
+    }
+}
+
+impl fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortOrder::Ascending => write!(f, "Ascending"),
+            SortOrder::Descending => write!(f, "Descending"),
+        }
+    }
+}
+
+/// Puts fractions in order, returning the position of each one in the input list.
+///
+/// The sort is stable, so equal fractions such as `1/2` and `0.5` stay in the order they were
+/// given in.
+pub fn order_fractions(fractions: &[Fraction], order: SortOrder) -> Vec<usize> {
+    let mut positions: Vec<usize> = (0..fractions.len()).collect();
+    positions.sort_by(|&i, &j| match order {
+        SortOrder::Ascending => fractions[i].cmp(&fractions[j]),
+        SortOrder::Descending => fractions[j].cmp(&fractions[i]),
+    });
+    positions
+}
+
+/// Writes an ordered list of values as an inequality such as `35% < 3/8 < 0.4 = 2/5`, using
+/// each value's original notation.
+///
+/// The list is expected to already be sorted, e.g. by [`order_fractions`].
+pub fn ordering_statement(ordered: &[(&str, Fraction)], order: SortOrder) -> String {
+    let mut statement = String::new();
+    for (i, (notation, value)) in ordered.iter().enumerate() {
+        if i > 0 {
+            let symbol = if ordered[i - 1].1 == *value { "=" } else { order.symbol() };
+            statement.push_str(&format!(" {} ", symbol));
+        }
+        statement.push_str(notation);
+    }
+    statement
+}
+
+/// The four operations of fraction arithmetic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operation {
+    /// A list of all operations.
+    pub const ALL: [Operation; 4] = [
+        Operation::Add,
+        Operation::Subtract,
+        Operation::Multiply,
+        Operation::Divide,
+    ];
+
+    /// The symbol used to write the operation.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Operation::Add => "+",
+            Operation::Subtract => "-",
+            Operation::Multiply => "×",
+            Operation::Divide => "÷",
+        }
+    }
+
+    /// Reads an operator symbol, accepting the keyboard forms `*`, `x` and `/` as well.
+    pub fn from_symbol(symbol: &str) -> Option<Operation> {
+        match symbol {
+            "+" => Some(Operation::Add),
+            "-" | "−" => Some(Operation::Subtract),
+            "*" | "x" | "×" => Some(Operation::Multiply),
+            "/" | "÷" => Some(Operation::Divide),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+/// The full working for a fraction calculation such as `2 1/3 + 3/4`.
+///
+/// The fractions are first written as improper fractions. For addition and subtraction they are
+/// rewritten over the LCM of the denominators and the numerators combined; for multiplication
+/// the numerators and denominators are multiplied; for division the second fraction is flipped
+/// and the two are multiplied. The combined fraction is then simplified by dividing by the HCF.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FractionCalculation {
+    pub left: Fraction,
+    pub operation: Operation,
+    pub right: Fraction,
+    /// The LCM of the denominators, used for addition and subtraction.
+    pub common_denominator: Option<i64>,
+    /// The two fractions, as `(numerator, denominator)`, in the form they are combined in.
+    pub combined_operands: [(i64, i64); 2],
+    /// The combined fraction before simplifying.
+    pub unsimplified: (i64, i64),
+    /// The HCF of the unsimplified numerator and denominator.
+    pub common_factor: i64,
+    /// The answer in its lowest terms.
+    pub result: Fraction,
+    /// The answer as a decimal, to [`DIVISION_FIGURES`] significant figures if it recurs.
+    pub decimal: Decimal,
+    /// `true` if the decimal ends, so it is exactly the answer.
+    pub exact_decimal: bool,
+    /// Whether each fraction was written as a mixed number, which the working first writes as an
+    /// improper fraction.
+    pub mixed: [bool; 2],
+}
+
+impl FractionCalculation {
+    /// Works through `left operation right`, failing when dividing by zero or if the numbers
+    /// overflow.
+    pub fn new(left: Fraction, operation: Operation, right: Fraction) -> Result<FractionCalculation, MathError> {
+        if operation == Operation::Divide && right.is_zero() {
+            return Err(MathError::DivisionByZero);
+        }
+        FractionCalculation::work_through(left, operation, right).ok_or(MathError::Overflow)
+    }
+
+    /// Does the working for [`FractionCalculation::new`], returning `None` on overflow.
+    fn work_through(left: Fraction, operation: Operation, right: Fraction) -> Option<FractionCalculation> {
+        let (a, b) = (left.numerator(), left.denominator());
+        let (c, d) = (right.numerator(), right.denominator());
+
+        let (common_denominator, combined_operands, unsimplified) = match operation {
+            Operation::Add | Operation::Subtract => {
+                let (common, numerators) = common_denominator(&[left, right])?;
+                let (x, y) = (numerators[0], numerators[1]);
+                let combined = if operation == Operation::Add {
+                    x.checked_add(y)?
+                } else {
+                    x.checked_sub(y)?
+                };
+                (Some(common), [(x, common), (y, common)], (combined, common))
+            }
+            Operation::Multiply => (None, [(a, b), (c, d)], (a.checked_mul(c)?, b.checked_mul(d)?)),
+            Operation::Divide => {
+                // Keep the first fraction, change ÷ to ×, and flip the second fraction.
+                let (flipped_numerator, flipped_denominator) = if c < 0 { (-d, -c) } else { (d, c) };
+                let unsimplified = (a.checked_mul(flipped_numerator)?, b.checked_mul(flipped_denominator)?);
+                (None, [(a, b), (flipped_numerator, flipped_denominator)], unsimplified)
+            }
+        };
+
+        let common_factor = hcf(unsimplified.0.unsigned_abs(), unsimplified.1.unsigned_abs()) as i64;
+        let result = Fraction::new(unsimplified.0, unsimplified.1)?;
+        let (decimal, exact_decimal) = result.to_decimal();
+
+        Some(FractionCalculation {
+            left,
+            operation,
+            right,
+            common_denominator,
+            combined_operands,
+            unsimplified,
+            common_factor,
+            result,
+            decimal,
+            exact_decimal,
+            mixed: [false; 2],
+        })
+    }
+
+    /// Describes each step of the working, one line per step.
+    pub fn steps(&self) -> Vec<String> {
+        let mut steps = Vec::new();
+        let [(p, q), (r, s)] = self.combined_operands;
+
+        // Step 1: Write any mixed numbers as improper fractions.
+        for (fraction, mixed) in [self.left, self.right].into_iter().zip(self.mixed) {
+            if mixed {
+                steps.push(format!(
+                    "Write {} as an improper fraction: {}",
+                    fraction.to_mixed_string(),
+                    fraction
+                ));
+            }
+        }
+
+        // Step 2: Combine the fractions.
+        match self.operation {
+            Operation::Add | Operation::Subtract => {
+                let common = self.common_denominator.unwrap_or(q);
+                steps.push(format!(
+                    "The LCM of the denominators {} and {} is {}",
+                    self.left.denominator(),
+                    self.right.denominator(),
+                    common
+                ));
+                steps.push(format!(
+                    "Rewrite over the common denominator: {} = {}/{} and {} = {}/{}",
+                    self.left, p, q, self.right, r, s
+                ));
+                steps.push(format!(
+                    "{} the numerators: {}/{} {} {}/{} = {}/{}",
+                    if self.operation == Operation::Add { "Add" } else { "Subtract" },
+                    p,
+                    q,
+                    self.operation,
+                    r,
+                    s,
+                    self.unsimplified.0,
+                    self.unsimplified.1
+                ));
+            }
+            Operation::Multiply => {
+                steps.push(format!(
+                    "Multiply the numerators and the denominators: ({} × {})/({} × {}) = {}/{}",
+                    p, r, q, s, self.unsimplified.0, self.unsimplified.1
+                ));
+            }
+            Operation::Divide => {
+                steps.push(format!(
+                    "Keep {}, change ÷ to × and flip {} to get {}/{}",
+                    self.left, self.right, r, s
+                ));
+                steps.push(format!(
+                    "Multiply the numerators and the denominators: ({} × {})/({} × {}) = {}/{}",
+                    p, r, q, s, self.unsimplified.0, self.unsimplified.1
+                ));
+            }
+        }
+
+        // Step 3: Simplify using the HCF.
+        if self.common_factor > 1 {
+            steps.push(format!(
+                "Simplify by dividing by the HCF {}: {}/{} = {}",
+                self.common_factor, self.unsimplified.0, self.unsimplified.1, self.result
+            ));
+        } else {
+            steps.push(format!(
+                "{}/{} is already in its lowest terms",
+                self.unsimplified.0, self.unsimplified.1
+            ));
+        }
+        steps
+    }
+}
+
+/// Reads a calculation such as `2 1/3 + 3/4` or `5/6 ÷ 1 1/2`.
+///
+/// The operator must have a space either side so that it is not mistaken for the `/` or `-`
+/// of a fraction.
+pub fn parse_calculation(input: &str) -> Result<(Fraction, Operation, Fraction), MathError> {
+    let ((_, left), operation, (_, right)) = read_calculation(input)?;
+    Ok((left, operation, right))
+}
+
+/// A fraction in a calculation, as written and as a value.
+type WrittenFraction<'a> = (&'a str, Fraction);
+
+/// Reads a calculation for [`parse_calculation`], keeping each fraction as it was written.
+fn read_calculation(input: &str) -> Result<(WrittenFraction<'_>, Operation, WrittenFraction<'_>), MathError> {
+    let (symbol, operation) = input
+        .split_whitespace()
+        .skip(1)
+        .find_map(|token| Operation::from_symbol(token).map(|operation| (token, operation)))
+        .ok_or(MathError::MissingOperator)?;
+    let position = Span::of(symbol, input);
+    let left = &input[..position.start];
+    let right = &input[position.end..];
+    let right_value: Fraction = right.parse().map_err(shift_into(right, input))?;
+    Ok(((left, left.parse()?), operation, (right, right_value)))
+}
+
+impl FromStr for FractionCalculation {
+    type Err = MathError;
+
+    /// Reads a calculation such as `2 1/3 + 3/4` and works through it. The working starts by
+    /// writing any fraction given as a mixed number as an improper fraction.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let ((left_written, left), operation, (right_written, right)) = read_calculation(input)?;
+        let mut calculation = FractionCalculation::new(left, operation, right)?;
+        // A mixed number is the only way of writing a fraction with a space in it.
+        calculation.mixed = [left_written, right_written].map(|written| written.trim().contains(char::is_whitespace));
+        Ok(calculation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frac(s: &str) -> Fraction {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn normalises_on_creation() {
+        assert_eq!(Fraction::new(24, 30), Fraction::new(4, 5));
+        assert_eq!(Fraction::new(3, -6).unwrap().to_string(), "-1/2");
+        assert_eq!(Fraction::new(-3, -6).unwrap().to_string(), "1/2");
+        assert_eq!(Fraction::new(0, 5), Some(Fraction::ZERO));
+        assert_eq!(Fraction::new(1, 0), None);
+    }
+
+    #[test]
+    fn parses_all_notations() {
+        assert_eq!(frac("3/4"), Fraction::new(3, 4).unwrap());
+        assert_eq!(frac("-3/4"), Fraction::new(-3, 4).unwrap());
+        assert_eq!(frac(" 2 1/3 "), Fraction::new(7, 3).unwrap());
+        assert_eq!(frac("-2 1/3"), Fraction::new(-7, 3).unwrap());
+        assert_eq!(frac("5"), Fraction::from_integer(5));
+        assert_eq!(frac("0.4"), Fraction::new(2, 5).unwrap());
+        assert_eq!(frac("-1.25"), Fraction::new(-5, 4).unwrap());
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!("".parse::<Fraction>(), Err(MathError::EmptyInput));
+        assert_eq!(
+            "3/0".parse::<Fraction>(),
+            Err(MathError::ZeroDenominator { position: Some(Span::new(2, 3)) })
+        );
+        assert_eq!(
+            " a/b".parse::<Fraction>(),
+            Err(MathError::InvalidToken { token: "a".to_string(), position: Span::new(1, 2) })
+        );
+        assert_eq!(
+            "2 1/3x".parse::<Fraction>(),
+            Err(MathError::InvalidToken { token: "3x".to_string(), position: Span::new(4, 6) })
+        );
+        assert_eq!(
+            "2 3/2".parse::<Fraction>(),
+            Err(MathError::InvalidToken { token: "3/2".to_string(), position: Span::new(2, 5) })
+        );
+        assert!("2 -1/3".parse::<Fraction>().is_err());
+        assert!("1 0/3".parse::<Fraction>().is_err());
+        assert!("1.2.3".parse::<Fraction>().is_err());
+    }
+
+    #[test]
+    fn arithmetic_is_exact() {
+        assert_eq!(frac("1/3") + frac("1/6"), frac("1/2"));
+        assert_eq!(frac("3/4") - frac("1 1/4"), frac("-1/2"));
+        assert_eq!(frac("2/3") * frac("9/4"), frac("3/2"));
+        assert_eq!(frac("5/6") / frac("1 1/2"), frac("5/9"));
+        assert_eq!(-frac("2/5"), frac("-2/5"));
+        assert_eq!(frac("1/2").checked_div(Fraction::ZERO), None);
+
+        let smallest = Fraction::from_integer(i64::MIN);
+        assert_eq!(smallest.checked_neg(), None);
+        assert_eq!(smallest.checked_abs(), None);
+        assert_eq!(smallest.checked_sub(Fraction::ONE), None);
+        assert_eq!(Fraction::ZERO.checked_sub(smallest), None);
+        assert_eq!(Fraction::from_mixed(i64::MIN, 0, 1), Some(smallest));
+    }
+
+    #[test]
+    fn orders_by_value() {
+        assert!(frac("3/8") < frac("2/5"));
+        assert!(frac("-1/2") < frac("1/3"));
+        assert_eq!(frac("2/4").cmp(&frac("1/2")), Ordering::Equal);
+    }
+
+    #[test]
+    fn converts_to_mixed_numbers() {
+        assert_eq!(frac("7/3").to_mixed(), (2, frac("1/3")));
+        assert_eq!(frac("-7/3").to_mixed(), (-2, frac("-1/3")));
+        assert_eq!(frac("7/3").to_mixed_string(), "2 1/3");
+        assert_eq!(frac("-7/3").to_mixed_string(), "-2 1/3");
+        assert_eq!(frac("6/3").to_mixed_string(), "2");
+        assert_eq!(frac("1/3").to_mixed_string(), "1/3");
+        assert_eq!(Fraction::from_mixed(4, 3, 5), Fraction::new(23, 5));
+    }
+
+    #[test]
+    fn rewrites_over_common_denominator() {
+        let (common, numerators) = common_denominator(&[frac("5/6"), frac("3/8")]).unwrap();
+        assert_eq!(common, 24);
+        assert_eq!(numerators, vec![20, 9]);
+    }
+
+    #[test]
+    fn parses_percentages() {
+        assert_eq!(parse_fraction_or_percentage("35%"), Ok(frac("7/20")));
+        assert_eq!(parse_fraction_or_percentage("12.5 %"), Ok(frac("1/8")));
+        assert_eq!(parse_fraction_or_percentage("1 1/4"), Ok(frac("5/4")));
+        assert!(parse_fraction_or_percentage("%").is_err());
+    }
+
+    #[test]
+    fn orders_mixed_notation() {
+        let inputs = ["3/8", "0.4", "35%", "1 1/4"];
+        let values: Vec<Fraction> = inputs.iter().map(|s| parse_fraction_or_percentage(s).unwrap()).collect();
+
+        let ascending = order_fractions(&values, SortOrder::Ascending);
+        assert_eq!(ascending, vec![2, 0, 1, 3]);
+        let ordered: Vec<(&str, Fraction)> = ascending.iter().map(|&i| (inputs[i], values[i])).collect();
+        assert_eq!(ordering_statement(&ordered, SortOrder::Ascending), "35% < 3/8 < 0.4 < 1 1/4");
+
+        assert_eq!(order_fractions(&values, SortOrder::Descending), vec![3, 1, 0, 2]);
+    }
+
+    #[test]
+    fn keeps_equal_fractions_in_input_order() {
+        let inputs = ["2/4", "1/3", "0.5", "50%"];
+        let values: Vec<Fraction> = inputs.iter().map(|s| parse_fraction_or_percentage(s).unwrap()).collect();
+
+        let descending = order_fractions(&values, SortOrder::Descending);
+        assert_eq!(descending, vec![0, 2, 3, 1]);
+        let ordered: Vec<(&str, Fraction)> = descending.iter().map(|&i| (inputs[i], values[i])).collect();
+        assert_eq!(ordering_statement(&ordered, SortOrder::Descending), "2/4 = 0.5 = 50% > 1/3");
+    }
+
+    #[test]
+    fn parses_fraction_lists() {
+        let values = parse_fraction_list("3/8, 0.4, 35%, 1 1/4,").unwrap();
+        let inputs: Vec<&str> = values.iter().map(|(input, _)| *input).collect();
+        assert_eq!(inputs, vec!["3/8", "0.4", "35%", "1 1/4"]);
+        assert_eq!(values[2].1, frac("7/20"));
+        assert_eq!(parse_fraction_list(" , "), Err(MathError::EmptyInput));
+        assert_eq!(
+            parse_fraction_list("1/2, 3/0"),
+            Err(MathError::ZeroDenominator { position: Some(Span::new(7, 8)) })
+        );
+        assert_eq!(
+            parse_fraction_list("1/2, 4o%"),
+            Err(MathError::InvalidToken { token: "4o".to_string(), position: Span::new(5, 7) })
+        );
+    }
+
+    #[test]
+    fn parses_equivalent_fractions() {
+        let known = |s: &str| Some(frac(s));
+        assert_eq!(parse_equivalent("12/30 = 4/x"), Ok([known("12"), known("30"), known("4"), None]));
+        assert_eq!(parse_equivalent("?/6=1/3"), Ok([None, known("6"), known("1"), known("3")]));
+        assert_eq!(parse_equivalent("12/30"), Err(MathError::NotAnEquation));
+        assert_eq!(parse_equivalent("1/2 = 2/4 = 4/8"), Err(MathError::NotAnEquation));
+    }
+
+    #[test]
+    fn points_out_unknowns_and_bad_values() {
+        assert_eq!(parse_equivalent("1/2 = 2/4"), Err(MathError::NoUnknown));
+        assert_eq!(
+            parse_equivalent("a/2 = b/4"),
+            Err(MathError::MultipleUnknowns { positions: vec![Span::new(0, 1), Span::new(6, 7)] })
+        );
+        assert_eq!(
+            parse_equivalent("12/30 = 4/x1"),
+            Err(MathError::InvalidToken { token: "x1".to_string(), position: Span::new(10, 12) })
+        );
+        assert_eq!(parse_unknown(" "), Ok(None));
+        assert_eq!(
+            parse_equivalent("12/0 = 4/x"),
+            Err(MathError::ZeroDenominator { position: Some(Span::new(3, 4)) })
+        );
+        assert_eq!(
+            parse_equivalent("1/2 = x/ 0"),
+            Err(MathError::ZeroDenominator { position: Some(Span::new(9, 10)) })
+        );
+    }
+
+    #[test]
+    fn reads_sort_order_names() {
+        assert_eq!(SortOrder::from_name("asc"), Some(SortOrder::Ascending));
+        assert_eq!(SortOrder::from_name("D"), Some(SortOrder::Descending));
+        assert_eq!(SortOrder::from_name("sideways"), None);
+        assert_eq!(SortOrder::from_name(""), None);
+    }
+
+    #[test]
+    fn solves_for_each_unknown() {
+        let known = |s: &str| Some(frac(s));
+        assert_eq!(solve_equivalent(known("12"), known("30"), known("4"), None), Ok(frac("10")));
+        assert_eq!(solve_equivalent(None, known("30"), known("4"), known("10")), Ok(frac("12")));
+        assert_eq!(solve_equivalent(known("1"), None, known("2"), known("4")), Ok(frac("2")));
+        assert_eq!(solve_equivalent(known("3"), known("4"), None, known("10")), Ok(frac("15/2")));
+    }
+
+    #[test]
+    fn rejects_missing_or_extra_unknowns() {
+        let known = |s: &str| Some(frac(s));
+        assert_eq!(solve_equivalent(known("1"), known("2"), known("3"), known("4")), Err(MathError::NoUnknown));
+        assert_eq!(
+            solve_equivalent(None, None, known("3"), known("4")),
+            Err(MathError::MultipleUnknowns { positions: vec![Span::new(0, 1), Span::new(2, 3)] })
+        );
+        assert_eq!(write_equivalent([None, None, known("3"), known("4")]).0, "?/? = 3/4");
+        assert_eq!(solve_equivalent(known("0"), known("2"), known("3"), None), Err(MathError::DivisionByZero));
+    }
+
+    #[test]
+    fn rejects_zero_denominators() {
+        let known = |s: &str| Some(frac(s));
+        assert_eq!(
+            solve_equivalent(known("12"), known("0"), known("4"), None),
+            Err(MathError::ZeroDenominator { position: Some(Span::new(3, 4)) })
+        );
+        assert_eq!(
+            solve_equivalent(known("1"), known("2"), None, known("0")),
+            Err(MathError::ZeroDenominator { position: Some(Span::new(8, 9)) })
+        );
+    }
+
+    #[test]
+    fn works_through_addition() {
+        let calculation: FractionCalculation = "2 1/3 + 3/4".parse().unwrap();
+        assert_eq!(calculation.common_denominator, Some(12));
+        assert_eq!(calculation.combined_operands, [(28, 12), (9, 12)]);
+        assert_eq!(calculation.result, frac("37/12"));
+        assert_eq!(
+            calculation.steps(),
+            vec![
+                "Write 2 1/3 as an improper fraction: 7/3",
+                "The LCM of the denominators 3 and 4 is 12",
+                "Rewrite over the common denominator: 7/3 = 28/12 and 3/4 = 9/12",
+                "Add the numerators: 28/12 + 9/12 = 37/12",
+                "37/12 is already in its lowest terms",
+            ]
+        );
+        assert_eq!(calculation.decimal.to_string(), "3.083333333");
+        assert!(!calculation.exact_decimal);
+
+        // A fraction that was already written improper needs no rewriting.
+        let calculation: FractionCalculation = "7/3 + 3/4".parse().unwrap();
+        assert_eq!(calculation.steps()[0], "The LCM of the denominators 3 and 4 is 12");
+        assert_eq!(FractionCalculation::new(frac("2 1/3"), Operation::Add, frac("3/4")).unwrap().mixed, [false; 2]);
+    }
+
+    #[test]
+    fn works_through_division() {
+        let calculation = FractionCalculation::new(frac("5/6"), Operation::Divide, frac("1 1/2")).unwrap();
+        assert_eq!(calculation.unsimplified, (10, 18));
+        assert_eq!(calculation.common_factor, 2);
+        assert_eq!(calculation.result, frac("5/9"));
+        assert_eq!(
+            FractionCalculation::new(frac("5/6"), Operation::Divide, Fraction::ZERO),
+            Err(MathError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn works_through_subtraction_and_multiplication() {
+        let difference = FractionCalculation::new(frac("1/2"), Operation::Subtract, frac("5/6")).unwrap();
+        assert_eq!(difference.unsimplified, (-2, 6));
+        assert_eq!(difference.result, frac("-1/3"));
+
+        let product = FractionCalculation::new(frac("2/3"), Operation::Multiply, frac("9/4")).unwrap();
+        assert_eq!(product.unsimplified, (18, 12));
+        assert_eq!(product.result, frac("3/2"));
+        assert_eq!((product.decimal.to_string(), product.exact_decimal), ("1.5".to_string(), true));
+    }
+
+    #[test]
+    fn parses_calculations() {
+        assert_eq!(
+            parse_calculation("2 1/3 + 3/4"),
+            Ok((frac("7/3"), Operation::Add, frac("3/4")))
+        );
+        assert_eq!(
+            parse_calculation("5/6 ÷ 1 1/2"),
+            Ok((frac("5/6"), Operation::Divide, frac("3/2")))
+        );
+        assert_eq!(
+            parse_calculation("-3/4 - -1/4"),
+            Ok((frac("-3/4"), Operation::Subtract, frac("-1/4")))
+        );
+        assert_eq!(parse_calculation("3/4"), Err(MathError::MissingOperator));
+        assert_eq!(
+            parse_calculation("1/2 + 3/z"),
+            Err(MathError::InvalidToken { token: "z".to_string(), position: Span::new(8, 9) })
+        );
+    }
+}
@@ -0,0 +1,20 @@
+//! MathRust: GCSE-style number calculators.
+//!
+//! Every calculator in the command-line binaries and the GUI is built on the routines in this
+//! library, so they can also be embedded in other tools.
+
+pub mod bodmas;
+pub mod bounds;
+pub mod decimal;
+pub mod error;
+pub mod exact;
+pub mod expressions;
+pub mod figures;
+pub mod fractions;
+pub mod number;
+pub mod parser;
+pub mod primes;
+pub mod results;
+pub mod rounding;
+pub mod session;
+pub mod standard_form;
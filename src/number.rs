@@ -0,0 +1,375 @@
+//! Whole-number routines: highest common factor, lowest common multiple and multiples, and
+//! reading lists of numbers.
+//!
+//! HCF and LCM work with any unsigned integer width and report overflow instead of wrapping.
+//! [`exact_lcm_of`] goes further and switches to an arbitrary-precision [`Natural`] when the
+//! answer no longer fits in a `u128`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{CheckedMul, ToPrimitive, Unsigned};
+use serde::{Serialize, Serializer};
+
+use crate::error::{MathError, Span};
+
+/// The whole-number types the HCF and LCM routines work with: every unsigned integer width,
+/// from `u8` to `u128`, and the arbitrary-precision [`BigUint`].
+pub trait WholeNumber: Integer + Unsigned + CheckedMul + Clone {}
+
+impl<T: Integer + Unsigned + CheckedMul + Clone> WholeNumber for T {}
+
+/// Calculates the highest common factor (HCF) of two numbers using the Euclidean algorithm.
+///
+/// The HCF is also known as the greatest common divisor (GCD). The algorithm relies on the
+/// fact that the HCF of two numbers does not change if the larger number is replaced by the
+/// remainder of dividing it by the smaller one. The HCF is never larger than the numbers, so
+/// it cannot overflow.
+pub fn hcf<T: WholeNumber>(a: T, b: T) -> T {
+    let mut temp_a = a;
+    let mut temp_b = b;
+    while !temp_b.is_zero() {
+        let t = temp_b.clone();
+        temp_b = temp_a % temp_b;
+        temp_a = t;
+    }
+    temp_a
+}
+
+/// Calculates the lowest common multiple (LCM) of two numbers, or `None` if it does not fit in
+/// the type.
+///
+/// Uses `LCM(a, b) = a / HCF(a, b) * b`, dividing first to keep the intermediate value small.
+/// If either number is zero, the LCM is 0.
+pub fn lcm<T: WholeNumber>(a: T, b: T) -> Option<T> {
+    if a.is_zero() || b.is_zero() {
+        Some(T::zero())
+    } else {
+        (a.clone() / hcf(a, b.clone())).checked_mul(&b)
+    }
+}
+
+/// Calculates the HCF of a list of numbers, or `None` if the list is empty.
+pub fn hcf_of<T: WholeNumber>(numbers: &[T]) -> Option<T> {
+    let (first, rest) = numbers.split_first()?;
+    Some(rest.iter().fold(first.clone(), |acc, num| hcf(acc, num.clone())))
+}
+
+/// Calculates the LCM of a list of numbers, failing if the list is empty or the LCM does not
+/// fit in the type.
+pub fn lcm_of<T: WholeNumber>(numbers: &[T]) -> Result<T, MathError> {
+    if numbers.is_empty() {
+        return Err(MathError::EmptyInput);
+    }
+    // 1 is the identity for LCM, so it is a safe starting value.
+    numbers
+        .iter()
+        .try_fold(T::one(), |acc, num| lcm(acc, num.clone()))
+        .ok_or(MathError::Overflow)
+}
+
+/// Calculates the exact LCM of a list of numbers of any width, or fails if the list is empty.
+///
+/// The LCM is worked out in a `u128` for as long as it fits, then carried on as a big integer,
+/// so it never overflows: the LCM of 1 to 100 has 41 digits.
+pub fn exact_lcm_of<T: Copy + Into<u128>>(numbers: &[T]) -> Result<Natural, MathError> {
+    if numbers.is_empty() {
+        return Err(MathError::EmptyInput);
+    }
+    Ok(numbers.iter().fold(Natural::Fixed(1), |acc, &num| acc.lcm(num.into())))
+}
+
+/// A whole number of any size, held in a `u128` when it fits and as a [`BigUint`] when it does
+/// not.
+///
+/// A value that fits in a `u128` is always stored as `Fixed`, so equal numbers compare equal.
+/// It serialises as a number when it fits in a `u128`, and as a string of digits otherwise,
+/// since most JSON readers cannot hold larger numbers exactly.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Natural {
+    Fixed(u128),
+    Big(BigUint),
+}
+
+impl Natural {
+    /// Stores a big integer, using a `u128` if it fits.
+    pub fn from_big(value: BigUint) -> Natural {
+        match value.to_u128() {
+            Some(value) => Natural::Fixed(value),
+            None => Natural::Big(value),
+        }
+    }
+
+    /// The value as a big integer.
+    pub fn to_big(&self) -> BigUint {
+        match self {
+            Natural::Fixed(value) => BigUint::from(*value),
+            Natural::Big(value) => value.clone(),
+        }
+    }
+
+    /// The value as a `u128`, or `None` if it is too large.
+    pub fn to_u128(&self) -> Option<u128> {
+        match self {
+            Natural::Fixed(value) => Some(*value),
+            Natural::Big(_) => None,
+        }
+    }
+
+    /// The LCM of this number and `other`, moving to a big integer if it no longer fits in a
+    /// `u128`.
+    pub fn lcm(self, other: u128) -> Natural {
+        let value = match self {
+            Natural::Fixed(value) => match lcm(value, other) {
+                Some(result) => return Natural::Fixed(result),
+                None => BigUint::from(value),
+            },
+            Natural::Big(value) => value,
+        };
+        let result = lcm(value, BigUint::from(other)).expect("big integers do not overflow");
+        Natural::from_big(result)
+    }
+}
+
+impl From<u128> for Natural {
+    fn from(value: u128) -> Self {
+        Natural::Fixed(value)
+    }
+}
+
+impl From<BigUint> for Natural {
+    fn from(value: BigUint) -> Self {
+        Natural::from_big(value)
+    }
+}
+
+impl fmt::Display for Natural {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Natural::Fixed(value) => write!(f, "{}", value),
+            Natural::Big(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl Serialize for Natural {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Natural::Fixed(value) => serialize_u128(value, serializer),
+            Natural::Big(value) => serializer.serialize_str(&value.to_string()),
+        }
+    }
+}
+
+/// Writes a whole number as a plain number if it fits in a `u64`, and as a string of digits if
+/// not, since JSON readers cannot hold anything bigger. For use with `#[serde(serialize_with)]`.
+pub fn serialize_u128<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+    match u64::try_from(*value) {
+        Ok(value) => serializer.serialize_u64(value),
+        Err(_) => serializer.serialize_str(&value.to_string()),
+    }
+}
+
+/// The most multiples that may be listed at once.
+const MAX_MULTIPLES: u64 = 100_000;
+
+/// Calculates the first `count` multiples of a given `number`. They are worked out as `u64`s,
+/// which hold every one of them; fails with [`MathError::TooLarge`] if `count` is above
+/// [`MAX_MULTIPLES`].
+pub fn get_multiples(number: u32, count: usize) -> Result<Vec<u64>, MathError> {
+    let count = u64::try_from(count).ok().filter(|&count| count <= MAX_MULTIPLES);
+    let count = count.ok_or(MathError::TooLarge { maximum: MAX_MULTIPLES })?;
+    Ok((1..=count).map(|i| u64::from(number) * i).collect())
+}
+
+/// The superscript digits `⁰` to `⁹`, in order.
+const SUPERSCRIPT_DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+
+/// Writes a whole number with superscript digits, such as `⁻³` for -3, for powers and indices.
+pub fn superscript(n: i64) -> String {
+    let digits = n.unsigned_abs().to_string();
+    let sign = if n < 0 { Some('⁻') } else { None };
+    sign.into_iter().chain(digits.chars().map(|c| SUPERSCRIPT_DIGITS[c as usize - '0' as usize])).collect()
+}
+
+/// Reads a whole number written with superscript digits, such as `⁻³`, or `None` if it is not
+/// one.
+pub fn parse_superscript(s: &str) -> Option<i64> {
+    let (negative, digits) = match s.strip_prefix('⁻') {
+        Some(digits) => (true, digits),
+        None => (false, s),
+    };
+    let digits: String = digits
+        .chars()
+        .map(|c| SUPERSCRIPT_DIGITS.iter().position(|&d| d == c).map(|i| char::from(b'0' + i as u8)))
+        .collect::<Option<String>>()?;
+    let n: i64 = digits.parse().ok()?;
+    Some(if negative { -n } else { n })
+}
+
+/// Reads a single number of any type, such as a `u32` or an `f64`.
+///
+/// Surrounding spaces are ignored; anything else that is not part of the number is reported as
+/// an [`MathError::InvalidToken`] pointing at the number.
+pub fn parse_number<T: FromStr>(input: &str) -> Result<T, MathError> {
+    let s = input.trim();
+    if s.is_empty() {
+        return Err(MathError::EmptyInput);
+    }
+    s.parse()
+        .map_err(|_| MathError::InvalidToken { token: s.to_string(), position: Span::of(s, input) })
+}
+
+/// Reads a list of numbers separated by spaces and/or commas, such as `12, 18 30`.
+///
+/// Every item must be a valid number: an invalid one is reported with its position rather than
+/// being skipped.
+pub fn parse_numbers<T: FromStr>(input: &str) -> Result<Vec<T>, MathError> {
+    let numbers = input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(|s| parse_number(s).map_err(|e| e.shifted(Span::of(s, input).start)))
+        .collect::<Result<Vec<T>, _>>()?;
+    if numbers.is_empty() {
+        return Err(MathError::EmptyInput);
+    }
+    Ok(numbers)
+}
+
+/// The most numbers a single range such as `1..40` may stand for.
+const MAX_RANGE_LENGTH: u64 = 100_000;
+
+/// Reads a list of whole numbers separated by spaces and/or commas, where a range such as
+/// `1..40` (or `1..=40`) stands for every number from the first to the last inclusive.
+pub fn parse_whole_numbers(input: &str) -> Result<Vec<u64>, MathError> {
+    let mut numbers = Vec::new();
+    for item in input.split(|c: char| c == ',' || c.is_whitespace()).filter(|s| !s.is_empty()) {
+        let offset = Span::of(item, input).start;
+        let Some((first, last)) = item.split_once("..") else {
+            numbers.push(parse_number(item).map_err(|e| e.shifted(offset))?);
+            continue;
+        };
+        let last = last.strip_prefix('=').unwrap_or(last);
+        let start: u64 = parse_number(first).map_err(|e| e.shifted(offset))?;
+        let end: u64 = parse_number(last).map_err(|e| e.shifted(Span::of(last, input).start))?;
+        if end < start || end - start >= MAX_RANGE_LENGTH {
+            return Err(MathError::InvalidToken {
+                token: item.to_string(),
+                position: Span::of(item, input),
+            });
+        }
+        numbers.extend(start..=end);
+    }
+    if numbers.is_empty() {
+        return Err(MathError::EmptyInput);
+    }
+    Ok(numbers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hcf_of_two_numbers() {
+        assert_eq!(hcf(12_u32, 18), 6);
+        assert_eq!(hcf(17_u64, 5), 1);
+        assert_eq!(hcf(0_u8, 9), 9);
+        assert_eq!(hcf(9_u128, 0), 9);
+        assert_eq!(hcf(BigUint::from(12_u32), BigUint::from(18_u32)), BigUint::from(6_u32));
+    }
+
+    #[test]
+    fn lcm_of_two_numbers() {
+        assert_eq!(lcm(4_u32, 6), Some(12));
+        assert_eq!(lcm(7_u32, 3), Some(21));
+        assert_eq!(lcm(0_u32, 5), Some(0));
+    }
+
+    #[test]
+    fn hcf_and_lcm_of_lists() {
+        assert_eq!(hcf_of(&[12_u32, 18, 30]), Some(6));
+        assert_eq!(lcm_of(&[4_u32, 6, 8]), Ok(24));
+        assert_eq!(hcf_of::<u32>(&[]), None);
+        assert_eq!(lcm_of::<u32>(&[]), Err(MathError::EmptyInput));
+    }
+
+    #[test]
+    fn detects_overflow_at_every_width() {
+        let one_to_thirty: Vec<u32> = (1..=30).collect();
+        assert_eq!(lcm_of(&one_to_thirty), Err(MathError::Overflow));
+        assert_eq!(lcm(u8::MAX, 2), None);
+        let wide: Vec<u64> = (1..=30).collect();
+        assert_eq!(lcm_of(&wide), Ok(2_329_089_562_800));
+    }
+
+    #[test]
+    fn falls_back_to_big_integers() {
+        let one_to_forty: Vec<u64> = (1..=40).collect();
+        assert_eq!(exact_lcm_of(&one_to_forty), Ok(Natural::Fixed(5_342_931_457_063_200)));
+
+        let one_to_hundred: Vec<u64> = (1..=100).collect();
+        let lcm = exact_lcm_of(&one_to_hundred).unwrap();
+        assert_eq!(lcm.to_string(), "69720375229712477164533808935312303556800");
+        assert_eq!(lcm.to_u128(), None);
+        assert_eq!(serde_json::to_value(&lcm).unwrap(), serde_json::json!("69720375229712477164533808935312303556800"));
+        assert_eq!(Natural::from_big(BigUint::from(12_u32)), Natural::Fixed(12));
+    }
+
+    #[test]
+    fn writes_numbers_beyond_u64_as_strings() {
+        let one_to_fifty: Vec<u64> = (1..=50).collect();
+        let lcm = exact_lcm_of(&one_to_fifty).unwrap();
+        assert!(lcm.to_u128().is_some_and(|value| value > u128::from(u64::MAX)));
+        assert_eq!(serde_json::to_value(&lcm).unwrap(), serde_json::json!(lcm.to_string()));
+        assert_eq!(serde_json::to_value(Natural::Fixed(u128::from(u64::MAX))).unwrap(), serde_json::json!(u64::MAX));
+    }
+
+    #[test]
+    fn reads_ranges_of_numbers() {
+        assert_eq!(parse_whole_numbers("1..4, 10"), Ok(vec![1, 2, 3, 4, 10]));
+        assert_eq!(parse_whole_numbers("2..=3"), Ok(vec![2, 3]));
+        assert_eq!(
+            parse_whole_numbers("5 9..2"),
+            Err(MathError::InvalidToken { token: "9..2".to_string(), position: Span::new(2, 6) })
+        );
+        assert_eq!(
+            parse_whole_numbers("1..x"),
+            Err(MathError::InvalidToken { token: "x".to_string(), position: Span::new(3, 4) })
+        );
+    }
+
+    #[test]
+    fn writes_and_reads_superscripts() {
+        assert_eq!(superscript(-34), "⁻³⁴");
+        assert_eq!(superscript(0), "⁰");
+        assert_eq!(parse_superscript("⁻³⁴"), Some(-34));
+        assert_eq!(parse_superscript("¹⁰"), Some(10));
+        assert_eq!(parse_superscript("1"), None);
+        assert_eq!(parse_superscript(""), None);
+    }
+
+    #[test]
+    fn multiples() {
+        assert_eq!(get_multiples(3, 5), Ok(vec![3, 6, 9, 12, 15]));
+        assert_eq!(get_multiples(3, 0), Ok(vec![]));
+        assert_eq!(get_multiples(4_000_000_000, 3), Ok(vec![4_000_000_000, 8_000_000_000, 12_000_000_000]));
+        assert_eq!(get_multiples(u32::MAX, 2), Ok(vec![4_294_967_295, 8_589_934_590]));
+        assert_eq!(get_multiples(u32::MAX, 100_000).unwrap().last(), Some(&429_496_729_500_000));
+        assert_eq!(get_multiples(1, 100_001), Err(MathError::TooLarge { maximum: 100_000 }));
+        assert_eq!(get_multiples(1, usize::MAX), Err(MathError::TooLarge { maximum: 100_000 }));
+    }
+
+    #[test]
+    fn parses_lists_of_numbers() {
+        assert_eq!(parse_numbers::<u32>("12, 18 30"), Ok(vec![12, 18, 30]));
+        assert_eq!(parse_number::<f64>(" 2.5 "), Ok(2.5));
+        assert_eq!(parse_numbers::<u32>(" , "), Err(MathError::EmptyInput));
+        assert_eq!(
+            parse_numbers::<u32>("12 1o 30"),
+            Err(MathError::InvalidToken { token: "1o".to_string(), position: Span::new(3, 5) })
+        );
+    }
+}
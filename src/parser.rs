@@ -0,0 +1,474 @@
+//! Reading expressions such as `2(3 + 4)² ÷ √9` into a tree the calculators can evaluate, work
+//! through step by step or round.
+//!
+//! The input is first split into [`Token`]s, then read into an [`Expr`] by precedence climbing.
+//! Every token, and every part of the tree, keeps the [`Span`] of the input it was read from, so
+//! errors and working can point back at exactly what was typed.
+
+use std::fmt;
+
+use crate::decimal::Decimal;
+use crate::error::{MathError, Span};
+use crate::number::{parse_superscript, superscript};
+
+/// The precedence of `^`, which is also how tightly a leading `-` or `√` holds the value after
+/// it, so `-2^2` is `-(2^2)`.
+const POWER_PRECEDENCE: u8 = 3;
+
+/// The kinds of symbol an expression is made of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenKind {
+    Number(Decimal),
+    /// The name of a constant, variable or function, such as `pi` or `sqrt`.
+    Name(String),
+    Plus,
+    /// `-` or `−`.
+    Minus,
+    /// `*` or `×`.
+    Times,
+    /// `/` or `÷`.
+    Divide,
+    Remainder,
+    Power,
+    /// A power written in superscript digits, such as `²`.
+    Index(u32),
+    Root,
+    OpenBracket,
+    CloseBracket,
+    Comma,
+    Equals,
+}
+
+/// One symbol of an expression and where it was written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+/// Splits an expression into tokens, skipping whitespace.
+pub fn tokenize(input: &str) -> Result<Vec<Token>, MathError> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    while let Some(c) = input[start..].chars().next() {
+        if c.is_whitespace() {
+            start += c.len_utf8();
+            continue;
+        }
+        let rest = &input[start..];
+        let length = if c.is_ascii_digit() || c == '.' {
+            number_length(rest)
+        } else if is_name_start(c) {
+            rest.find(|c: char| !(is_name_start(c) || c.is_ascii_digit() || c == '_')).unwrap_or(rest.len())
+        } else if is_index(c) {
+            rest.find(|c: char| !is_index(c)).unwrap_or(rest.len())
+        } else {
+            c.len_utf8()
+        };
+        let text = &rest[..length];
+        let span = Span::new(start, start + length);
+        let invalid = || MathError::InvalidToken { token: text.to_string(), position: span };
+
+        let kind = match c {
+            '0'..='9' | '.' => TokenKind::Number(text.parse().map_err(|_| invalid())?),
+            _ if is_name_start(c) => TokenKind::Name(text.to_string()),
+            _ if is_index(c) => {
+                let power = parse_superscript(text).and_then(|power| u32::try_from(power).ok());
+                TokenKind::Index(power.ok_or(MathError::Overflow)?)
+            }
+            '+' => TokenKind::Plus,
+            '-' | '−' => TokenKind::Minus,
+            '*' | '×' => TokenKind::Times,
+            '/' | '÷' => TokenKind::Divide,
+            '%' => TokenKind::Remainder,
+            '^' => TokenKind::Power,
+            '√' => TokenKind::Root,
+            '(' => TokenKind::OpenBracket,
+            ')' => TokenKind::CloseBracket,
+            ',' => TokenKind::Comma,
+            '=' => TokenKind::Equals,
+            _ => return Err(invalid()),
+        };
+        tokens.push(Token { kind, span });
+        start = span.end;
+    }
+    Ok(tokens)
+}
+
+/// The length of the number at the start of `rest`: digits and decimal points, then an
+/// exponent such as `e6` or `E-3` if one follows. A lone `e` is left for the constant.
+fn number_length(rest: &str) -> usize {
+    let bytes = rest.as_bytes();
+    let mut length = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+    if matches!(bytes.get(length), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(length + 1), Some(b'+' | b'-')));
+        if bytes.get(length + 1 + sign).is_some_and(u8::is_ascii_digit) {
+            length += 1 + sign;
+            length += rest[length..].find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len() - length);
+        }
+    }
+    length
+}
+
+/// Returns `true` if `c` can start a name. Superscript digits count as numeric in Unicode, so
+/// names are limited to letters here to keep `x²` apart.
+fn is_name_start(c: char) -> bool {
+    c.is_alphabetic()
+}
+
+fn is_index(c: char) -> bool {
+    c != '⁻' && parse_superscript(c.encode_utf8(&mut [0; 4])).is_some()
+}
+
+/// An operation between two values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Power,
+}
+
+impl BinaryOp {
+    /// The symbol used when writing the operation out, e.g. `×` for multiplication.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Subtract => "-",
+            BinaryOp::Multiply => "×",
+            BinaryOp::Divide => "÷",
+            BinaryOp::Remainder => "%",
+            BinaryOp::Power => "^",
+        }
+    }
+
+    /// How tightly the operation holds its values: powers before multiplication and division,
+    /// and those before addition and subtraction.
+    pub fn precedence(self) -> u8 {
+        match self {
+            BinaryOp::Add | BinaryOp::Subtract => 1,
+            BinaryOp::Multiply | BinaryOp::Divide | BinaryOp::Remainder => 2,
+            BinaryOp::Power => POWER_PRECEDENCE,
+        }
+    }
+}
+
+/// A part of an expression and where it was written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+/// The kinds of part an expression is built from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprKind {
+    Number(Decimal),
+    /// A constant or variable, such as `pi`.
+    Name(String),
+    Negate(Box<Expr>),
+    Binary {
+        op: BinaryOp,
+        left: Box<Expr>,
+        right: Box<Expr>,
+        /// `true` for a multiplication written without a sign, as in `2(3 + 4)`.
+        implicit: bool,
+    },
+    /// A power written in superscript digits, such as `x²`.
+    Index(Box<Expr>, u32),
+    SquareRoot(Box<Expr>),
+    Call { name: String, arguments: Vec<Expr> },
+    Brackets(Box<Expr>),
+}
+
+impl Expr {
+    /// The parts directly inside this one, in the order they were written.
+    pub fn children(&self) -> Vec<&Expr> {
+        match &self.kind {
+            ExprKind::Number(_) | ExprKind::Name(_) => Vec::new(),
+            ExprKind::Negate(operand)
+            | ExprKind::Index(operand, _)
+            | ExprKind::SquareRoot(operand)
+            | ExprKind::Brackets(operand) => vec![operand],
+            ExprKind::Binary { left, right, .. } => vec![left, right],
+            ExprKind::Call { arguments, .. } => arguments.iter().collect(),
+        }
+    }
+
+    /// The parts directly inside this one, in the order they were written, for changing them.
+    pub fn children_mut(&mut self) -> Vec<&mut Expr> {
+        match &mut self.kind {
+            ExprKind::Number(_) | ExprKind::Name(_) => Vec::new(),
+            ExprKind::Negate(operand)
+            | ExprKind::Index(operand, _)
+            | ExprKind::SquareRoot(operand)
+            | ExprKind::Brackets(operand) => vec![operand],
+            ExprKind::Binary { left, right, .. } => vec![left, right],
+            ExprKind::Call { arguments, .. } => arguments.iter_mut().collect(),
+        }
+    }
+
+    /// Every number written in the expression, from left to right.
+    pub fn numbers(&self) -> Vec<(&Decimal, Span)> {
+        match &self.kind {
+            ExprKind::Number(number) => vec![(number, self.span)],
+            _ => self.children().into_iter().flat_map(Expr::numbers).collect(),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    /// Writes the expression out with `×` and `÷` and single spaces around operators, keeping
+    /// its brackets, e.g. `2(3 + 4) ÷ 7`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ExprKind::Number(number) => write!(f, "{}", number),
+            ExprKind::Name(name) => write!(f, "{}", name),
+            ExprKind::Negate(operand) => write!(f, "-{}", operand),
+            ExprKind::Binary { op: BinaryOp::Power, left, right, .. } => write!(f, "{}^{}", left, right),
+            // Two numbers side by side would read as one, so they get a sign between them.
+            ExprKind::Binary { left, right, implicit: true, .. } if !matches!(right.kind, ExprKind::Number(_)) => {
+                write!(f, "{}{}", left, right)
+            }
+            ExprKind::Binary { op, left, right, .. } => write!(f, "{} {} {}", left, op.symbol(), right),
+            ExprKind::Index(base, power) => write!(f, "{}{}", base, superscript(i64::from(*power))),
+            ExprKind::SquareRoot(operand) => write!(f, "√{}", operand),
+            ExprKind::Call { name, arguments } => {
+                let arguments: Vec<String> = arguments.iter().map(Expr::to_string).collect();
+                write!(f, "{}({})", name, arguments.join(", "))
+            }
+            ExprKind::Brackets(inner) => write!(f, "({})", inner),
+        }
+    }
+}
+
+/// Reads an expression into a tree.
+///
+/// The usual order of operations applies, with `^` grouping from the right. A multiplication
+/// sign may be left out before a bracket, a name or `√`, as in `2(3 + 4)`, `2pi` or `3√2`, and
+/// binds as tightly as a written one. A name followed by a bracket is read as a function call.
+pub fn parse(input: &str) -> Result<Expr, MathError> {
+    let tokens = tokenize(input)?;
+    parse_tokens(input, &tokens)
+}
+
+/// Reads tokens taken from `input` into a tree, for when they have already been split up, for
+/// example around an `=`.
+pub fn parse_tokens(input: &str, tokens: &[Token]) -> Result<Expr, MathError> {
+    if tokens.is_empty() {
+        return Err(MathError::EmptyInput);
+    }
+    let mut parser = Parser { input, tokens, position: 0 };
+    let expr = parser.expression(0)?;
+    match parser.peek() {
+        Some(token) => Err(parser.unexpected(token)),
+        None => Ok(expr),
+    }
+}
+
+/// Reads a list of tokens by precedence climbing.
+struct Parser<'a> {
+    input: &'a str,
+    tokens: &'a [Token],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<&'a Token, MathError> {
+        let token = self.tokens.get(self.position).ok_or_else(|| MathError::InvalidExpression {
+            message: "the expression is incomplete".to_string(),
+        })?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn unexpected(&self, token: &Token) -> MathError {
+        let text = &self.input[token.span.start..token.span.end];
+        MathError::InvalidToken { token: text.to_string(), position: token.span }
+    }
+
+    /// Reads values joined by operators that hold at least as tightly as `min_precedence`.
+    fn expression(&mut self, min_precedence: u8) -> Result<Expr, MathError> {
+        let mut left = self.prefix()?;
+        while let Some(token) = self.peek() {
+            let (op, implicit) = match token.kind {
+                TokenKind::Plus => (BinaryOp::Add, false),
+                TokenKind::Minus => (BinaryOp::Subtract, false),
+                TokenKind::Times => (BinaryOp::Multiply, false),
+                TokenKind::Divide => (BinaryOp::Divide, false),
+                TokenKind::Remainder => (BinaryOp::Remainder, false),
+                TokenKind::Power => (BinaryOp::Power, false),
+                TokenKind::Name(_) | TokenKind::OpenBracket | TokenKind::Root => (BinaryOp::Multiply, true),
+                _ => break,
+            };
+            if op.precedence() < min_precedence {
+                break;
+            }
+            if !implicit {
+                self.position += 1;
+            }
+            // `^` groups from the right, so 2^3^2 is 2^9; everything else groups from the left.
+            let next_precedence = if op == BinaryOp::Power { op.precedence() } else { op.precedence() + 1 };
+            let right = self.expression(next_precedence)?;
+            let span = Span::new(left.span.start, right.span.end);
+            left = Expr { kind: ExprKind::Binary { op, left: Box::new(left), right: Box::new(right), implicit }, span };
+        }
+        Ok(left)
+    }
+
+    /// Reads a value with any signs or `√` in front of it.
+    fn prefix(&mut self) -> Result<Expr, MathError> {
+        let token = self.next()?.clone();
+        let wrap = |kind: fn(Box<Expr>) -> ExprKind, operand: Expr| {
+            let span = Span::new(token.span.start, operand.span.end);
+            Expr { kind: kind(Box::new(operand)), span }
+        };
+        match token.kind {
+            TokenKind::Plus => self.expression(POWER_PRECEDENCE),
+            TokenKind::Minus => Ok(wrap(ExprKind::Negate, self.expression(POWER_PRECEDENCE)?)),
+            TokenKind::Root => Ok(wrap(ExprKind::SquareRoot, self.expression(POWER_PRECEDENCE)?)),
+            _ => {
+                self.position -= 1;
+                self.postfix()
+            }
+        }
+    }
+
+    /// Reads a value followed by any superscript powers, such as `x²`.
+    fn postfix(&mut self) -> Result<Expr, MathError> {
+        let mut expr = self.primary()?;
+        while let Some(&Token { kind: TokenKind::Index(power), span }) = self.peek() {
+            self.position += 1;
+            let span = Span::new(expr.span.start, span.end);
+            expr = Expr { kind: ExprKind::Index(Box::new(expr), power), span };
+        }
+        Ok(expr)
+    }
+
+    /// Reads a number, a name, a function call or a bracket.
+    fn primary(&mut self) -> Result<Expr, MathError> {
+        let token = self.next()?.clone();
+        match token.kind {
+            TokenKind::Number(number) => Ok(Expr { kind: ExprKind::Number(number), span: token.span }),
+            TokenKind::Name(name) => {
+                if self.peek().is_some_and(|next| next.kind == TokenKind::OpenBracket) {
+                    self.position += 1;
+                    let mut arguments = vec![self.expression(0)?];
+                    while self.peek().is_some_and(|next| next.kind == TokenKind::Comma) {
+                        self.position += 1;
+                        arguments.push(self.expression(0)?);
+                    }
+                    let end = self.close_bracket()?;
+                    let span = Span::new(token.span.start, end);
+                    return Ok(Expr { kind: ExprKind::Call { name, arguments }, span });
+                }
+                Ok(Expr { kind: ExprKind::Name(name), span: token.span })
+            }
+            TokenKind::OpenBracket => {
+                let inner = self.expression(0)?;
+                let end = self.close_bracket()?;
+                Ok(Expr { kind: ExprKind::Brackets(Box::new(inner)), span: Span::new(token.span.start, end) })
+            }
+            _ => Err(self.unexpected(&token)),
+        }
+    }
+
+    /// Reads the `)` that closes a bracket, returning where it ends.
+    fn close_bracket(&mut self) -> Result<usize, MathError> {
+        match self.peek() {
+            Some(token) if token.kind == TokenKind::CloseBracket => {
+                self.position += 1;
+                Ok(token.span.end)
+            }
+            Some(token) => Err(self.unexpected(token)),
+            None => Err(MathError::InvalidExpression { message: "a bracket was opened but not closed".to_string() }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(input: &str) -> Vec<TokenKind> {
+        tokenize(input).unwrap().into_iter().map(|token| token.kind).collect()
+    }
+
+    #[test]
+    fn splits_into_tokens() {
+        assert_eq!(
+            kinds("2x² − 1.5e3÷π"),
+            vec![
+                TokenKind::Number(Decimal::from(2)),
+                TokenKind::Name("x".to_string()),
+                TokenKind::Index(2),
+                TokenKind::Minus,
+                TokenKind::Number(Decimal::from(1500)),
+                TokenKind::Divide,
+                TokenKind::Name("π".to_string()),
+            ]
+        );
+        // A lone `e` after a number is the constant, not an exponent.
+        assert_eq!(kinds("2e"), vec![TokenKind::Number(Decimal::from(2)), TokenKind::Name("e".to_string())]);
+        assert_eq!(tokenize("3 × 4").unwrap()[1].span, Span::new(2, 4));
+    }
+
+    #[test]
+    fn follows_the_order_of_operations() {
+        assert_eq!(parse("20 - 3 * 4 + 2").unwrap().to_string(), "20 - 3 × 4 + 2");
+        let expr = parse("2^3^2").unwrap();
+        let ExprKind::Binary { op: BinaryOp::Power, right, .. } = expr.kind else { panic!("expected a power") };
+        assert_eq!(right.to_string(), "3^2");
+
+        // -2² is -(2²), not (-2)².
+        let expr = parse("-2²").unwrap();
+        assert!(matches!(expr.kind, ExprKind::Negate(_)));
+        assert_eq!(expr.span, Span::new(0, 4));
+    }
+
+    #[test]
+    fn reads_implicit_multiplication() {
+        let expr = parse("2(3+4)").unwrap();
+        assert!(matches!(expr.kind, ExprKind::Binary { op: BinaryOp::Multiply, implicit: true, .. }));
+        assert_eq!(expr.to_string(), "2(3 + 4)");
+        assert_eq!(parse("3√2 ÷ 2pi").unwrap().to_string(), "3√2 ÷ 2pi");
+        assert_eq!(parse("sqrt(16, 2)").unwrap().children().len(), 2);
+    }
+
+    #[test]
+    fn lists_the_numbers_written() {
+        let expr = parse("(9.7*326)/(3.26)").unwrap();
+        let numbers: Vec<(String, Span)> = expr.numbers().into_iter().map(|(n, span)| (n.to_string(), span)).collect();
+        assert_eq!(
+            numbers,
+            vec![
+                ("9.7".to_string(), Span::new(1, 4)),
+                ("326".to_string(), Span::new(5, 8)),
+                ("3.26".to_string(), Span::new(11, 15)),
+            ]
+        );
+    }
+
+    #[test]
+    fn explains_what_is_wrong() {
+        let incomplete = MathError::InvalidExpression { message: "the expression is incomplete".to_string() };
+        assert_eq!(parse("2 +"), Err(incomplete));
+        assert_eq!(
+            parse("(2 + 3"),
+            Err(MathError::InvalidExpression { message: "a bracket was opened but not closed".to_string() })
+        );
+        let invalid = |token: &str, start, end| {
+            Err(MathError::InvalidToken { token: token.to_string(), position: Span::new(start, end) })
+        };
+        assert_eq!(parse("2 + * 3"), invalid("*", 4, 5));
+        assert_eq!(parse("2 + 3)"), invalid(")", 5, 6));
+        assert_eq!(parse("1.2.3"), invalid("1.2.3", 0, 5));
+        assert_eq!(parse(" "), Err(MathError::EmptyInput));
+    }
+}
@@ -0,0 +1,821 @@
+//! Prime numbers: primality testing, prime factorisation and listing primes with a sieve.
+//!
+//! Testing and factorising work for any unsigned integer up to a `u128`, using the Miller–Rabin
+//! and Baillie–PSW tests and Pollard's rho method.
+//!
+//! Primes are listed with the Sieve of Eratosthenes. Ranges that reach beyond what fits in memory
+//! at once are sieved in segments, so any range up to [`SIEVE_LIMIT`] can be counted.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::error::{MathError, Span};
+use crate::number::{hcf, parse_number, serialize_u128, superscript};
+
+/// The primes used for trial division before the faster tests take over.
+const SMALL_PRIMES: [u128; 25] =
+    [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97];
+
+/// Below this bound, Miller–Rabin with the first 13 primes as bases (2 to 41) is proven never to
+/// mistake a composite number for a prime. It is larger than every `u64`.
+const MILLER_RABIN_13_BASES_BOUND: u128 = 3_317_044_064_679_887_385_961_981;
+
+/// Checks if a number is prime, for any unsigned integer up to a `u128`.
+///
+/// Small numbers are settled by trial division. Larger ones use the Miller–Rabin test, which
+/// checks that `a^(n - 1) ≡ 1 (mod n)` holds for some bases `a` in a way only primes can pass.
+/// Below 3.3 × 10²⁴, which covers every `u64`, the first 13 primes as bases are proven enough.
+/// Above that the Baillie–PSW test is used: Miller–Rabin to base 2 followed by a strong Lucas
+/// test. The two catch different composites, and no composite number is known to pass both.
+pub fn is_prime<T: Into<u128>>(n: T) -> bool {
+    let n = n.into();
+    if n < 2 {
+        return false;
+    }
+    for p in SMALL_PRIMES {
+        if n == p {
+            return true;
+        }
+        if n.is_multiple_of(p) {
+            return false;
+        }
+    }
+    // A number with no prime factor up to its square root is prime.
+    if n < 100 * 100 {
+        return true;
+    }
+
+    let modulus = Montgomery::new(n);
+    if n < MILLER_RABIN_13_BASES_BOUND {
+        SMALL_PRIMES[..13].iter().all(|&base| modulus.is_strong_probable_prime(base))
+    } else {
+        modulus.is_strong_probable_prime(2) && modulus.is_strong_lucas_probable_prime()
+    }
+}
+
+/// The Jacobi symbol `(a/n)` for odd `n`: 0 if they share a factor, and otherwise 1 or -1. For a
+/// prime `n` it is 1 exactly when `a` is a square modulo `n`.
+fn jacobi(mut a: u128, mut n: u128) -> i32 {
+    a %= n;
+    let mut result = 1;
+    while a != 0 {
+        while a.is_multiple_of(2) {
+            a /= 2;
+            if n % 8 == 3 || n % 8 == 5 {
+                result = -result;
+            }
+        }
+        std::mem::swap(&mut a, &mut n);
+        if a % 4 == 3 && n % 4 == 3 {
+            result = -result;
+        }
+        a %= n;
+    }
+    if n == 1 { result } else { 0 }
+}
+
+/// Calculates the prime factorisation of `n`, for any unsigned integer up to a `u128`.
+///
+/// Returns a map from each prime factor to its exponent, in ascending order of prime, so
+/// 72 gives `{2: 3, 3: 2}`. Numbers below 2 have no prime factors and give an empty map.
+///
+/// Small factors are found by trial division and the rest by Pollard's rho method, which finds
+/// a factor `p` in roughly `√p` steps. Numbers with at most one prime factor above 10¹⁵ or so
+/// are factorised almost instantly; a product of two primes that are both around 10¹⁸ or
+/// larger can take minutes.
+pub fn prime_factorization<T: Into<u128>>(n: T) -> BTreeMap<u128, u32> {
+    let mut n = n.into();
+    let mut factors = BTreeMap::new();
+    if n < 2 {
+        return factors;
+    }
+
+    for p in SMALL_PRIMES {
+        while n.is_multiple_of(p) {
+            *factors.entry(p).or_insert(0) += 1;
+            n /= p;
+        }
+    }
+
+    // Split what is left until every part is prime.
+    let mut unsplit = if n > 1 { vec![n] } else { Vec::new() };
+    while let Some(m) = unsplit.pop() {
+        if is_prime(m) {
+            *factors.entry(m).or_insert(0) += 1;
+        } else {
+            let factor = pollard_rho(m);
+            unsplit.push(factor);
+            unsplit.push(m / factor);
+        }
+    }
+    factors
+}
+
+/// Finds a factor of `n` other than 1 and `n`, where `n` is odd, composite and has no factor
+/// in [`SMALL_PRIMES`].
+///
+/// The sequence `x → x² + c (mod n)` must eventually repeat, and it repeats modulo each prime
+/// factor `p` of `n` much sooner, after about `√p` steps. When two values agree modulo `p`
+/// their difference shares the factor `p` with `n`, which the HCF reveals. Brent's variant
+/// multiplies many differences together before taking each HCF, to save work.
+fn pollard_rho(n: u128) -> u128 {
+    const BATCH: u32 = 128;
+    let modulus = Montgomery::new(n);
+
+    for c in 1.. {
+        let c = modulus.to_montgomery(c);
+        let step = |x: u128| modulus.add(modulus.mul(x, x), c);
+        let (mut x, mut y, mut saved) = (0, modulus.to_montgomery(2), 0);
+        let mut product = modulus.one();
+        let mut factor = 1;
+        let mut length = 1;
+
+        while factor == 1 {
+            x = y;
+            for _ in 0..length {
+                y = step(y);
+            }
+            let mut done = 0;
+            while done < length && factor == 1 {
+                saved = y;
+                for _ in 0..BATCH.min(length - done) {
+                    y = step(y);
+                    product = modulus.mul(product, x.abs_diff(y));
+                }
+                factor = hcf(product, n);
+                done += BATCH;
+            }
+            length *= 2;
+        }
+
+        // If the batch overshot and every factor came together, go back one step at a time.
+        if factor == n {
+            loop {
+                saved = step(saved);
+                factor = hcf(x.abs_diff(saved), n);
+                if factor != 1 {
+                    break;
+                }
+            }
+        }
+        if factor != n {
+            return factor;
+        }
+    }
+    unreachable!("some constant c finds a factor of every composite number")
+}
+
+/// Arithmetic modulo an odd number `n` in Montgomery form, where `a` is stored as `aR mod n`
+/// with `R = 2¹²⁸`. Multiplying then needs no division by `n`, and the full 256-bit products
+/// that moduli above 2⁶⁴ need are handled without overflow.
+struct Montgomery {
+    n: u128,
+    /// `-n⁻¹ mod R`.
+    n_inverse: u128,
+    /// `R² mod n`, for converting into Montgomery form.
+    r_squared: u128,
+}
+
+impl Montgomery {
+    fn new(n: u128) -> Montgomery {
+        debug_assert!(n % 2 == 1, "the modulus must be odd");
+        // Newton's method doubles the number of correct low bits each time: 1, 2, 4, ..., 128.
+        let mut inverse: u128 = 1;
+        for _ in 0..7 {
+            inverse = inverse.wrapping_mul(2u128.wrapping_sub(n.wrapping_mul(inverse)));
+        }
+        // R mod n, doubled 128 more times, gives R² mod n.
+        let mut r_squared = (u128::MAX % n + 1) % n;
+        for _ in 0..128 {
+            r_squared = add_mod(r_squared, r_squared, n);
+        }
+        Montgomery { n, n_inverse: inverse.wrapping_neg(), r_squared }
+    }
+
+    /// Computes `TR⁻¹ mod n` for the 256-bit number `T = high·R + low`, where `T < nR`.
+    fn reduce(&self, high: u128, low: u128) -> u128 {
+        let m = low.wrapping_mul(self.n_inverse);
+        let (mn_high, mn_low) = widening_mul(m, self.n);
+        let (_, carry) = low.overflowing_add(mn_low);
+        let (sum, overflow_1) = high.overflowing_add(mn_high);
+        let (sum, overflow_2) = sum.overflowing_add(carry as u128);
+        // The result is below 2n, so at most one subtraction is needed.
+        if overflow_1 || overflow_2 || sum >= self.n { sum.wrapping_sub(self.n) } else { sum }
+    }
+
+    fn mul(&self, a: u128, b: u128) -> u128 {
+        let (high, low) = widening_mul(a, b);
+        self.reduce(high, low)
+    }
+
+    fn add(&self, a: u128, b: u128) -> u128 {
+        add_mod(a, b, self.n)
+    }
+
+    fn one(&self) -> u128 {
+        (u128::MAX % self.n + 1) % self.n
+    }
+
+    fn to_montgomery(&self, a: u128) -> u128 {
+        self.mul(a % self.n, self.r_squared)
+    }
+
+    /// Computes `base^exponent`, with `base` in Montgomery form.
+    fn pow(&self, mut base: u128, mut exponent: u128) -> u128 {
+        let mut result = self.one();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            base = self.mul(base, base);
+            exponent >>= 1;
+        }
+        result
+    }
+
+    /// One round of the Miller–Rabin test. Writing `n - 1 = d × 2ˢ` with `d` odd, a prime `n`
+    /// must have `a^d ≡ 1` or `a^(d × 2ʳ) ≡ -1 (mod n)` for some `r < s`.
+    fn is_strong_probable_prime(&self, base: u128) -> bool {
+        let base = base % self.n;
+        if base == 0 {
+            return true;
+        }
+        let shifts = (self.n - 1).trailing_zeros();
+        let one = self.one();
+        let minus_one = self.n - one;
+        let mut x = self.pow(self.to_montgomery(base), (self.n - 1) >> shifts);
+        if x == one || x == minus_one {
+            return true;
+        }
+        for _ in 1..shifts {
+            x = self.mul(x, x);
+            if x == minus_one {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// The strong Lucas test, with the parameters chosen by Selfridge's method: `D` is the first
+    /// of 5, -7, 9, -11, … with `(D/n) = -1`, `P = 1` and `Q = (1 - D) / 4`. Writing
+    /// `n + 1 = d × 2ˢ` with `d` odd, a prime `n` must have `U_d ≡ 0` or `V_(d × 2ʳ) ≡ 0 (mod n)`
+    /// for some `r < s`, where `U` and `V` are the Lucas sequences for `P` and `Q`.
+    fn is_strong_lucas_probable_prime(&self) -> bool {
+        let n = self.n;
+        // A square has no D with (D/n) = -1, so it is ruled out first.
+        let root = n.isqrt();
+        if root * root == n {
+            return false;
+        }
+        let mut d: i128 = 5;
+        loop {
+            let d_mod_n = if d < 0 { n - d.unsigned_abs() % n } else { d.unsigned_abs() % n };
+            match jacobi(d_mod_n, n) {
+                -1 => break,
+                0 if d.unsigned_abs() < n => return false,
+                _ => d = if d < 0 { 2 - d } else { -d - 2 },
+            }
+        }
+
+        let signed = |value: i128| {
+            let magnitude = self.to_montgomery(value.unsigned_abs());
+            if value < 0 { self.sub(0, magnitude) } else { magnitude }
+        };
+        let (p, q, big_d) = (self.one(), signed((1 - d) / 4), signed(d));
+        // n is odd and not u128::MAX, which has the factor 3, so n + 1 does not overflow.
+        let shifts = (n + 1).trailing_zeros();
+        let odd = (n + 1) >> shifts;
+
+        // Work up to U_odd and V_odd by doubling k, and adding 1 where `odd` has a 1 bit.
+        let (mut u, mut v, mut q_k) = (p, p, q);
+        for bit in (0..odd.ilog2()).rev() {
+            u = self.mul(u, v);
+            v = self.sub(self.mul(v, v), self.add(q_k, q_k));
+            q_k = self.mul(q_k, q_k);
+            if odd >> bit & 1 == 1 {
+                (u, v) = (
+                    self.half(self.add(self.mul(p, u), v)),
+                    self.half(self.add(self.mul(big_d, u), self.mul(p, v))),
+                );
+                q_k = self.mul(q_k, q);
+            }
+        }
+        if u == 0 || v == 0 {
+            return true;
+        }
+        for _ in 1..shifts {
+            v = self.sub(self.mul(v, v), self.add(q_k, q_k));
+            if v == 0 {
+                return true;
+            }
+            q_k = self.mul(q_k, q_k);
+        }
+        false
+    }
+
+    fn sub(&self, a: u128, b: u128) -> u128 {
+        if a >= b { a - b } else { self.n - (b - a) }
+    }
+
+    /// Computes `a / 2 mod n`, which is the same in Montgomery form.
+    fn half(&self, a: u128) -> u128 {
+        // (a + n) / 2 is worked out without forming a + n, which could overflow.
+        if a.is_multiple_of(2) { a / 2 } else { a / 2 + self.n / 2 + 1 }
+    }
+}
+
+/// Computes `(a + b) mod n` for `a, b < n` without overflowing.
+fn add_mod(a: u128, b: u128, n: u128) -> u128 {
+    let (sum, overflow) = a.overflowing_add(b);
+    if overflow || sum >= n { sum.wrapping_sub(n) } else { sum }
+}
+
+/// Multiplies two `u128`s into a 256-bit product, returned as its high and low halves.
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const LOW: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & LOW);
+    let (b_high, b_low) = (b >> 64, b & LOW);
+    let low_low = a_low * b_low;
+    let low_high = a_low * b_high;
+    let high_low = a_high * b_low;
+    let middle = (low_low >> 64) + (low_high & LOW) + (high_low & LOW);
+    let low = (low_low & LOW) | (middle << 64);
+    let high = a_high * b_high + (low_high >> 64) + (high_low >> 64) + (middle >> 64);
+    (high, low)
+}
+
+/// Formats a prime factorisation as a product of powers, e.g. `2^3 * 3^2`.
+pub fn format_factors(factors: &BTreeMap<u128, u32>) -> String {
+    factors
+        .iter()
+        .map(|(base, exp)| {
+            if *exp == 1 {
+                format!("{}", base)
+            } else {
+                format!("{}^{}", base, exp)
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" * ")
+}
+
+/// Formats a prime factorisation in index form with superscripts, e.g. `2³ × 3²`.
+pub fn format_index_form(factors: &BTreeMap<u128, u32>) -> String {
+    factors
+        .iter()
+        .map(|(base, exp)| if *exp == 1 { base.to_string() } else { format!("{}{}", base, superscript(*exp as i64)) })
+        .collect::<Vec<String>>()
+        .join(" × ")
+}
+
+/// One division in breaking a number down into primes: `number = prime × quotient`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct DivisionStep {
+    #[serde(serialize_with = "serialize_u128")]
+    pub number: u128,
+    #[serde(serialize_with = "serialize_u128")]
+    pub prime: u128,
+    #[serde(serialize_with = "serialize_u128")]
+    pub quotient: u128,
+}
+
+/// Breaks `n` down by dividing by its smallest prime factor again and again until 1 is left,
+/// the way a factor tree or a division ladder is worked out by hand. Numbers below 2 give no
+/// steps.
+pub fn division_steps<T: Into<u128>>(n: T) -> Vec<DivisionStep> {
+    let mut number = n.into();
+    let mut steps = Vec::new();
+    for (prime, exponent) in prime_factorization(number) {
+        for _ in 0..exponent {
+            steps.push(DivisionStep { number, prime, quotient: number / prime });
+            number /= prime;
+        }
+    }
+    steps
+}
+
+/// Draws the steps as a factor tree, splitting off one prime on each branch:
+///
+/// ```text
+/// 18
+/// ├── 2
+/// └── 9
+///     ├── 3
+///     └── 3
+/// ```
+pub fn factor_tree(steps: &[DivisionStep]) -> Vec<String> {
+    let Some(first) = steps.first() else {
+        return Vec::new();
+    };
+    let mut lines = vec![first.number.to_string()];
+    // The tree stops once both branches are prime, so the final division by itself is left out.
+    for (depth, step) in steps.iter().filter(|step| step.quotient > 1).enumerate() {
+        let indent = "    ".repeat(depth);
+        lines.push(format!("{}├── {}", indent, step.prime));
+        lines.push(format!("{}└── {}", indent, step.quotient));
+    }
+    lines
+}
+
+/// Draws the steps as a division ladder, with each prime divided out on the left:
+///
+/// ```text
+/// 2 │ 18
+/// 3 │ 9
+/// 3 │ 3
+///   │ 1
+/// ```
+pub fn division_ladder(steps: &[DivisionStep]) -> Vec<String> {
+    let width = steps.iter().map(|step| step.prime.to_string().len()).max().unwrap_or(0);
+    let mut lines: Vec<String> =
+        steps.iter().map(|step| format!("{:>width$} │ {}", step.prime, step.number, width = width)).collect();
+    if !steps.is_empty() {
+        lines.push(format!("{:>width$} │ 1", "", width = width));
+    }
+    lines
+}
+
+/// The largest number that primes can be listed up to.
+pub const SIEVE_LIMIT: u64 = 10_000_000_000;
+
+/// How many primes a [`PrimeRange`] keeps to list; the rest are counted but not stored.
+pub const LISTED_PRIMES: usize = 1000;
+
+/// How many odd numbers are sieved at a time. A segment fits comfortably in a CPU cache.
+const SEGMENT_LENGTH: usize = 1 << 16;
+
+/// Lists every prime up to and including `limit` with the Sieve of Eratosthenes.
+///
+/// This marks one number per byte, so it is meant for limits up to a few million; use
+/// [`for_each_prime_between`] for larger ranges.
+pub fn sieve(limit: u64) -> Vec<u64> {
+    let limit = limit as usize;
+    let mut is_composite = vec![false; limit + 1];
+    let mut primes = Vec::new();
+    for n in 2..=limit {
+        if is_composite[n] {
+            continue;
+        }
+        primes.push(n as u64);
+        // Smaller multiples of n were already crossed out by smaller primes.
+        for multiple in (n * n..=limit).step_by(n) {
+            is_composite[multiple] = true;
+        }
+    }
+    primes
+}
+
+/// Calls `f` with every prime from `start` to `end` inclusive, in ascending order.
+///
+/// The range is sieved in segments of odd numbers, crossing out multiples of the primes up to
+/// `√end`, so only a small amount of memory is used however wide the range is. Returns
+/// [`MathError::TooLarge`] if `end` is above [`SIEVE_LIMIT`].
+pub fn for_each_prime_between(start: u64, end: u64, mut f: impl FnMut(u64)) -> Result<(), MathError> {
+    if end > SIEVE_LIMIT {
+        return Err(MathError::TooLarge { maximum: SIEVE_LIMIT });
+    }
+    if start <= 2 && 2 <= end {
+        f(2);
+    }
+
+    let base_primes = sieve(end.isqrt());
+    let mut is_composite = vec![false; SEGMENT_LENGTH];
+    // Each segment holds the odd numbers low, low + 2, low + 4, ...
+    let mut low = start.max(3) | 1;
+    while low <= end {
+        let high = end.min(low + 2 * (SEGMENT_LENGTH as u64 - 1));
+        let length = ((high - low) / 2 + 1) as usize;
+        is_composite[..length].fill(false);
+
+        for &p in base_primes.iter().skip(1).take_while(|&&p| p * p <= high) {
+            // Start at the first odd multiple of p in the segment, but never at p itself.
+            let mut multiple = (p * p).max(low.div_ceil(p) * p);
+            if multiple.is_multiple_of(2) {
+                multiple += p;
+            }
+            while multiple <= high {
+                is_composite[((multiple - low) / 2) as usize] = true;
+                multiple += 2 * p;
+            }
+        }
+
+        for (i, _) in is_composite[..length].iter().enumerate().filter(|(_, composite)| !**composite) {
+            f(low + 2 * i as u64);
+        }
+        low = high + 2;
+    }
+    Ok(())
+}
+
+/// Reads the range to list primes in: a single number `N` for the primes up to `N`, or two
+/// numbers written as `A B`, `A, B` or `A..B` for the primes from `A` to `B` inclusive.
+pub fn parse_prime_range(input: &str) -> Result<(u64, u64), MathError> {
+    let s = input.trim();
+    if s.is_empty() {
+        return Err(MathError::EmptyInput);
+    }
+    let parts: Vec<&str> = match s.split_once("..") {
+        Some((first, last)) => vec![first, last.strip_prefix('=').unwrap_or(last)],
+        None => s.split(|c: char| c == ',' || c.is_whitespace()).filter(|part| !part.is_empty()).collect(),
+    };
+    let read = |part: &str| parse_number::<u64>(part).map_err(|e| e.shifted(Span::of(part, input).start));
+    let (start, end) = match parts[..] {
+        [end] => (0, read(end)?),
+        [start, end] => (read(start)?, read(end)?),
+        _ => {
+            let extra = parts[2];
+            return Err(MathError::InvalidToken { token: extra.to_string(), position: Span::of(extra, input) });
+        }
+    };
+    if start > end {
+        return Err(MathError::InvalidToken { token: s.to_string(), position: Span::of(s, input) });
+    }
+    if end > SIEVE_LIMIT {
+        return Err(MathError::TooLarge { maximum: SIEVE_LIMIT });
+    }
+    Ok((start, end))
+}
+
+/// The gap between two consecutive primes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PrimeGap {
+    pub after: u64,
+    pub before: u64,
+}
+
+impl PrimeGap {
+    /// How far apart the two primes are.
+    pub fn size(&self) -> u64 {
+        self.before - self.after
+    }
+}
+
+/// The primes in a range, with their count and twin prime and gap statistics.
+///
+/// Only the first [`LISTED_PRIMES`] primes are kept, so a range up to [`SIEVE_LIMIT`] can be
+/// summarised without holding hundreds of millions of primes in memory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PrimeRange {
+    pub start: u64,
+    pub end: u64,
+    /// The first primes in the range, at most [`LISTED_PRIMES`] of them.
+    pub primes: Vec<u64>,
+    pub count: u64,
+    pub first: Option<u64>,
+    pub last: Option<u64>,
+    /// How many pairs of primes in the range are 2 apart, such as 11 and 13.
+    pub twin_pairs: u64,
+    pub first_twin_pair: Option<(u64, u64)>,
+    /// The widest gap between consecutive primes in the range, the first if there is a tie.
+    pub largest_gap: Option<PrimeGap>,
+}
+
+impl PrimeRange {
+    /// Sieves the range from `start` to `end` inclusive.
+    pub fn sieve(start: u64, end: u64) -> Result<PrimeRange, MathError> {
+        let mut range = PrimeRange {
+            start,
+            end,
+            primes: Vec::new(),
+            count: 0,
+            first: None,
+            last: None,
+            twin_pairs: 0,
+            first_twin_pair: None,
+            largest_gap: None,
+        };
+        for_each_prime_between(start, end, |p| {
+            if range.primes.len() < LISTED_PRIMES {
+                range.primes.push(p);
+            }
+            if let Some(previous) = range.last {
+                let gap = PrimeGap { after: previous, before: p };
+                if gap.size() == 2 {
+                    range.twin_pairs += 1;
+                    range.first_twin_pair.get_or_insert((previous, p));
+                }
+                if range.largest_gap.is_none_or(|largest| gap.size() > largest.size()) {
+                    range.largest_gap = Some(gap);
+                }
+            }
+            range.first.get_or_insert(p);
+            range.last = Some(p);
+            range.count += 1;
+        })?;
+        Ok(range)
+    }
+
+    /// Returns `true` if every prime in the range was kept in [`PrimeRange::primes`].
+    pub fn lists_every_prime(&self) -> bool {
+        self.primes.len() as u64 == self.count
+    }
+
+    /// The mean gap between consecutive primes in the range, if it has at least two.
+    pub fn average_gap(&self) -> Option<f64> {
+        match (self.first, self.last) {
+            (Some(first), Some(last)) if self.count > 1 => Some((last - first) as f64 / (self.count - 1) as f64),
+            _ => None,
+        }
+    }
+
+    /// Describes the count, twin primes and gaps in words, one line each.
+    pub fn statistics(&self) -> Vec<String> {
+        let mut statistics = vec![match self.count {
+            1 => format!("There is 1 prime between {} and {}", self.start, self.end),
+            count => format!("There are {} primes between {} and {}", count, self.start, self.end),
+        }];
+        if let Some((a, b)) = self.first_twin_pair {
+            let pairs = format!("{} pair{}", self.twin_pairs, if self.twin_pairs == 1 { "" } else { "s" });
+            statistics.push(format!("Twin primes (2 apart): {}, the first being {} and {}", pairs, a, b));
+        } else if self.count > 1 {
+            statistics.push("There are no twin primes (primes 2 apart) in the range".to_string());
+        }
+        if let (Some(gap), Some(average)) = (self.largest_gap, self.average_gap()) {
+            statistics.push(format!("The largest gap is {}, between {} and {}", gap.size(), gap.after, gap.before));
+            statistics.push(format!("The average gap is {:.2}", average));
+        }
+        statistics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identifies_primes() {
+        let primes: Vec<u32> = (0..30).filter(|&n| is_prime(n)).collect();
+        assert_eq!(primes, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        let trial_division = |n: u64| n > 1 && (2..).take_while(|d| d * d <= n).all(|d| !n.is_multiple_of(d));
+        assert!((1_000_000..1_010_000u64).all(|n| is_prime(n) == trial_division(n)));
+    }
+
+    #[test]
+    fn identifies_large_primes() {
+        assert!(is_prime(u64::MAX - 58));
+        assert!(!is_prime(u64::MAX));
+        // The smallest composite number that passes Miller–Rabin for the bases 2 to 37.
+        assert!(!is_prime(3_825_123_056_546_413_051u64));
+        assert!(is_prime(2u128.pow(89) - 1));
+        assert!(is_prime(2u128.pow(127) - 1));
+        assert!(!is_prime((2u128.pow(61) - 1) * (2u128.pow(31) - 1)));
+        assert!(!is_prime(u128::MAX));
+        assert!(!is_prime((2u128.pow(61) - 1).pow(2)));
+        assert!(!is_prime((2u128.pow(89) - 1) * (2u128.pow(31) - 1)));
+    }
+
+    #[test]
+    fn runs_the_strong_lucas_test() {
+        let lucas = |n: u128| Montgomery::new(n).is_strong_lucas_probable_prime();
+        let pseudoprimes = [5459, 5777];
+        let odd = (5..10_000u32).filter(|n| n % 2 == 1 && !pseudoprimes.contains(n));
+        assert!(odd.into_iter().all(|n| lucas(n.into()) == is_prime(n)));
+        // 2047 fools Miller–Rabin to base 2 but not the Lucas test, while the smallest strong
+        // Lucas pseudoprimes, 5459 and 5777, fail Miller–Rabin to base 2.
+        assert!(!lucas(2047) && Montgomery::new(2047).is_strong_probable_prime(2));
+        assert!(lucas(5459) && !Montgomery::new(5459).is_strong_probable_prime(2));
+        assert!(lucas(5777) && !Montgomery::new(5777).is_strong_probable_prime(2));
+        assert!(!lucas(49));
+    }
+
+    #[test]
+    fn factorises() {
+        assert_eq!(prime_factorization(72u32), BTreeMap::from([(2, 3), (3, 2)]));
+        assert_eq!(prime_factorization(97u32), BTreeMap::from([(97, 1)]));
+        assert_eq!(prime_factorization(2u32 * 2 * 7 * 7 * 13), BTreeMap::from([(2, 2), (7, 2), (13, 1)]));
+        assert!(prime_factorization(1u32).is_empty());
+    }
+
+    #[test]
+    fn factorises_large_numbers() {
+        let semiprime = 1_000_000_007u64 * 998_244_353;
+        assert_eq!(prime_factorization(semiprime), BTreeMap::from([(998_244_353, 1), (1_000_000_007, 1)]));
+        assert_eq!(
+            prime_factorization(u64::MAX),
+            BTreeMap::from([(3, 1), (5, 1), (17, 1), (257, 1), (641, 1), (65_537, 1), (6_700_417, 1)])
+        );
+        let (mersenne_31, mersenne_61) = (2u128.pow(31) - 1, 2u128.pow(61) - 1);
+        assert_eq!(
+            prime_factorization(mersenne_31 * mersenne_31 * mersenne_61),
+            BTreeMap::from([(mersenne_31, 2), (mersenne_61, 1)])
+        );
+        assert_eq!(prime_factorization(2u128.pow(127)), BTreeMap::from([(2, 127)]));
+        let product: u128 = prime_factorization(u128::MAX).iter().map(|(p, &e)| p.pow(e)).product();
+        assert_eq!(product, u128::MAX);
+    }
+
+    #[test]
+    fn formats_factors() {
+        assert_eq!(format_factors(&prime_factorization(72u32)), "2^3 * 3^2");
+        assert_eq!(format_factors(&prime_factorization(30u32)), "2 * 3 * 5");
+    }
+
+    #[test]
+    fn writes_index_form() {
+        assert_eq!(format_index_form(&prime_factorization(72u32)), "2³ × 3²");
+        assert_eq!(format_index_form(&prime_factorization(1024u32)), "2¹⁰");
+        assert_eq!(format_index_form(&prime_factorization(30u32)), "2 × 3 × 5");
+    }
+
+    #[test]
+    fn draws_factor_trees_and_ladders() {
+        let steps = division_steps(72u32);
+        assert_eq!(steps.len(), 5);
+        assert_eq!(steps[0], DivisionStep { number: 72, prime: 2, quotient: 36 });
+        assert_eq!(
+            factor_tree(&steps),
+            [
+                "72",
+                "├── 2",
+                "└── 36",
+                "    ├── 2",
+                "    └── 18",
+                "        ├── 2",
+                "        └── 9",
+                "            ├── 3",
+                "            └── 3",
+            ]
+        );
+        assert_eq!(division_ladder(&steps), ["2 │ 72", "2 │ 36", "2 │ 18", "3 │ 9", "3 │ 3", "  │ 1"]);
+
+        let steps = division_steps(143u32);
+        assert_eq!(division_ladder(&steps), ["11 │ 143", "13 │ 13", "   │ 1"]);
+        assert_eq!(factor_tree(&division_steps(97u32)), ["97"]);
+        assert!(division_steps(1u32).is_empty());
+    }
+
+    #[test]
+    fn sieves_primes() {
+        assert_eq!(sieve(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert!(sieve(1).is_empty());
+        let checked: Vec<u64> = (0..2000).filter(|&n| is_prime(n as u32)).collect();
+        assert_eq!(sieve(1999), checked);
+    }
+
+    #[test]
+    fn sieves_in_segments() {
+        let mut primes = Vec::new();
+        for_each_prime_between(0, 300_000, |p| primes.push(p)).unwrap();
+        assert_eq!(primes, sieve(300_000));
+
+        let mut primes = Vec::new();
+        for_each_prime_between(90, 110, |p| primes.push(p)).unwrap();
+        assert_eq!(primes, vec![97, 101, 103, 107, 109]);
+
+        let mut primes = Vec::new();
+        for_each_prime_between(9_999_999_900, SIEVE_LIMIT, |p| primes.push(p)).unwrap();
+        assert_eq!(primes, vec![9_999_999_929, 9_999_999_943, 9_999_999_967]);
+        assert_eq!(for_each_prime_between(0, SIEVE_LIMIT + 1, |_| ()), Err(MathError::TooLarge { maximum: SIEVE_LIMIT }));
+    }
+
+    #[test]
+    fn reads_prime_ranges() {
+        assert_eq!(parse_prime_range("100"), Ok((0, 100)));
+        assert_eq!(parse_prime_range("1000 2000"), Ok((1000, 2000)));
+        assert_eq!(parse_prime_range("1000, 2000"), Ok((1000, 2000)));
+        assert_eq!(parse_prime_range("10..=20"), Ok((10, 20)));
+        assert_eq!(
+            parse_prime_range("10 2o"),
+            Err(MathError::InvalidToken { token: "2o".to_string(), position: Span::new(3, 5) })
+        );
+        assert!(matches!(parse_prime_range("20..10"), Err(MathError::InvalidToken { .. })));
+        assert_eq!(parse_prime_range("20000000000"), Err(MathError::TooLarge { maximum: SIEVE_LIMIT }));
+    }
+
+    #[test]
+    fn summarises_prime_ranges() {
+        let range = PrimeRange::sieve(0, 100).unwrap();
+        assert_eq!(range.count, 25);
+        assert!(range.lists_every_prime());
+        assert_eq!(range.twin_pairs, 8);
+        assert_eq!(range.first_twin_pair, Some((3, 5)));
+        assert_eq!(range.largest_gap, Some(PrimeGap { after: 89, before: 97 }));
+        assert_eq!(
+            range.statistics(),
+            [
+                "There are 25 primes between 0 and 100",
+                "Twin primes (2 apart): 8 pairs, the first being 3 and 5",
+                "The largest gap is 8, between 89 and 97",
+                "The average gap is 3.96",
+            ]
+        );
+
+        let range = PrimeRange::sieve(0, 1_000_000).unwrap();
+        assert_eq!(range.count, 78_498);
+        assert_eq!(range.primes.len(), LISTED_PRIMES);
+        assert_eq!(range.twin_pairs, 8_169);
+        assert_eq!(range.largest_gap.map(|gap| gap.size()), Some(114));
+
+        assert_eq!(
+            PrimeRange::sieve(10, 14).unwrap().statistics(),
+            [
+                "There are 2 primes between 10 and 14",
+                "Twin primes (2 apart): 1 pair, the first being 11 and 13",
+                "The largest gap is 2, between 11 and 13",
+                "The average gap is 2.00",
+            ]
+        );
+        assert_eq!(PrimeRange::sieve(24, 28).unwrap().statistics(), ["There are 0 primes between 24 and 28"]);
+    }
+}
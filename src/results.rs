@@ -0,0 +1,1102 @@
+//! Structured results for each calculator.
+//!
+//! Each result keeps the inputs, the values found along the way and the answer, so it can be
+//! serialised (for example to JSON for a marking tool) as well as described in words with
+//! [`CalculatorResult::answer`] and [`CalculatorResult::working`].
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::bodmas::{bodmas_working, write_answer, BodmasStep, BodmasWorking};
+use crate::bounds::{Accuracy, Approximation, BoundsCalculation, ErrorInterval};
+use crate::decimal::{Decimal, RoundingMode};
+use crate::error::MathError;
+use crate::exact::{evaluate_exact, Exact};
+use crate::expressions::{estimate, evaluate_expr, format_approximate};
+use crate::figures::{DigitRole, FigureCount};
+use crate::fractions::{
+    common_denominator, order_fractions, ordering_statement, solve_equivalent, Fraction, FractionCalculation,
+    SortOrder,
+};
+use crate::number::{exact_lcm_of, get_multiples, hcf_of, serialize_u128, Natural};
+use crate::parser::parse;
+use crate::primes::{
+    division_steps, format_factors, format_index_form, is_prime, DivisionStep, PrimeRange,
+};
+use crate::rounding::{
+    estimate_square_root_to_places, format_significant_figures, round_to_decimal_places, round_to_place,
+    round_to_significant_figures, surrounding_squares,
+};
+use crate::standard_form::{StandardFormCalculation, StandardFormConversion, DIVISION_FIGURES};
+
+/// The result of evaluating an expression with the BODMAS calculator.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BodmasResult {
+    pub expression: String,
+    pub result: f64,
+    /// The answer the working ends with, to 10 significant figures if it is not exact.
+    pub answer: Decimal,
+    /// `false` if the answer has been rounded, or was worked out from a rounded value.
+    pub answer_is_exact: bool,
+    /// The exact answer as a fraction or surd, if one was asked for and could be found.
+    pub exact: Option<Exact>,
+    /// The expression written out with `×` and `÷`, as the working starts from it.
+    pub written: String,
+    /// Each operation in the order BODMAS works them out.
+    pub steps: Vec<BodmasStep>,
+}
+
+impl BodmasResult {
+    /// Evaluates `expression`, giving the answer exactly if `exact` is `true` and it has an
+    /// exact form, and as a decimal otherwise.
+    pub fn calculate(expression: &str, exact: bool) -> Result<BodmasResult, MathError> {
+        let expr = parse(expression)?;
+        let result = evaluate_expr(&expr)?;
+        let exact = if exact { evaluate_exact(&expr)? } else { None };
+        let BodmasWorking { steps, answer, exact: answer_is_exact } = bodmas_working(&expr)?;
+        Ok(BodmasResult {
+            expression: expression.to_string(),
+            result,
+            answer,
+            answer_is_exact,
+            exact,
+            written: expr.to_string(),
+            steps,
+        })
+    }
+}
+
+/// A number rounded to a number of decimal places.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecimalPlacesResult {
+    pub number: Decimal,
+    pub places: u32,
+    pub mode: RoundingMode,
+    pub result: Decimal,
+}
+
+impl DecimalPlacesResult {
+    /// Fails if there are too many decimal places to write out.
+    pub fn calculate(number: Decimal, places: u32, mode: RoundingMode) -> Result<DecimalPlacesResult, MathError> {
+        let result = round_to_decimal_places(&number, places, mode)?;
+        Ok(DecimalPlacesResult { number, places, mode, result })
+    }
+}
+
+/// One number from an estimated expression, as written and after rounding.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EstimatedNumber {
+    pub number: String,
+    pub rounded: Decimal,
+}
+
+/// An expression estimated by rounding each number, usually to one significant figure, next to
+/// the answer from the numbers as written.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EstimationResult {
+    pub expression: String,
+    /// How accurately each number is rounded.
+    pub accuracy: Accuracy,
+    /// The rule used to round each number.
+    pub mode: RoundingMode,
+    pub roundings: Vec<EstimatedNumber>,
+    pub rounded_expression: String,
+    /// The estimate, or `None` when rounding leaves a calculation that cannot be done.
+    pub result: Option<f64>,
+    pub exact: f64,
+    pub percentage_error: Option<f64>,
+    /// Roundings that make the estimate misleading, such as a divisor rounding to 0.
+    pub warnings: Vec<String>,
+}
+
+impl EstimationResult {
+    pub fn calculate(expression: &str, accuracy: Accuracy, mode: RoundingMode) -> Result<EstimationResult, MathError> {
+        let estimate = estimate(expression, &accuracy, mode)?;
+        Ok(EstimationResult {
+            expression: expression.trim().to_string(),
+            accuracy,
+            mode,
+            percentage_error: estimate.percentage_error(),
+            roundings: estimate
+                .roundings
+                .into_iter()
+                .map(|(number, rounded)| EstimatedNumber { number, rounded })
+                .collect(),
+            rounded_expression: estimate.rounded_expression,
+            result: estimate.estimate,
+            exact: estimate.exact,
+            warnings: estimate.warnings,
+        })
+    }
+}
+
+/// A square root estimated from the perfect squares either side of the number.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SquareRootEstimateResult {
+    pub number: f64,
+    /// The whole-number part of the square root.
+    pub root: u64,
+    pub lower_square: u64,
+    pub upper_square: u64,
+    pub places: u32,
+    /// The estimate, rounded to `places` decimal places.
+    pub estimate: Decimal,
+}
+
+impl SquareRootEstimateResult {
+    /// Fails for negative numbers, which have no square root, or too many decimal places.
+    pub fn calculate(number: f64, places: u32) -> Result<SquareRootEstimateResult, MathError> {
+        if number < 0.0 {
+            return Err(MathError::NoRealSquareRoot);
+        }
+        let (root, lower_square, upper_square) = surrounding_squares(number)?;
+        let estimate = estimate_square_root_to_places(number, places)?;
+        Ok(SquareRootEstimateResult { number, root, lower_square, upper_square, places, estimate })
+    }
+}
+
+/// The highest common factor of a list of numbers.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HcfResult {
+    pub numbers: Vec<u64>,
+    pub hcf: u64,
+}
+
+impl HcfResult {
+    /// Fails if the list is empty.
+    pub fn calculate(numbers: &[u64]) -> Result<HcfResult, MathError> {
+        let hcf = hcf_of(numbers).ok_or(MathError::EmptyInput)?;
+        Ok(HcfResult { numbers: numbers.to_vec(), hcf })
+    }
+}
+
+/// The lowest common multiple of a list of numbers.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LcmResult {
+    pub numbers: Vec<u64>,
+    /// The exact LCM, however large. It is written as a string in JSON if it does not fit in a
+    /// `u128`.
+    pub lcm: Natural,
+}
+
+impl LcmResult {
+    /// Fails if the list is empty.
+    pub fn calculate(numbers: &[u64]) -> Result<LcmResult, MathError> {
+        let lcm = exact_lcm_of(numbers)?;
+        Ok(LcmResult { numbers: numbers.to_vec(), lcm })
+    }
+}
+
+/// The first few multiples of one number.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MultiplesList {
+    pub number: u32,
+    pub multiples: Vec<u64>,
+}
+
+/// The first `count` multiples of each of a list of numbers.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MultiplesResult {
+    pub count: usize,
+    pub lists: Vec<MultiplesList>,
+}
+
+impl MultiplesResult {
+    /// Fails if any number is 0, which has no multiples to list, or if `count` is too large.
+    pub fn calculate(numbers: &[u32], count: usize) -> Result<MultiplesResult, MathError> {
+        if numbers.contains(&0) {
+            return Err(MathError::NotPositive);
+        }
+        let lists = numbers
+            .iter()
+            .map(|&number| Ok(MultiplesList { number, multiples: get_multiples(number, count)? }))
+            .collect::<Result<_, MathError>>()?;
+        Ok(MultiplesResult { count, lists })
+    }
+}
+
+/// Whether one number is prime.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PrimeCheck {
+    #[serde(serialize_with = "serialize_u128")]
+    pub number: u128,
+    pub is_prime: bool,
+}
+
+/// Which of a list of numbers are prime.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PrimesResult {
+    pub checks: Vec<PrimeCheck>,
+}
+
+impl PrimesResult {
+    pub fn calculate(numbers: &[u128]) -> PrimesResult {
+        let checks = numbers.iter().map(|&number| PrimeCheck { number, is_prime: is_prime(number) }).collect();
+        PrimesResult { checks }
+    }
+}
+
+/// A prime raised to a power, one term of a prime factorisation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PrimePower {
+    #[serde(serialize_with = "serialize_u128")]
+    pub prime: u128,
+    pub exponent: u32,
+}
+
+/// A number written as a product of its prime factors.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PrimeFactorsResult {
+    #[serde(serialize_with = "serialize_u128")]
+    pub number: u128,
+    pub factors: Vec<PrimePower>,
+    /// The factorisation written out, e.g. `2^3 * 3^2`.
+    pub product: String,
+    /// The factorisation in index form, e.g. `2³ × 3²`.
+    pub index_form: String,
+    /// Each division by a prime, from which a factor tree or division ladder is drawn.
+    pub steps: Vec<DivisionStep>,
+}
+
+impl PrimeFactorsResult {
+    /// Fails for 0 and 1, which cannot be written as a product of primes.
+    pub fn calculate(number: u128) -> Result<PrimeFactorsResult, MathError> {
+        if number < 2 {
+            return Err(MathError::TooSmall { minimum: 2 });
+        }
+        // Each division takes out one prime, so counting them gives the factorisation.
+        let steps = division_steps(number);
+        let mut factors = BTreeMap::new();
+        for step in &steps {
+            *factors.entry(step.prime).or_insert(0) += 1;
+        }
+        Ok(PrimeFactorsResult {
+            number,
+            product: format_factors(&factors),
+            index_form: format_index_form(&factors),
+            steps,
+            factors: factors.into_iter().map(|(prime, exponent)| PrimePower { prime, exponent }).collect(),
+        })
+    }
+}
+
+/// A number rounded to a place value such as the nearest ten.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RoundingResult {
+    pub number: Decimal,
+    pub place: Decimal,
+    pub mode: RoundingMode,
+    pub result: Decimal,
+}
+
+impl RoundingResult {
+    /// Fails unless the place value is greater than 0.
+    pub fn calculate(number: Decimal, place: Decimal, mode: RoundingMode) -> Result<RoundingResult, MathError> {
+        if !place.is_positive() {
+            return Err(MathError::NotPositive);
+        }
+        let result = round_to_place(&number, &place, mode);
+        Ok(RoundingResult { number, place, mode, result })
+    }
+}
+
+/// A number rounded to a number of significant figures.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SignificantFiguresResult {
+    pub number: Decimal,
+    pub figures: u32,
+    pub mode: RoundingMode,
+    pub result: Decimal,
+}
+
+impl SignificantFiguresResult {
+    /// Fails if the place to round to is too small to hold.
+    pub fn calculate(number: Decimal, figures: u32, mode: RoundingMode) -> Result<SignificantFiguresResult, MathError> {
+        let result = round_to_significant_figures(&number, figures, mode)?;
+        Ok(SignificantFiguresResult { number, figures, mode, result })
+    }
+}
+
+/// The error interval of a rounded or truncated number.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BoundsResult {
+    pub number: Decimal,
+    pub accuracy: Accuracy,
+    pub approximation: Approximation,
+    /// The degree of accuracy as a step, e.g. 0.1 for 1 d.p.
+    pub step: Decimal,
+    /// Half the degree of accuracy, for a rounded number.
+    pub error_margin: Option<Decimal>,
+    /// The smaller step for numbers nearer zero, for a rounded power of ten given to significant
+    /// figures: 1000 to 1 s.f. can be as low as 950, as 999 is to the nearest 100.
+    pub step_nearer_zero: Option<Decimal>,
+    pub interval: ErrorInterval,
+}
+
+impl BoundsResult {
+    /// Fails if the accuracy is too fine to write out.
+    pub fn calculate(
+        number: Decimal,
+        accuracy: Accuracy,
+        approximation: Approximation,
+    ) -> Result<BoundsResult, MathError> {
+        let step = accuracy.step(&number)?.trim();
+        let interval = ErrorInterval::new(&number, &accuracy, approximation)?;
+        let (error_margin, step_nearer_zero) = match approximation {
+            Approximation::Rounded => {
+                let step_nearer_zero = accuracy.step_nearer_zero(&number)?.trim();
+                (Some(step.half().trim()), Some(step_nearer_zero).filter(|smaller| *smaller != step))
+            }
+            Approximation::Truncated => (None, None),
+        };
+        Ok(BoundsResult { number, accuracy, approximation, step, step_nearer_zero, error_margin, interval })
+    }
+}
+
+/// The missing value of `a/b = c/d`, where exactly one of the four values is `None`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EquivalentFractionResult {
+    pub a: Option<Fraction>,
+    pub b: Option<Fraction>,
+    pub c: Option<Fraction>,
+    pub d: Option<Fraction>,
+    pub result: Fraction,
+}
+
+impl EquivalentFractionResult {
+    /// Fails unless exactly one value is unknown and no division by zero is needed.
+    pub fn calculate(
+        a: Option<Fraction>,
+        b: Option<Fraction>,
+        c: Option<Fraction>,
+        d: Option<Fraction>,
+    ) -> Result<EquivalentFractionResult, MathError> {
+        let result = solve_equivalent(a, b, c, d)?;
+        Ok(EquivalentFractionResult { a, b, c, d, result })
+    }
+}
+
+/// A fraction simplified by dividing by the HCF of its numerator and denominator.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SimplifyResult {
+    pub numerator: i64,
+    pub denominator: i64,
+    pub hcf: i64,
+    pub result: Fraction,
+}
+
+impl SimplifyResult {
+    /// Fails if the denominator is zero.
+    pub fn calculate(numerator: i64, denominator: i64) -> Result<SimplifyResult, MathError> {
+        let result = Fraction::new(numerator, denominator).ok_or(MathError::ZeroDenominator { position: None })?;
+        // The HCF is whatever the denominator was divided by to reach lowest terms.
+        let hcf = (denominator / result.denominator()).abs();
+        Ok(SimplifyResult { numerator, denominator, hcf, result })
+    }
+}
+
+/// A value written both as an improper fraction and as a mixed number.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MixedNumberResult {
+    pub improper: Fraction,
+    pub whole: i64,
+    /// The proper fraction left over, with the same sign as the whole part.
+    pub remainder: Fraction,
+    pub mixed: String,
+    /// `true` when converting a mixed number to an improper fraction, `false` for the reverse.
+    pub from_mixed: bool,
+}
+
+impl MixedNumberResult {
+    pub fn calculate(value: Fraction, from_mixed: bool) -> MixedNumberResult {
+        let (whole, remainder) = value.to_mixed();
+        MixedNumberResult { improper: value, whole, remainder, mixed: value.to_mixed_string(), from_mixed }
+    }
+}
+
+/// One value being ordered, as written and as a fraction.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OrderedValue {
+    pub input: String,
+    pub value: Fraction,
+    /// The numerator once the value is written over the common denominator.
+    pub rewritten_numerator: Option<i64>,
+}
+
+/// A list of fractions, decimals and percentages put in order.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OrderingResult {
+    pub order: SortOrder,
+    /// The values in the order they were given.
+    pub values: Vec<OrderedValue>,
+    /// The LCM of the denominators, or `None` if it is too large to calculate.
+    pub common_denominator: Option<i64>,
+    /// The values as written, in order.
+    pub ordered: Vec<String>,
+    /// The ordered values written as an inequality, e.g. `35% < 3/8 < 0.4`.
+    pub statement: String,
+}
+
+impl OrderingResult {
+    pub fn calculate(inputs: &[(&str, Fraction)], order: SortOrder) -> OrderingResult {
+        let fractions: Vec<Fraction> = inputs.iter().map(|(_, fraction)| *fraction).collect();
+        let common = common_denominator(&fractions);
+        let values = inputs
+            .iter()
+            .enumerate()
+            .map(|(i, (input, value))| OrderedValue {
+                input: input.to_string(),
+                value: *value,
+                rewritten_numerator: common.as_ref().map(|(_, numerators)| numerators[i]),
+            })
+            .collect();
+        let ordered: Vec<(&str, Fraction)> =
+            order_fractions(&fractions, order).into_iter().map(|i| inputs[i]).collect();
+        OrderingResult {
+            order,
+            values,
+            common_denominator: common.map(|(denominator, _)| denominator),
+            ordered: ordered.iter().map(|(input, _)| input.to_string()).collect(),
+            statement: ordering_statement(&ordered, order),
+        }
+    }
+}
+
+/// The result of any calculator, tagged with the calculator's name when serialised.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "calculator", rename_all = "snake_case")]
+pub enum CalculatorResult {
+    Bodmas(BodmasResult),
+    DecimalPlaces(DecimalPlacesResult),
+    Estimation(EstimationResult),
+    SquareRootEstimate(SquareRootEstimateResult),
+    Hcf(HcfResult),
+    Lcm(LcmResult),
+    Multiples(MultiplesResult),
+    Primes(PrimesResult),
+    PrimeRange(PrimeRange),
+    PrimeFactors(PrimeFactorsResult),
+    Rounding(RoundingResult),
+    SignificantFigures(SignificantFiguresResult),
+    FigureCount(FigureCount),
+    Bounds(BoundsResult),
+    CalculationBounds(BoundsCalculation),
+    EquivalentFraction(EquivalentFractionResult),
+    Simplify(SimplifyResult),
+    MixedNumber(MixedNumberResult),
+    Ordering(OrderingResult),
+    FractionArithmetic(FractionCalculation),
+    StandardForm(StandardFormConversion),
+    StandardFormArithmetic(StandardFormCalculation),
+}
+
+impl CalculatorResult {
+    /// The answer, written the way it would be on paper.
+    pub fn answer(&self) -> String {
+        match self {
+            CalculatorResult::Bodmas(r) => {
+                r.exact.as_ref().map_or_else(|| write_answer(&r.answer, r.answer_is_exact), Exact::to_string)
+            }
+            CalculatorResult::DecimalPlaces(r) => format!("{} ({})", r.result, r.mode.name()),
+            CalculatorResult::Estimation(r) => {
+                let exact = format_approximate(r.exact);
+                match (r.result, r.percentage_error) {
+                    (Some(result), Some(error)) => {
+                        format!("{} (exact answer {}, {:.1}% error)", format_approximate(result), exact, error)
+                    }
+                    (Some(result), None) => format!("{} (exact answer {})", format_approximate(result), exact),
+                    (None, _) => format!("no estimate (exact answer {})", exact),
+                }
+            }
+            CalculatorResult::SquareRootEstimate(r) => r.estimate.to_string(),
+            CalculatorResult::Hcf(r) => r.hcf.to_string(),
+            CalculatorResult::Lcm(r) => r.lcm.to_string(),
+            CalculatorResult::Multiples(r) => r
+                .lists
+                .iter()
+                .map(|list| {
+                    let multiples: Vec<String> = list.multiples.iter().map(u64::to_string).collect();
+                    format!("{}: {}", list.number, multiples.join(", "))
+                })
+                .collect::<Vec<String>>()
+                .join("\n"),
+            CalculatorResult::Primes(r) => r
+                .checks
+                .iter()
+                .map(|check| {
+                    if check.is_prime {
+                        format!("{} is a prime number", check.number)
+                    } else {
+                        format!("{} is not a prime number", check.number)
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join("\n"),
+            CalculatorResult::PrimeRange(range) => {
+                let primes: Vec<String> = range.primes.iter().map(u64::to_string).collect();
+                let mut lines = vec![primes.join(", ")];
+                if !range.lists_every_prime() {
+                    lines[0].push_str(&format!(" … (the first {} of {})", range.primes.len(), range.count));
+                }
+                lines.extend(range.statistics());
+                lines.join("\n")
+            }
+            CalculatorResult::PrimeFactors(r) => r.index_form.clone(),
+            CalculatorResult::Rounding(r) => format!("{} ({})", r.result, r.mode.name()),
+            // The rule goes in the same brackets as the standard form, if there is one.
+            CalculatorResult::SignificantFigures(r) => match format_significant_figures(&r.result).strip_suffix(')') {
+                Some(answer) => format!("{}, {})", answer, r.mode.name()),
+                None => format!("{} ({})", r.result, r.mode.name()),
+            },
+            CalculatorResult::FigureCount(r) => format!(
+                "{} has {} and {}",
+                r.number,
+                plural(&r.significant_figures_text(), "significant figure", r.maximum_significant_figures),
+                plural(&r.decimal_places.to_string(), "decimal place", r.decimal_places as usize)
+            ),
+            CalculatorResult::Bounds(r) => r.interval.to_string(),
+            CalculatorResult::CalculationBounds(calculation) => {
+                let bounds = format!(
+                    "Lower bound: {}, upper bound: {}",
+                    calculation.interval.lower, calculation.interval.upper
+                );
+                if calculation.exact {
+                    bounds
+                } else {
+                    format!("{} (to {} significant figures)", bounds, DIVISION_FIGURES)
+                }
+            }
+            CalculatorResult::EquivalentFraction(r) => r.result.to_string(),
+            CalculatorResult::Simplify(r) => r.result.to_string(),
+            CalculatorResult::MixedNumber(r) => {
+                if r.from_mixed {
+                    r.improper.to_string()
+                } else {
+                    r.mixed.clone()
+                }
+            }
+            CalculatorResult::Ordering(r) => r.statement.clone(),
+            CalculatorResult::FractionArithmetic(calculation) => {
+                let result = calculation.result;
+                let sign = if calculation.exact_decimal { "=" } else { "≈" };
+                if result.is_integer() {
+                    result.to_string()
+                } else if result.to_mixed().0 == 0 {
+                    format!("{} {} {}", result, sign, calculation.decimal)
+                } else {
+                    format!("{} = {} {} {}", result, result.to_mixed_string(), sign, calculation.decimal)
+                }
+            }
+            CalculatorResult::StandardForm(conversion) => conversion.answer(),
+            CalculatorResult::StandardFormArithmetic(calculation) => {
+                if calculation.exact {
+                    calculation.result.to_string()
+                } else {
+                    format!("{} (to {} significant figures)", calculation.result, DIVISION_FIGURES)
+                }
+            }
+        }
+    }
+
+    /// The working that leads to the answer, one line per step. Calculators with nothing to
+    /// show give an empty list.
+    pub fn working(&self) -> Vec<String> {
+        match self {
+            CalculatorResult::DecimalPlaces(r) => vec![rule_used(r.mode)],
+            CalculatorResult::Rounding(r) => vec![rule_used(r.mode)],
+            CalculatorResult::SignificantFigures(r) => vec![rule_used(r.mode)],
+            CalculatorResult::FigureCount(r) => {
+                // The number and the role of each digit are written in line with each other.
+                let mut working = vec![
+                    format!("Number: {}", r.number),
+                    format!("Roles:  {}", r.markers()),
+                    "Key: s = significant, l = leading zero, t = trailing zero, ? = ambiguous zero".to_string(),
+                ];
+                let zeros = [
+                    (DigitRole::LeadingZero, "Leading zeros, which only hold the place value"),
+                    (DigitRole::TrailingZero, "Zeros at the end after the decimal point, which are significant"),
+                    (
+                        DigitRole::AmbiguousZero,
+                        "Zeros at the end of a whole number, which are significant only if it was rounded to them",
+                    ),
+                ];
+                for (role, description) in zeros {
+                    let count = r.count_of(role);
+                    if count > 0 {
+                        working.push(format!("{}: {}", description, count));
+                    }
+                }
+                working
+            }
+            CalculatorResult::Bodmas(r) => {
+                // The expression is written out first, then rewritten once for each step.
+                std::iter::once(r.written.clone()).chain(r.steps.iter().map(BodmasStep::to_string)).collect()
+            }
+            CalculatorResult::Estimation(r) => {
+                let mut working = vec![format!("Each number is rounded {}", r.accuracy), rule_used(r.mode)];
+                working.extend(r.roundings.iter().map(|rounding| format!("{} ≈ {}", rounding.number, rounding.rounded)));
+                working.push(format!("Estimated expression: {}", r.rounded_expression));
+                working.extend(r.warnings.iter().map(|warning| format!("Warning: {}", warning)));
+                if let Some(result) = r.result {
+                    working.push(format!("Estimate: {}", format_approximate(result)));
+                }
+                working.push(format!("Exact answer: {}", format_approximate(r.exact)));
+                if let Some(error) = r.percentage_error {
+                    working.push(format!("Percentage error: {:.1}%", error));
+                }
+                working
+            }
+            CalculatorResult::SquareRootEstimate(r) => vec![format!(
+                "{} is between {} ({}²) and {} ({}²)",
+                r.number,
+                r.lower_square,
+                r.root,
+                r.upper_square,
+                r.root + 1
+            )],
+            CalculatorResult::Bounds(r) => {
+                let mut working = Vec::new();
+                if !matches!(r.accuracy, Accuracy::Nearest(_)) {
+                    working.push(format!("{} {} is to the nearest {}", r.number, r.accuracy, r.step));
+                }
+                let (lower, upper) = (&r.interval.lower_bound, &r.interval.upper_bound);
+                match &r.error_margin {
+                    Some(margin) => {
+                        working.push(format!("The error margin is {} ÷ 2 = {}", r.step, margin));
+                        let (mut lower_margin, mut upper_margin) = (margin.clone(), margin.clone());
+                        // A power of ten is also reached by rounding up from the numbers just nearer zero,
+                        // which are given to a smaller step.
+                        if let Some(step) = &r.step_nearer_zero {
+                            let nearer_zero = step.half().trim();
+                            working.push(format!(
+                                "Numbers nearer zero than {} are to the nearest {}, so on that side it is {} ÷ 2 = {}",
+                                r.number, step, step, nearer_zero
+                            ));
+                            if r.number.is_negative() {
+                                upper_margin = nearer_zero;
+                            } else {
+                                lower_margin = nearer_zero;
+                            }
+                        }
+                        working.push(format!("Lower bound: {} - {} = {}", r.number, lower_margin, lower));
+                        working.push(format!("Upper bound: {} + {} = {}", r.number, upper_margin, upper));
+                    }
+                    None => {
+                        // Truncating only ever cuts a number towards zero.
+                        working.push(format!(
+                            "Truncating cuts off everything after the nearest {}, so x can be up to {} further from zero",
+                            r.step, r.step
+                        ));
+                        if r.number.is_positive() {
+                            working.push(format!("Lower bound: {}", lower));
+                        } else {
+                            working.push(format!("Lower bound: {} - {} = {}", r.number, r.step, lower));
+                        }
+                        if r.number.is_negative() {
+                            working.push(format!("Upper bound: {}", upper));
+                        } else {
+                            working.push(format!("Upper bound: {} + {} = {}", r.number, r.step, upper));
+                        }
+                    }
+                }
+                working
+            }
+            CalculatorResult::EquivalentFraction(r) => {
+                let show = |value: Option<Fraction>| value.map_or("x".to_string(), |v| v.to_string());
+                let (a, b, c, d) = (show(r.a), show(r.b), show(r.c), show(r.d));
+                let calculation = match (r.a, r.b, r.c) {
+                    (None, _, _) => format!("({} × {}) ÷ {}", b, c, d),
+                    (_, None, _) => format!("({} × {}) ÷ {}", a, d, c),
+                    (_, _, None) => format!("({} × {}) ÷ {}", a, d, b),
+                    _ => format!("({} × {}) ÷ {}", b, c, a),
+                };
+                vec![
+                    format!("The equation is {}/{} = {}/{}", a, b, c, d),
+                    format!("To find x, calculate {}", calculation),
+                ]
+            }
+            CalculatorResult::Simplify(r) => {
+                let (numerator, denominator) = (r.numerator / r.hcf, r.denominator / r.hcf);
+                let mut steps = vec![
+                    format!("The HCF of {} and {} is {}", r.numerator, r.denominator, r.hcf),
+                    format!(
+                        "{} ÷ {} = {} and {} ÷ {} = {}",
+                        r.numerator, r.hcf, numerator, r.denominator, r.hcf, denominator
+                    ),
+                ];
+                // A minus sign on the bottom is moved to the top, or cancels one already there.
+                if denominator < 0 {
+                    steps.push(format!("{}/{} = {}", numerator, denominator, r.result));
+                }
+                steps
+            }
+            CalculatorResult::MixedNumber(r) => {
+                let (numerator, denominator) = (r.improper.numerator().unsigned_abs(), r.improper.denominator());
+                let (whole, remainder) = (r.whole.unsigned_abs(), r.remainder.numerator().unsigned_abs());
+                if r.from_mixed {
+                    vec![format!("{} × {} + {} = {}", whole, denominator, remainder, numerator)]
+                } else {
+                    vec![format!("{} ÷ {} = {} remainder {}", numerator, denominator, whole, remainder)]
+                }
+            }
+            CalculatorResult::Ordering(r) => {
+                let mut working: Vec<String> = r
+                    .values
+                    .iter()
+                    .filter(|value| value.input != value.value.to_string())
+                    .map(|value| format!("{} = {}", value.input, value.value))
+                    .collect();
+                if let Some(common) = r.common_denominator {
+                    working.push(format!("The LCM of the denominators is {}", common));
+                    for value in &r.values {
+                        if let Some(numerator) = value.rewritten_numerator {
+                            working.push(format!("{} becomes {}/{}", value.input, numerator, common));
+                        }
+                    }
+                }
+                working
+            }
+            CalculatorResult::PrimeFactors(r) => {
+                r.steps.iter().map(|step| format!("{} ÷ {} = {}", step.number, step.prime, step.quotient)).collect()
+            }
+            CalculatorResult::CalculationBounds(calculation) => calculation.working(),
+            CalculatorResult::FractionArithmetic(calculation) => calculation.steps(),
+            CalculatorResult::StandardForm(conversion) => conversion.steps(),
+            CalculatorResult::StandardFormArithmetic(calculation) => calculation.steps(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Writes a count followed by a noun, adding an "s" unless the count is exactly 1.
+fn plural(count: &str, noun: &str, amount: usize) -> String {
+    if amount == 1 { format!("{} {}", count, noun) } else { format!("{} {}s", count, noun) }
+}
+
+/// The working line saying which rounding rule was applied.
+fn rule_used(mode: RoundingMode) -> String {
+    format!("Rounding rule: {}", mode.to_string().to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frac(s: &str) -> Fraction {
+        s.parse().unwrap()
+    }
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn serialises_with_calculator_tag() {
+        let result = CalculatorResult::Lcm(LcmResult::calculate(&[4, 6]).unwrap());
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            serde_json::json!({"calculator": "lcm", "numbers": [4, 6], "lcm": 12})
+        );
+        assert_eq!(result.answer(), "12");
+    }
+
+    #[test]
+    fn rejects_values_a_calculator_cannot_use() {
+        assert_eq!(MultiplesResult::calculate(&[3, 0], 5), Err(MathError::NotPositive));
+        assert_eq!(PrimeFactorsResult::calculate(1), Err(MathError::TooSmall { minimum: 2 }));
+        assert_eq!(RoundingResult::calculate(dec("52"), dec("0"), RoundingMode::HalfUp), Err(MathError::NotPositive));
+        assert_eq!(SquareRootEstimateResult::calculate(-4.0, 1), Err(MathError::NoRealSquareRoot));
+        assert_eq!(SquareRootEstimateResult::calculate(1e20, 1), Err(MathError::Overflow));
+        assert_eq!(HcfResult::calculate(&[]), Err(MathError::EmptyInput));
+    }
+
+    #[test]
+    fn serialises_fractions_as_numerator_and_denominator() {
+        let result = SimplifyResult::calculate(24, 30).unwrap();
+        assert_eq!(result.hcf, 6);
+        let working = CalculatorResult::Simplify(result.clone()).working();
+        assert_eq!(working, ["The HCF of 24 and 30 is 6", "24 ÷ 6 = 4 and 30 ÷ 6 = 5"]);
+        assert_eq!(
+            CalculatorResult::Simplify(SimplifyResult::calculate(-24, -30).unwrap()).working(),
+            ["The HCF of -24 and -30 is 6", "-24 ÷ 6 = -4 and -30 ÷ 6 = -5", "-4/-5 = 4/5"]
+        );
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            serde_json::json!({
+                "numerator": 24,
+                "denominator": 30,
+                "hcf": 6,
+                "result": {"numerator": 4, "denominator": 5}
+            })
+        );
+    }
+
+    #[test]
+    fn records_rewritten_numerators_when_ordering() {
+        let inputs = [("3/8", frac("3/8")), ("0.4", frac("0.4")), ("35%", frac("7/20"))];
+        let result = OrderingResult::calculate(&inputs, SortOrder::Ascending);
+        assert_eq!(result.common_denominator, Some(40));
+        let numerators: Vec<Option<i64>> = result.values.iter().map(|value| value.rewritten_numerator).collect();
+        assert_eq!(numerators, vec![Some(15), Some(16), Some(14)]);
+        assert_eq!(result.ordered, vec!["35%", "3/8", "0.4"]);
+        assert_eq!(serde_json::to_value(result.order).unwrap(), serde_json::json!("ascending"));
+    }
+
+    #[test]
+    fn shows_significant_zeros_in_answers() {
+        let result = CalculatorResult::SignificantFigures(SignificantFiguresResult::calculate(
+            dec("2.4996"),
+            3,
+            RoundingMode::HalfUp,
+        ).unwrap());
+        assert_eq!(result.answer(), "2.50 (half-up)");
+        let result = CalculatorResult::SignificantFigures(SignificantFiguresResult::calculate(
+            dec("49996"),
+            2,
+            RoundingMode::HalfUp,
+        ).unwrap());
+        assert_eq!(result.answer(), "50000 (5.0 × 10⁴, half-up)");
+        let result = DecimalPlacesResult::calculate(dec("5"), 2, RoundingMode::HalfUp).unwrap();
+        let result = CalculatorResult::DecimalPlaces(result);
+        assert_eq!(result.answer(), "5.00 (half-up)");
+        let result = RoundingResult::calculate(dec("3.145"), dec("0.01"), RoundingMode::HalfEven).unwrap();
+        let result = CalculatorResult::Rounding(result);
+        assert_eq!(result.answer(), "3.14 (half-even)");
+    }
+
+    #[test]
+    fn works_through_bodmas_step_by_step() {
+        let result = CalculatorResult::Bodmas(BodmasResult::calculate("30/(15-12)", false).unwrap());
+        assert_eq!(result.answer(), "10");
+        assert_eq!(
+            result.working(),
+            ["30 ÷ (15 - 12)", "= 30 ÷ 3   (Brackets: 15 - 12 = 3)", "= 10   (Division: 30 ÷ 3 = 10)"]
+        );
+        assert_eq!(serde_json::to_value(&result).unwrap()["steps"][1]["rule"], "division");
+
+        // An exact answer is given as a fraction or surd, falling back to a decimal.
+        let result = CalculatorResult::Bodmas(BodmasResult::calculate("1/3 + 1/6", true).unwrap());
+        assert_eq!(result.answer(), "1/2");
+        assert_eq!(serde_json::to_value(&result).unwrap()["exact"], "1/2");
+        let result = CalculatorResult::Bodmas(BodmasResult::calculate("2pi - 6", true).unwrap());
+        assert_eq!(result.answer(), "≈ 0.2831853072");
+
+        // The answer is the one the working ends with, not the f64 it was checked against, and
+        // is marked if it was worked out from a rounded value.
+        let answers = [("0.1+0.2", "0.3", "0.3"), ("2^0.5*2^0.5", "2", "≈ 2"), ("sin(pi/6)", "0.5", "≈ 0.5")];
+        for (expression, last_step, answer) in answers {
+            let result = BodmasResult::calculate(expression, false).unwrap();
+            assert_eq!(CalculatorResult::Bodmas(result.clone()).answer(), answer);
+            assert_eq!(result.steps.last().unwrap().expression, last_step);
+        }
+
+        // Rounded answers with more whole-number digits than they were rounded to are given in
+        // standard form, in the working as well as the answer.
+        let result = BodmasResult::calculate("3^40/7", true).unwrap();
+        assert_eq!(result.exact, None);
+        assert_eq!(CalculatorResult::Bodmas(result.clone()).answer(), "≈ 1.736809351 × 10¹⁸");
+        let result = BodmasResult::calculate("2^1000", false).unwrap();
+        assert_eq!(CalculatorResult::Bodmas(result.clone()).answer(), "≈ 1.071508607 × 10³⁰¹");
+        assert_eq!(result.steps[0].to_string(), "= 1.071508607 × 10³⁰¹   (Orders: 2^1000 ≈ 1.071508607 × 10³⁰¹)");
+    }
+
+    #[test]
+    fn gives_fraction_answers_in_every_form() {
+        let answer = |input: &str| CalculatorResult::FractionArithmetic(input.parse().unwrap()).answer();
+        assert_eq!(answer("1 1/2 + 1/4"), "7/4 = 1 3/4 = 1.75");
+        assert_eq!(answer("2 1/3 + 3/4"), "37/12 = 3 1/12 ≈ 3.083333333");
+        assert_eq!(answer("1/2 × 2/3"), "1/3 ≈ 0.3333333333");
+        assert_eq!(answer("1/2 + 1/2"), "1");
+
+        let result = CalculatorResult::FractionArithmetic("1 1/2 + 1/4".parse().unwrap());
+        assert_eq!(serde_json::to_value(&result).unwrap()["decimal"], "1.75");
+    }
+
+    #[test]
+    fn gives_estimates_next_to_the_exact_answer() {
+        let estimate = |expression, accuracy| {
+            CalculatorResult::Estimation(EstimationResult::calculate(expression, accuracy, RoundingMode::HalfUp).unwrap())
+        };
+        let result = estimate("(9.7*326)/(3.26)", Accuracy::SignificantFigures(1));
+        assert_eq!(result.answer(), "1000 (exact answer 970, 3.1% error)");
+        assert_eq!(
+            result.working()[..2],
+            ["Each number is rounded to 1 significant figure", "Rounding rule: round half up"]
+        );
+
+        let result = estimate("7.2 / 0.04", Accuracy::DecimalPlaces(1));
+        assert_eq!(result.answer(), "no estimate (exact answer 180)");
+        assert_eq!(result.working()[5], "Warning: the divisor 0.04 rounds to 0, so there is no estimate");
+    }
+
+    #[test]
+    fn describes_the_role_of_each_digit() {
+        let result = CalculatorResult::FigureCount(FigureCount::count("0.003040").unwrap());
+        assert_eq!(result.answer(), "0.003040 has 4 significant figures and 6 decimal places");
+        assert_eq!(result.working()[..2], ["Number: 0.003040", "Roles:  l.llssst"]);
+
+        let result = CalculatorResult::FigureCount(FigureCount::count("12000").unwrap());
+        assert_eq!(result.answer(), "12000 has 2 to 5 significant figures and 0 decimal places");
+        assert_eq!(
+            result.working()[3],
+            "Zeros at the end of a whole number, which are significant only if it was rounded to them: 3"
+        );
+    }
+
+    #[test]
+    fn describes_standard_form() {
+        let conversion = StandardFormConversion::new("0.00032".parse().unwrap());
+        let result = CalculatorResult::StandardForm(conversion);
+        assert_eq!(result.answer(), "0.00032 = 3.2 × 10⁻⁴");
+        assert_eq!(
+            serde_json::to_value(&result).unwrap()["standard_form"],
+            serde_json::json!({"mantissa": "3.2", "power": -4})
+        );
+
+        let calculation = StandardFormCalculation::new(
+            "2e3".parse().unwrap(),
+            crate::fractions::Operation::Divide,
+            "3".parse().unwrap(),
+        )
+        .unwrap();
+        let result = CalculatorResult::StandardFormArithmetic(calculation);
+        assert_eq!(result.answer(), "6.666666667 × 10² (to 10 significant figures)");
+        assert_eq!(serde_json::to_value(&result).unwrap()["calculator"], "standard_form_arithmetic");
+    }
+
+    #[test]
+    fn describes_bounds() {
+        let nearest = |step: &str| Accuracy::Nearest(dec(step));
+        let bounds = |n: &str, accuracy| {
+            CalculatorResult::Bounds(BoundsResult::calculate(dec(n), accuracy, Approximation::Rounded).unwrap())
+        };
+        let result = bounds("70", nearest("10"));
+        assert_eq!(result.answer(), "65 ≤ x < 75");
+        assert_eq!(
+            result.working(),
+            vec!["The error margin is 10 ÷ 2 = 5", "Lower bound: 70 - 5 = 65", "Upper bound: 70 + 5 = 75"]
+        );
+
+        let result = bounds("0.3", nearest("0.1"));
+        assert_eq!(result.answer(), "0.25 ≤ x < 0.35");
+
+        let result = bounds("6500", Accuracy::SignificantFigures(2));
+        assert_eq!(result.answer(), "6450 ≤ x < 6550");
+        assert_eq!(result.working()[0], "6500 to 2 significant figures is to the nearest 100");
+
+        let result = bounds("1000", Accuracy::SignificantFigures(1));
+        assert_eq!(result.answer(), "950 ≤ x < 1500");
+        assert_eq!(
+            result.working(),
+            vec![
+                "1000 to 1 significant figure is to the nearest 1000",
+                "The error margin is 1000 ÷ 2 = 500",
+                "Numbers nearer zero than 1000 are to the nearest 100, so on that side it is 100 ÷ 2 = 50",
+                "Lower bound: 1000 - 50 = 950",
+                "Upper bound: 1000 + 500 = 1500",
+            ]
+        );
+    }
+
+    #[test]
+    fn describes_bounds_of_truncated_numbers() {
+        let result = CalculatorResult::Bounds(BoundsResult::calculate(
+            dec("6.4"),
+            Accuracy::DecimalPlaces(1),
+            Approximation::Truncated,
+        ).unwrap());
+        assert_eq!(result.answer(), "6.4 ≤ x < 6.5");
+        assert_eq!(result.working()[2..], ["Lower bound: 6.4", "Upper bound: 6.4 + 0.1 = 6.5"]);
+        assert_eq!(
+            serde_json::to_value(&result).unwrap()["accuracy"],
+            serde_json::json!({"decimal_places": 1})
+        );
+        assert_eq!(serde_json::to_value(&result).unwrap()["interval"]["includes_upper"], false);
+    }
+
+    #[test]
+    fn describes_prime_factors() {
+        let result = CalculatorResult::PrimeFactors(PrimeFactorsResult::calculate(72).unwrap());
+        assert_eq!(result.answer(), "2³ × 3²");
+        assert_eq!(result.working(), ["72 ÷ 2 = 36", "36 ÷ 2 = 18", "18 ÷ 2 = 9", "9 ÷ 3 = 3", "3 ÷ 3 = 1"]);
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["product"], "2^3 * 3^2");
+        assert_eq!(json["steps"][0], serde_json::json!({"number": 72, "prime": 2, "quotient": 36}));
+    }
+
+    #[test]
+    fn writes_numbers_beyond_u64_as_json_strings() {
+        let mersenne = 2u128.pow(127) - 1;
+        let result = CalculatorResult::PrimeFactors(PrimeFactorsResult::calculate(mersenne).unwrap());
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["number"], mersenne.to_string());
+        assert_eq!(json["factors"], serde_json::json!([{"prime": mersenne.to_string(), "exponent": 1}]));
+        assert_eq!(json["steps"][0]["quotient"], 1);
+
+        let result = CalculatorResult::Primes(PrimesResult::calculate(&[mersenne, 9]));
+        assert_eq!(
+            serde_json::to_value(&result).unwrap()["checks"],
+            serde_json::json!([{"number": mersenne.to_string(), "is_prime": true}, {"number": 9, "is_prime": false}])
+        );
+    }
+
+    #[test]
+    fn lists_primes_with_statistics() {
+        let result = CalculatorResult::PrimeRange(PrimeRange::sieve(10, 30).unwrap());
+        assert_eq!(
+            result.answer(),
+            "11, 13, 17, 19, 23, 29\nThere are 6 primes between 10 and 30\n\
+             Twin primes (2 apart): 2 pairs, the first being 11 and 13\n\
+             The largest gap is 6, between 23 and 29\nThe average gap is 3.60"
+        );
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["calculator"], "prime_range");
+        assert_eq!(json["largest_gap"], serde_json::json!({"after": 23, "before": 29}));
+
+        let result = CalculatorResult::PrimeRange(PrimeRange::sieve(0, 10_000).unwrap());
+        assert!(result.answer().lines().next().unwrap().ends_with("… (the first 1000 of 1229)"));
+    }
+
+    #[test]
+    fn describes_bounds_of_calculations() {
+        let quantities = vec!["d = 120 (nearest 10)".parse().unwrap(), "t = 2.5 (1 d.p.)".parse().unwrap()];
+        let result = CalculatorResult::CalculationBounds(BoundsCalculation::calculate("d / t", quantities).unwrap());
+        assert_eq!(
+            result.answer(),
+            "Lower bound: 45.09803922, upper bound: 51.02040816 (to 10 significant figures)"
+        );
+        assert_eq!(result.working()[0], "d = 120 to the nearest 10, so 115 ≤ d < 125");
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["calculator"], "calculation_bounds");
+        assert_eq!(json["steps"][0]["upper"]["from"], serde_json::json!(["upper", "lower"]));
+    }
+
+    #[test]
+    fn serialises_rounded_numbers_as_exact_strings() {
+        let result = DecimalPlacesResult::calculate(dec("2.675"), 2, RoundingMode::HalfUp).unwrap();
+        let result = CalculatorResult::DecimalPlaces(result);
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            serde_json::json!({
+                "calculator": "decimal_places",
+                "number": "2.675",
+                "places": 2,
+                "mode": "half_up",
+                "result": "2.68"
+            })
+        );
+        assert_eq!(result.working(), vec!["Rounding rule: round half up"]);
+    }
+
+    #[test]
+    fn describes_mixed_number_conversions() {
+        let to_mixed = CalculatorResult::MixedNumber(MixedNumberResult::calculate(frac("7/3"), false));
+        assert_eq!(to_mixed.answer(), "2 1/3");
+        assert_eq!(to_mixed.working(), vec!["7 ÷ 3 = 2 remainder 1"]);
+
+        let from_mixed = CalculatorResult::MixedNumber(MixedNumberResult::calculate(frac("-2 1/3"), true));
+        assert_eq!(from_mixed.answer(), "-7/3");
+        assert_eq!(from_mixed.working(), vec!["2 × 3 + 1 = 7"]);
+    }
+}
@@ -0,0 +1,269 @@
+//! Rounding and estimation routines: decimal places, significant figures, rounding to a place
+//! value and square root estimates.
+//!
+//! Everything except the square root estimate works on exact [`Decimal`]s, so the answers match
+//! rounding the digits by hand. Each of them takes the [`RoundingMode`] to use, and the answers
+//! keep the digits they were rounded to, so 2.4996 to 3 s.f. is written `2.50`.
+
+use crate::decimal::{Decimal, RoundingMode, LARGEST_EXPONENT};
+use crate::error::MathError;
+use crate::standard_form::StandardForm;
+
+/// Rounds a number to a specified number of decimal places.
+///
+/// The rounding is done on the exact decimal digits, so 2.675 to 2 d.p. is 2.68 and 1.005 is
+/// 1.01, which `f64` arithmetic gets wrong. The answer always has `decimal_places` digits after
+/// the decimal point, so 5 to 2 d.p. is `5.00`.
+///
+/// Fails with [`MathError::Overflow`] for more than [`LARGEST_EXPONENT`] decimal places.
+pub fn round_to_decimal_places(n: &Decimal, decimal_places: u32, mode: RoundingMode) -> Result<Decimal, MathError> {
+    Ok(n.round_to_multiple(&decimal_place(decimal_places)?, mode))
+}
+
+/// The place value of the last of `places` decimal places, e.g. 0.01 for 2 d.p.
+///
+/// Fails with [`MathError::Overflow`] for more than [`LARGEST_EXPONENT`] places, the most a
+/// number can be read with, as an answer that long would take too long to write out.
+pub fn decimal_place(places: u32) -> Result<Decimal, MathError> {
+    i32::try_from(places)
+        .ok()
+        .filter(|places| *places <= LARGEST_EXPONENT)
+        .map(|places| Decimal::power_of_ten(-places))
+        .ok_or(MathError::Overflow)
+}
+
+/// Rounds a number to the given place value, e.g. 1 for the nearest whole number, 10 for the
+/// nearest ten or 0.1 for the nearest tenth.
+///
+/// For example, to round 5468.9 to the nearest ten: 5468.9 is 546.89 tens, which rounds to
+/// 547 tens, or 5470. The answer is written to the precision of the place value, so 2.96 to the
+/// nearest 0.1 is `3.0`.
+///
+/// # Panics
+///
+/// Panics if `place` is zero or negative.
+pub fn round_to_place(n: &Decimal, place: &Decimal, mode: RoundingMode) -> Decimal {
+    n.round_to_multiple(&place.trim(), mode)
+}
+
+/// Rounds a number to `sf` significant figures.
+///
+/// The place value of the first significant digit is found (10⁴ for 52691, 10⁻⁴ for
+/// 0.00097151), and the number is rounded to the place `sf - 1` digits below it. The answer keeps
+/// exactly `sf` significant figures, including any zeros at the end, so 2.4996 to 3 s.f. is
+/// `2.50`. Use [`format_significant_figures`] to show it when some of those zeros come before
+/// the decimal point.
+///
+/// Fails with [`MathError::Overflow`] for more than [`LARGEST_EXPONENT`] significant figures, or
+/// if the place to round to is too small to hold.
+pub fn round_to_significant_figures(n: &Decimal, sf: u32, mode: RoundingMode) -> Result<Decimal, MathError> {
+    // Zero significant figures is not meaningful, and zero has no leading digit to find.
+    let position = match n.leading_digit_position() {
+        Some(position) if sf > 0 => position,
+        _ => return Ok(Decimal::zero()),
+    };
+    let rounded = n.round_to_multiple(&last_figure(position, sf)?, mode);
+
+    // Rounding up can carry into a new leading digit, as 9.96 to 2 s.f. becomes 10.0. The answer
+    // is then a multiple of the next place value up, so it is written to that instead.
+    match rounded.leading_digit_position() {
+        Some(new_position) if new_position > position => {
+            Ok(rounded.round_to_multiple(&last_figure(new_position, sf)?, mode))
+        }
+        _ => Ok(rounded),
+    }
+}
+
+/// The place value of the last of `sf` significant figures, when the first is at
+/// `10^position`, e.g. 100 for 2 s.f. of 6500.
+///
+/// Fails with [`MathError::Overflow`] for more than [`LARGEST_EXPONENT`] significant figures, or
+/// if the place is too small to hold.
+pub fn last_figure(position: i32, sf: u32) -> Result<Decimal, MathError> {
+    i32::try_from(sf)
+        .ok()
+        .filter(|sf| *sf <= LARGEST_EXPONENT)
+        .and_then(|sf| position.checked_sub(sf))
+        .and_then(|power| power.checked_add(1))
+        .map(Decimal::power_of_ten)
+        .ok_or(MathError::Overflow)
+}
+
+/// Writes a number that has been rounded to significant figures. When zeros at the end of a
+/// whole number are significant, the number is also given in standard form to show them, as
+/// `50000 (5.0 × 10⁴)` for 50000 to 2 s.f.
+pub fn format_significant_figures(n: &Decimal) -> String {
+    if n.has_significant_trailing_zeros() {
+        format!("{} ({})", n, StandardForm::from_decimal(n))
+    } else {
+        n.to_string()
+    }
+}
+
+/// Rounds a number to one significant figure, as used when estimating.
+pub fn round_to_one_sf(n: &Decimal, mode: RoundingMode) -> Result<Decimal, MathError> {
+    round_to_significant_figures(n, 1, mode)
+}
+
+/// Estimates the square root of a number by linear interpolation between the two perfect
+/// squares it lies between.
+///
+/// For example, 40 lies between 36 (6²) and 49 (7²), 4/13 of the way along, so its square root
+/// is estimated as 6 + 4/13 ≈ 6.31. Fails as [`surrounding_squares`] does.
+pub fn estimate_square_root(n: f64) -> Result<f64, MathError> {
+    let (root, lower_square, upper_square) = surrounding_squares(n)?;
+    let integer_part = root as f64;
+    let distance_from_lower = n - lower_square as f64;
+    let total_distance = (upper_square - lower_square) as f64;
+    Ok(integer_part + distance_from_lower / total_distance)
+}
+
+/// Estimates the square root of a number as [`estimate_square_root`] does, rounded half up to
+/// `places` decimal places.
+///
+/// The estimate is worked out exactly from the digits of `n`, so every place asked for is right,
+/// where an `f64` holds only about 16 significant figures. Fails as [`surrounding_squares`] and
+/// [`round_to_decimal_places`] do.
+pub fn estimate_square_root_to_places(n: f64, places: u32) -> Result<Decimal, MathError> {
+    let step = decimal_place(places)?;
+    let (root, _, _) = surrounding_squares(n)?;
+    // The root is below 2³², so it and 2 × root + 1 fit in an i64, though the squares may not.
+    let root = Decimal::from(root as i64);
+    let distance_from_lower = Decimal::from_f64(n).ok_or(MathError::Overflow)?.sub(&root.mul(&root));
+    let total_distance = root.add(&root).add(&Decimal::from(1));
+    // Rounding the quotient twice could only go wrong after a run of 20 nines, and a fraction
+    // over a denominator this small never has one, so 20 figures more than are shown are enough.
+    let (fraction, _) = distance_from_lower.div(&total_distance, places + 20).expect("the squares are different");
+    Ok(root.add(&fraction).round_to_multiple(&step, RoundingMode::HalfUp))
+}
+
+/// Finds the perfect squares either side of `n`.
+///
+/// Returns `(root, root², (root + 1)²)` where `root` is the whole-number part of √n. Fails with
+/// [`MathError::Overflow`] if `(root + 1)²` is too large for a `u64`, as it is for 1e20.
+pub fn surrounding_squares(n: f64) -> Result<(u64, u64, u64), MathError> {
+    let root = n.sqrt().trunc() as u64;
+    let next = root.checked_add(1).ok_or(MathError::Overflow)?;
+    let upper_square = next.checked_mul(next).ok_or(MathError::Overflow)?;
+    Ok((root, root * root, upper_square))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decimal::RoundingMode::*;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn rounds_to_decimal_places() {
+        assert_eq!(round_to_decimal_places(&dec("4.56789"), 2, HalfUp).unwrap(), dec("4.57"));
+        assert_eq!(round_to_decimal_places(&dec("2.5"), 0, HalfUp).unwrap(), dec("3"));
+    }
+
+    #[test]
+    fn rounds_the_digits_as_written() {
+        assert_eq!(round_to_decimal_places(&dec("2.675"), 2, HalfUp).unwrap().to_string(), "2.68");
+        assert_eq!(round_to_decimal_places(&dec("1.005"), 2, HalfUp).unwrap().to_string(), "1.01");
+        assert_eq!(round_to_decimal_places(&dec("0.30000000000000004"), 2, HalfUp).unwrap().to_string(), "0.30");
+        assert_eq!(round_to_place(&dec("0.15"), &dec("0.1"), HalfUp).to_string(), "0.2");
+        assert_eq!(round_to_significant_figures(&dec("1.255"), 3, HalfUp).unwrap().to_string(), "1.26");
+    }
+
+    #[test]
+    fn rounds_with_the_chosen_mode() {
+        assert_eq!(round_to_decimal_places(&dec("2.345"), 2, HalfEven).unwrap(), dec("2.34"));
+        assert_eq!(round_to_decimal_places(&dec("2.355"), 2, HalfEven).unwrap(), dec("2.36"));
+        assert_eq!(round_to_decimal_places(&dec("2.349"), 2, Truncate).unwrap(), dec("2.34"));
+        assert_eq!(round_to_place(&dec("5468.9"), &dec("100"), Floor), dec("5400"));
+        assert_eq!(round_to_place(&dec("5401"), &dec("100"), Ceiling), dec("5500"));
+        assert_eq!(round_to_significant_figures(&dec("-0.00097151"), 2, Floor).unwrap(), dec("-0.00098"));
+        assert_eq!(round_to_one_sf(&dec("15"), HalfEven).unwrap(), dec("20"));
+        assert_eq!(round_to_one_sf(&dec("25"), HalfEven).unwrap(), dec("20"));
+    }
+
+    #[test]
+    fn keeps_trailing_zeros() {
+        assert_eq!(round_to_decimal_places(&dec("5"), 2, HalfUp).unwrap().to_string(), "5.00");
+        assert_eq!(round_to_decimal_places(&dec("2.996"), 2, HalfUp).unwrap().to_string(), "3.00");
+        assert_eq!(round_to_significant_figures(&dec("2.4996"), 3, HalfUp).unwrap().to_string(), "2.50");
+        assert_eq!(round_to_significant_figures(&dec("9.96"), 2, HalfUp).unwrap().to_string(), "10");
+        assert_eq!(round_to_significant_figures(&dec("0.09996"), 2, HalfUp).unwrap().to_string(), "0.10");
+        assert_eq!(round_to_place(&dec("2.96"), &dec("0.1"), HalfUp).to_string(), "3.0");
+        assert_eq!(round_to_place(&dec("5468.9"), &dec("10.0"), HalfUp).to_string(), "5470");
+    }
+
+    #[test]
+    fn shows_significant_zeros_in_whole_numbers() {
+        let format = |n: &str, sf| {
+            format_significant_figures(&round_to_significant_figures(&dec(n), sf, HalfUp).unwrap())
+        };
+        assert_eq!(format("50012", 2), "50000 (5.0 × 10⁴)");
+        assert_eq!(format("99.96", 3), "100 (1.00 × 10²)");
+        assert_eq!(format("52691", 2), "53000");
+        assert_eq!(format("2.4996", 3), "2.50");
+    }
+
+    #[test]
+    fn rounds_to_place_value() {
+        assert_eq!(round_to_place(&dec("5468.9"), &dec("1"), HalfUp), dec("5469"));
+        assert_eq!(round_to_place(&dec("5468.9"), &dec("10"), HalfUp), dec("5470"));
+        assert_eq!(round_to_place(&dec("5468.9"), &dec("100"), HalfUp), dec("5500"));
+        assert_eq!(round_to_place(&dec("5468.9"), &dec("1000"), HalfUp), dec("5000"));
+    }
+
+    #[test]
+    fn rounds_to_significant_figures() {
+        assert_eq!(round_to_significant_figures(&dec("52691"), 1, HalfUp).unwrap(), dec("50000"));
+        assert_eq!(round_to_significant_figures(&dec("6.578"), 2, HalfUp).unwrap(), dec("6.6"));
+        assert_eq!(round_to_significant_figures(&dec("0.00097151"), 3, HalfUp).unwrap().to_string(), "0.000972");
+        assert_eq!(round_to_significant_figures(&dec("-0.00097151"), 3, HalfUp).unwrap().to_string(), "-0.000972");
+        assert_eq!(round_to_significant_figures(&dec("0"), 3, HalfUp).unwrap(), dec("0"));
+        assert_eq!(round_to_significant_figures(&dec("123"), 0, HalfUp).unwrap(), dec("0"));
+    }
+
+    #[test]
+    fn fails_when_the_place_is_out_of_range() {
+        let figures = i32::MAX.unsigned_abs();
+        assert_eq!(round_to_significant_figures(&dec("1e-1000"), figures, HalfUp), Err(MathError::Overflow));
+        assert_eq!(round_to_significant_figures(&dec("5"), u32::MAX, HalfUp), Err(MathError::Overflow));
+        assert_eq!(round_to_significant_figures(&dec("5"), 2_000_000_000, HalfUp), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn fails_when_there_are_too_many_decimal_places() {
+        assert_eq!(round_to_decimal_places(&dec("2.5"), 1000, HalfUp).unwrap().decimal_places(), 1000);
+        for places in [1001, 20_000_000, 2_147_483_648, 3_000_000_000] {
+            assert_eq!(round_to_decimal_places(&dec("2.5"), places, HalfUp), Err(MathError::Overflow));
+        }
+    }
+
+    #[test]
+    fn rounds_to_one_significant_figure() {
+        assert_eq!(round_to_one_sf(&dec("9.7"), HalfUp).unwrap(), dec("10"));
+        assert_eq!(round_to_one_sf(&dec("326"), HalfUp).unwrap(), dec("300"));
+        assert_eq!(round_to_one_sf(&dec("0"), HalfUp).unwrap(), dec("0"));
+    }
+
+    #[test]
+    fn estimates_square_roots() {
+        assert_eq!(surrounding_squares(40.0), Ok((6, 36, 49)));
+        assert!((estimate_square_root(40.0).unwrap() - (6.0 + 4.0 / 13.0)).abs() < 1e-12);
+        assert_eq!(estimate_square_root(49.0), Ok(7.0));
+        assert_eq!(surrounding_squares(1e19), Ok((3162277660, 9999999998935075600, 10000000005259630921)));
+        assert_eq!(surrounding_squares(1e20), Err(MathError::Overflow));
+        assert_eq!(estimate_square_root(f64::INFINITY), Err(MathError::Overflow));
+
+        assert_eq!(estimate_square_root_to_places(40.0, 2), Ok(dec("6.31")));
+        assert_eq!(estimate_square_root_to_places(49.0, 3), Ok(dec("7.000")));
+        // 10 is 1/7 of the way from 9 to 16, and 1/7 = 0.142857 recurring.
+        let estimate = estimate_square_root_to_places(10.0, 400).unwrap().to_string();
+        assert_eq!(estimate, format!("3.{}1429", "142857".repeat(66)));
+        assert_eq!(estimate_square_root_to_places(2.5, 1), Ok(dec("1.5")));
+        assert_eq!(estimate_square_root_to_places(10.5, 20), Ok(dec("3.21428571428571428571")));
+        assert_eq!(estimate_square_root_to_places(10.0, 1001), Err(MathError::Overflow));
+        assert_eq!(estimate_square_root_to_places(1e20, 1), Err(MathError::Overflow));
+    }
+}
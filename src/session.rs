@@ -0,0 +1,472 @@
+//! A calculator session, which remembers the last answer, variables and functions between
+//! calculations.
+//!
+//! Each line entered is a calculation such as `2ans + 1`, an assignment such as `r = 3.5` or
+//! `area = pi*r^2`, whose value is worked out straight away, or a function such as
+//! `f(x) = 2x + 1`, which is kept as written and worked out each time it is used.
+//!
+//! Names are replaced by their values before anything is worked out, so the BODMAS working and
+//! exact answers from the other modules apply to the result unchanged.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::decimal::Decimal;
+use crate::error::{MathError, Span};
+use crate::exact::{evaluate_exact, Exact};
+use crate::expressions::{evaluate_expr, format_approximate, CONSTANTS, FUNCTIONS};
+use crate::parser::{parse, parse_tokens, tokenize, BinaryOp, Expr, ExprKind, TokenKind};
+
+/// The name that always stands for the last answer.
+pub const ANS: &str = "ans";
+
+/// How deeply functions may call one another before they are taken to go on for ever.
+const LARGEST_DEPTH: usize = 32;
+
+/// How many parts an expression may have once its names are replaced. A function that uses its
+/// value twice doubles the length of what it is given, so a few calls can make an expression too
+/// long to hold.
+const LARGEST_SIZE: usize = 10_000;
+
+/// A value kept by a session, along with its exact form if it has one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answer {
+    pub value: f64,
+    pub exact: Option<Exact>,
+}
+
+impl Answer {
+    fn of(expr: &Expr) -> Result<Answer, MathError> {
+        Ok(Answer { value: evaluate_expr(expr)?, exact: evaluate_exact(expr)? })
+    }
+
+    /// The value written into an expression in place of the name at `span`. Decimals that end
+    /// are written as they are; other exact values are written as a fraction or surd in
+    /// brackets, so `1/3` keeps its value. Values with no exact form are written with every
+    /// figure the `f64` holds, so nothing is lost by giving a value a name; they are only rounded
+    /// when the value itself is shown.
+    fn to_expr(&self, span: Span) -> Expr {
+        let decimal = match &self.exact {
+            Some(exact) => match exact.to_decimal() {
+                Some(decimal) => decimal,
+                None => {
+                    let mut expr = parse(&exact.to_string()).expect("an exact value is written as an expression");
+                    set_span(&mut expr, span);
+                    return Expr { kind: ExprKind::Brackets(Box::new(expr)), span };
+                }
+            },
+            None => Decimal::from_f64(self.value).expect("every value in a session is finite"),
+        };
+        if decimal.is_negative() {
+            let number = Expr { kind: ExprKind::Number(decimal.abs()), span };
+            let negative = Expr { kind: ExprKind::Negate(Box::new(number)), span };
+            Expr { kind: ExprKind::Brackets(Box::new(negative)), span }
+        } else {
+            Expr { kind: ExprKind::Number(decimal), span }
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    /// Writes the value as a decimal if it ends, otherwise as a fraction or surd, falling back
+    /// to 10 significant figures.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.exact {
+            Some(exact) => match exact.to_decimal() {
+                Some(decimal) => write!(f, "{}", decimal),
+                None => write!(f, "{}", exact),
+            },
+            None => write!(f, "{}", format_approximate(self.value)),
+        }
+    }
+}
+
+/// A function defined in a session, such as `f(x) = 2x + 1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub parameters: Vec<String>,
+    /// The expression as written, with the spans of its definition.
+    pub body: Expr,
+}
+
+/// What a line entered in a session did.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Entry {
+    /// A calculation, with the session's names replaced by their values. Its answer is now `ans`.
+    Calculation { expr: Expr, answer: Answer },
+    /// A variable was given the value of an expression, which is now also `ans`.
+    Assignment { name: String, expr: Expr, answer: Answer },
+    /// A function was defined.
+    Definition { name: String },
+}
+
+/// The answers, variables and functions remembered from one calculation to the next.
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    ans: Option<Answer>,
+    variables: BTreeMap<String, Answer>,
+    functions: BTreeMap<String, Function>,
+}
+
+impl Session {
+    pub fn new() -> Session {
+        Session::default()
+    }
+
+    /// The last answer, if there has been one.
+    pub fn ans(&self) -> Option<&Answer> {
+        self.ans.as_ref()
+    }
+
+    /// Reads a line and carries it out: a calculation, an assignment such as `r = 3.5` or a
+    /// function definition such as `f(x) = 2x + 1`.
+    ///
+    /// Nothing is remembered from a line with a mistake in it.
+    pub fn enter(&mut self, input: &str) -> Result<Entry, MathError> {
+        let tokens = tokenize(input)?;
+        let Some(equals) = tokens.iter().position(|token| token.kind == TokenKind::Equals) else {
+            let expr = self.expand(&parse_tokens(input, &tokens)?, &BTreeMap::new(), 0, &mut 0)?;
+            let answer = Answer::of(&expr)?;
+            self.ans = Some(answer.clone());
+            return Ok(Entry::Calculation { expr, answer });
+        };
+
+        let target = parse_tokens(input, &tokens[..equals])?;
+        let value = parse_tokens(input, &tokens[equals + 1..])?;
+        match target.kind {
+            ExprKind::Name(name) => {
+                check_can_define(&name)?;
+                if self.functions.contains_key(&name) {
+                    let message = format!("{} is a function, so it cannot also be given a value", name);
+                    return Err(MathError::InvalidExpression { message });
+                }
+                let expr = self.expand(&value, &BTreeMap::new(), 0, &mut 0)?;
+                let answer = Answer::of(&expr)?;
+                self.variables.insert(name.clone(), answer.clone());
+                self.ans = Some(answer.clone());
+                Ok(Entry::Assignment { name, expr, answer })
+            }
+            ExprKind::Call { name, arguments } => {
+                check_can_define(&name)?;
+                if self.variables.contains_key(&name) {
+                    let message = format!("{} has a value, so it cannot also be a function", name);
+                    return Err(MathError::InvalidExpression { message });
+                }
+                let mut parameters = Vec::new();
+                let mut scope = BTreeMap::new();
+                for argument in arguments {
+                    match &argument.kind {
+                        ExprKind::Name(parameter) if !scope.contains_key(parameter) => {
+                            check_can_define(parameter)?;
+                            parameters.push(parameter.clone());
+                            scope.insert(parameter.clone(), argument);
+                        }
+                        _ => {
+                            let token = argument.to_string();
+                            return Err(MathError::InvalidToken { token, position: argument.span });
+                        }
+                    }
+                }
+                // Expanding the body with its parameters left as names checks every other name.
+                self.expand(&value, &scope, 0, &mut 0)?;
+                self.functions.insert(name.clone(), Function { parameters, body: value });
+                Ok(Entry::Definition { name })
+            }
+            _ => Err(MathError::InvalidExpression {
+                message: "only a name such as r, or a function such as f(x), can be given a value".to_string(),
+            }),
+        }
+    }
+
+    /// The session's definitions, one per line: `ans`, then the variables and functions in
+    /// alphabetical order.
+    pub fn definitions(&self) -> Vec<String> {
+        let ans = self.ans.iter().map(|answer| format!("{} = {}", ANS, answer));
+        let variables = self.variables.iter().map(|(name, answer)| format!("{} = {}", name, answer));
+        let functions = self
+            .functions
+            .iter()
+            .map(|(name, function)| format!("{}({}) = {}", name, function.parameters.join(", "), function.body));
+        ans.chain(variables).chain(functions).collect()
+    }
+
+    /// Replaces every name in an expression with its value and every call to a function of the
+    /// session with its body. `scope` holds the values of the parameters of the function being
+    /// worked out, `depth` counts the calls it is inside and `size` counts the parts written so far.
+    fn expand(
+        &self,
+        expr: &Expr,
+        scope: &BTreeMap<String, Expr>,
+        depth: usize,
+        size: &mut usize,
+    ) -> Result<Expr, MathError> {
+        grow(size, 1)?;
+        match &expr.kind {
+            ExprKind::Name(name) => self.value_of(name, expr.span, scope, size),
+            ExprKind::Call { name, arguments } if !FUNCTIONS.contains(&name.as_str()) => {
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| self.expand(argument, scope, depth, size))
+                    .collect::<Result<_, _>>()?;
+                self.call(name, arguments, expr.span, scope, depth, size)
+            }
+            _ => {
+                let mut expanded = expr.clone();
+                for child in expanded.children_mut() {
+                    *child = self.expand(child, scope, depth, size)?;
+                }
+                Ok(expanded)
+            }
+        }
+    }
+
+    /// The value of a name, for writing at `span`.
+    fn value_of(
+        &self,
+        name: &str,
+        span: Span,
+        scope: &BTreeMap<String, Expr>,
+        size: &mut usize,
+    ) -> Result<Expr, MathError> {
+        if let Some(value) = scope.get(name) {
+            grow(size, parts_in(value))?;
+            return Ok(value.clone());
+        }
+        if name == ANS {
+            return self.ans.as_ref().map(|answer| answer.to_expr(span)).ok_or_else(|| MathError::InvalidExpression {
+                message: "there is no answer yet for ans to stand for".to_string(),
+            });
+        }
+        if let Some(answer) = self.variables.get(name) {
+            return Ok(answer.to_expr(span));
+        }
+        if CONSTANTS.contains(&name) {
+            return Ok(Expr { kind: ExprKind::Name(name.to_string()), span });
+        }
+        Err(MathError::InvalidToken { token: name.to_string(), position: span })
+    }
+
+    /// Works out a call to a function of the session, or a value written before a bracket, as
+    /// in `r(r + 1)`, which multiplies. `span` covers the call as written.
+    fn call(
+        &self,
+        name: &str,
+        arguments: Vec<Expr>,
+        span: Span,
+        scope: &BTreeMap<String, Expr>,
+        depth: usize,
+        size: &mut usize,
+    ) -> Result<Expr, MathError> {
+        let name_span = Span::new(span.start, span.start + name.len());
+        let Some(function) = self.functions.get(name) else {
+            let value = self.value_of(name, name_span, scope, size)?;
+            let Ok([argument]) = <[Expr; 1]>::try_from(arguments) else {
+                return Err(MathError::InvalidToken { token: name.to_string(), position: name_span });
+            };
+            let argument = Expr { span: argument.span, kind: ExprKind::Brackets(Box::new(argument)) };
+            let (left, right) = (Box::new(value), Box::new(argument));
+            return Ok(Expr { kind: ExprKind::Binary { op: BinaryOp::Multiply, left, right, implicit: true }, span });
+        };
+
+        if arguments.len() != function.parameters.len() {
+            let count = function.parameters.len();
+            return Err(MathError::InvalidExpression {
+                message: format!(
+                    "{} takes {} value{}, as in {}({})",
+                    name,
+                    count,
+                    if count == 1 { "" } else { "s" },
+                    name,
+                    function.parameters.join(", ")
+                ),
+            });
+        }
+        if depth == LARGEST_DEPTH {
+            let message = format!("{} goes on calling functions for ever", name);
+            return Err(MathError::InvalidExpression { message });
+        }
+        let scope = function.parameters.iter().cloned().zip(arguments.into_iter().map(bracketed)).collect();
+        // The body was written on another line, so any mistake in it is pointed out at the call.
+        let mut body = function.body.clone();
+        set_span(&mut body, span);
+        Ok(bracketed(self.expand(&body, &scope, depth + 1, size)?))
+    }
+}
+
+/// Adds `parts` to the size of an expression being written out, failing once it is past
+/// [`LARGEST_SIZE`].
+fn grow(size: &mut usize, parts: usize) -> Result<(), MathError> {
+    *size += parts;
+    if *size > LARGEST_SIZE {
+        let message = format!("the functions make an expression of more than {} parts", LARGEST_SIZE);
+        return Err(MathError::InvalidExpression { message });
+    }
+    Ok(())
+}
+
+/// How many parts an expression has.
+fn parts_in(expr: &Expr) -> usize {
+    1 + expr.children().into_iter().map(parts_in).sum::<usize>()
+}
+
+/// Checks that a name is free to be given a value, which the constants, the built-in functions
+/// and `ans` are not.
+fn check_can_define(name: &str) -> Result<(), MathError> {
+    if name == ANS || CONSTANTS.contains(&name) || FUNCTIONS.contains(&name) {
+        let message = format!("{} already has a meaning, so it cannot be changed", name);
+        Err(MathError::InvalidExpression { message })
+    } else {
+        Ok(())
+    }
+}
+
+/// Puts an expression in brackets unless it is a single value already.
+fn bracketed(expr: Expr) -> Expr {
+    match expr.kind {
+        ExprKind::Number(_) | ExprKind::Name(_) | ExprKind::Call { .. } | ExprKind::Brackets(_) => expr,
+        _ => Expr { span: expr.span, kind: ExprKind::Brackets(Box::new(expr)) },
+    }
+}
+
+/// Moves every part of an expression to `span`.
+fn set_span(expr: &mut Expr, span: Span) {
+    expr.span = span;
+    for child in expr.children_mut() {
+        set_span(child, span);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::bodmas::bodmas_steps;
+
+    /// Enters each line in turn, returning what the last one gave.
+    fn enter_all(session: &mut Session, lines: &[&str]) -> Result<Entry, MathError> {
+        let (last, rest) = lines.split_last().expect("at least one line");
+        for line in rest {
+            session.enter(line).unwrap();
+        }
+        session.enter(last)
+    }
+
+    fn calculation(entry: Result<Entry, MathError>) -> (String, f64) {
+        match entry.unwrap() {
+            Entry::Calculation { expr, answer } => (expr.to_string(), answer.value),
+            entry => panic!("expected a calculation, got {:?}", entry),
+        }
+    }
+
+    #[test]
+    fn remembers_answers_and_variables() {
+        let mut session = Session::new();
+        assert_eq!(calculation(enter_all(&mut session, &["2 + 3", "ans * 2"])), ("5 × 2".to_string(), 10.0));
+        let (written, value) = calculation(enter_all(&mut session, &["r = 3.5", "2r - ans"]));
+        assert_eq!((written.as_str(), value), ("2 × 3.5 - 3.5", 3.5));
+
+        let area = session.enter("area = pi*r^2").unwrap();
+        assert!(matches!(area, Entry::Assignment { name, answer, .. } if name == "area" && answer.value == PI * 12.25));
+        assert_eq!(calculation(enter_all(&mut session, &["t = -2", "t^2"])), ("(-2)^2".to_string(), 4.0));
+        assert_eq!(calculation(session.enter("r(r + 1)")), ("3.5(3.5 + 1)".to_string(), 15.75));
+    }
+
+    #[test]
+    fn keeps_exact_values_exact() {
+        let mut session = Session::new();
+        let (written, _) = calculation(enter_all(&mut session, &["third = 1/3", "3third"]));
+        assert_eq!(written, "3(1 ÷ 3)");
+        assert_eq!(session.ans().unwrap().to_string(), "1");
+        session.enter("√8").unwrap();
+        assert_eq!(session.ans().unwrap().to_string(), "2√2");
+    }
+
+    #[test]
+    fn works_out_functions_with_their_values() {
+        let mut session = Session::new();
+        let (written, value) = calculation(enter_all(&mut session, &["f(x) = 2x+1", "f(3)"]));
+        assert_eq!((written.as_str(), value), ("(2 × 3 + 1)", 7.0));
+        assert_eq!(calculation(session.enter("2f(1 + 1)")), ("2(2(1 + 1) + 1)".to_string(), 10.0));
+        assert_eq!(calculation(enter_all(&mut session, &["g(x, y) = x^2 + y", "g(-1, 2)"])).1, 3.0);
+        assert_eq!(session.definitions(), ["ans = 3", "f(x) = 2x + 1", "g(x, y) = x^2 + y"]);
+    }
+
+    #[test]
+    fn works_through_functions_that_round() {
+        let mut session = Session::new();
+        let Entry::Calculation { expr, answer } = enter_all(&mut session, &["f(x) = x/3 + 1/7", "f(1)"]).unwrap() else {
+            panic!("expected a calculation");
+        };
+        let working: Vec<String> = bodmas_steps(&expr).unwrap().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            working,
+            vec![
+                "= (0.3333333333 + 1 ÷ 7)   (Brackets: 1 ÷ 3 ≈ 0.3333333333)",
+                "= (0.3333333333 + 0.1428571429)   (Brackets: 1 ÷ 7 ≈ 0.1428571429)",
+                "= 0.4761904762   (Brackets: 0.3333333333 + 0.1428571429 ≈ 0.4761904762)",
+            ]
+        );
+        assert_eq!(answer.to_string(), "10/21");
+    }
+
+    #[test]
+    fn keeps_inexact_answers_in_full() {
+        let mut session = Session::new();
+        let (written, value) = calculation(enter_all(&mut session, &["r = 3.5", "area = pi*r^2", "ans*2"]));
+        assert_eq!((written.as_str(), value), ("38.48451000647496 × 2", PI * 24.5));
+        let steps = bodmas_steps(&parse(&written).unwrap()).unwrap();
+        assert_eq!(steps.last().unwrap().expression, "76.96902001294992");
+        // A value with no exact form is only rounded when it is shown.
+        assert_eq!(session.definitions()[..2], ["ans = 76.96902001294992", "area = 38.48451001"]);
+
+        let (_, value) = calculation(enter_all(&mut session, &["x = sin(1)", "x - 0.84147098480789"]));
+        assert!((value - (1f64.sin() - 0.84147098480789)).abs() < 1e-20);
+    }
+
+    #[test]
+    fn keeps_variables_and_functions_apart() {
+        let mut session = Session::new();
+        assert_eq!(calculation(enter_all(&mut session, &["r = 2", "r = r + 1", "r"])).1, 3.0);
+        assert!(matches!(enter_all(&mut session, &["f(x) = 2x", "f = 2"]), Err(MathError::InvalidExpression { .. })));
+        assert_eq!(calculation(session.enter("f(3)")), ("(2 × 3)".to_string(), 6.0));
+        assert!(matches!(session.enter("r(x) = x + 1"), Err(MathError::InvalidExpression { .. })));
+        assert_eq!(calculation(session.enter("r(2)")), ("3(2)".to_string(), 6.0));
+        assert_eq!(session.definitions(), ["ans = 6", "r = 3", "f(x) = 2x"]);
+    }
+
+    #[test]
+    fn explains_what_cannot_be_done() {
+        let mut session = Session::new();
+        assert!(matches!(session.enter("ans + 1"), Err(MathError::InvalidExpression { .. })));
+        assert!(matches!(session.enter("pi = 3"), Err(MathError::InvalidExpression { .. })));
+        assert_eq!(
+            session.enter("f(x) = 2y"),
+            Err(MathError::InvalidToken { token: "y".to_string(), position: Span::new(8, 9) })
+        );
+        assert!(session.definitions().is_empty());
+
+        let loop_lines = ["g(x) = x", "f(x) = g(x)", "g(x) = f(x)", "f(1)"];
+        assert!(matches!(enter_all(&mut session, &loop_lines), Err(MathError::InvalidExpression { .. })));
+        assert!(matches!(session.enter("f(1, 2)"), Err(MathError::InvalidExpression { .. })));
+        assert_eq!(
+            session.enter("1 + h(2)"),
+            Err(MathError::InvalidToken { token: "h".to_string(), position: Span::new(4, 5) })
+        );
+    }
+
+    #[test]
+    fn refuses_expressions_too_long_to_hold() {
+        let mut session = Session::new();
+        let lines = ["f(x) = x + x", "g(x) = f(f(f(f(f(f(f(f(f(f(x))))))))))", "g(1)"];
+        let (_, value) = calculation(enter_all(&mut session, &lines));
+        assert_eq!(value, 1024.0);
+
+        let too_long = Err(MathError::InvalidExpression {
+            message: "the functions make an expression of more than 10000 parts".to_string(),
+        });
+        assert_eq!(session.enter("h(x) = g(g(g(x)))"), too_long);
+        assert_eq!(session.enter("g(g(g(1)))"), too_long);
+        assert_eq!(session.definitions().len(), 3);
+    }
+}
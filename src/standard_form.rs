@@ -0,0 +1,558 @@
+//! Numbers in standard form, `A × 10ⁿ` with 1 ≤ |A| < 10, and calculations with them.
+//!
+//! The working follows GCSE mark schemes: for × and ÷ the numbers and the powers are dealt with
+//! separately, for + and − both numbers are first written with the same power, and an answer
+//! such as `12.8 × 10⁸` is always finished off by writing it properly in standard form.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::decimal::{Decimal, LARGEST_EXPONENT};
+use crate::error::{MathError, Span};
+use crate::fractions::Operation;
+use crate::number::{parse_superscript, superscript};
+
+/// How many significant figures a division that recurs is given to.
+pub const DIVISION_FIGURES: u32 = 10;
+
+/// A number written as `mantissa × 10^power`.
+///
+/// The number is not necessarily normalised: `32 × 10⁴` and a plain `320000` (with a power of
+/// 0) are both held as written until [`StandardForm::normalised`] is called.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StandardForm {
+    pub mantissa: Decimal,
+    pub power: i32,
+}
+
+impl StandardForm {
+    /// Writes a number in standard form, keeping every digit of its precision, so 50000 to
+    /// 2 s.f. gives `5.0 × 10⁴` and 0.000972 gives `9.72 × 10⁻⁴`. Zero is written with a power
+    /// of 0.
+    pub fn from_decimal(number: &Decimal) -> StandardForm {
+        match number.leading_digit_position() {
+            Some(power) => StandardForm { mantissa: number.shifted(-power), power },
+            None => StandardForm { mantissa: Decimal::zero(), power: 0 },
+        }
+    }
+
+    /// The number written out in full, e.g. 320000 for `3.2 × 10⁵`.
+    pub fn to_decimal(&self) -> Decimal {
+        self.mantissa.shifted(self.power)
+    }
+
+    /// Returns `true` if the number is properly in standard form, with 1 ≤ |A| < 10. Zero is
+    /// only normalised with a power of 0.
+    pub fn is_normalised(&self) -> bool {
+        match self.mantissa.leading_digit_position() {
+            Some(position) => position == 0,
+            None => self.power == 0,
+        }
+    }
+
+    /// The same number properly in standard form, e.g. `3.2 × 10⁵` for `32 × 10⁴`.
+    ///
+    /// Zeros at the end of a number written in full, such as `320000`, only hold the place
+    /// value, so they are left out of the mantissa: it becomes `3.2 × 10⁵`, not `3.20000 × 10⁵`.
+    pub fn normalised(&self) -> StandardForm {
+        if self.is_written_in_full() {
+            return StandardForm::from_decimal(&self.mantissa.trim_whole_number_zeros());
+        }
+        StandardForm::from_decimal(&self.to_decimal())
+    }
+
+    /// The same number without any trailing zeros in the mantissa, for answers worked out
+    /// exactly rather than rounded.
+    fn trimmed(&self) -> StandardForm {
+        StandardForm { mantissa: self.mantissa.trim(), power: self.power }
+    }
+
+    /// Returns `true` if the number was written without a power of ten, such as `320000`.
+    fn is_written_in_full(&self) -> bool {
+        self.power == 0 && !self.is_normalised()
+    }
+}
+
+impl fmt::Display for StandardForm {
+    /// Writes the number as `3.2 × 10⁵`. Zero is written as `0`, and a number read without a
+    /// power of ten as it was typed, e.g. `320000`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.mantissa.is_zero() && self.power == 0 || self.is_written_in_full() {
+            return write!(f, "{}", self.mantissa);
+        }
+        write!(f, "{} × 10{}", self.mantissa, superscript(self.power.into()))
+    }
+}
+
+impl FromStr for StandardForm {
+    type Err = MathError;
+
+    /// Reads a number such as `3.2 × 10^5`, `3.2x10^-4`, `3.2 * 10⁵` or `3.2e5`. A number
+    /// written without a power of ten, such as `320000`, is read with a power of 0.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let s = input.trim();
+        if s.is_empty() {
+            return Err(MathError::EmptyInput);
+        }
+        let invalid = |part: &str| {
+            let part = if part.is_empty() { s } else { part };
+            MathError::InvalidToken { token: part.to_string(), position: Span::of(part, input) }
+        };
+
+        let (mantissa, power) = if let Some(i) = s.find(['×', 'x', 'X', '*']) {
+            let sign_length = s[i..].chars().next().map_or(1, char::len_utf8);
+            let power = s[i + sign_length..].trim();
+            let exponent = power
+                .strip_prefix("10")
+                .and_then(|exponent| match exponent.strip_prefix('^') {
+                    Some(exponent) => read_power(exponent),
+                    None => parse_superscript(exponent).and_then(|n| i32::try_from(n).ok()),
+                })
+                .ok_or_else(|| invalid(power))?;
+            (s[..i].trim(), exponent)
+        } else if let Some((mantissa, power)) = s.split_once(['e', 'E']) {
+            (mantissa, read_power(power).ok_or_else(|| invalid(power))?)
+        } else {
+            (s, 0)
+        };
+
+        if mantissa.is_empty() {
+            return Err(invalid(s));
+        }
+        if power.unsigned_abs() > LARGEST_EXPONENT.unsigned_abs() {
+            return Err(MathError::Overflow);
+        }
+        let offset = Span::of(mantissa, input).start;
+        let mantissa: Decimal = mantissa.parse().map_err(|e: MathError| e.shifted(offset))?;
+        Ok(StandardForm { mantissa, power })
+    }
+}
+
+/// Reads a power of ten such as `5`, `-4`, `−4` or `(-4)`.
+fn read_power(text: &str) -> Option<i32> {
+    let text = text.trim();
+    let text = text.strip_prefix('(').and_then(|t| t.strip_suffix(')')).unwrap_or(text);
+    text.trim().replace('−', "-").parse().ok()
+}
+
+/// Returns `true` if `token` starts with a power of ten such as `10^5` or `10⁵`, so that a
+/// `×` before it is part of a number rather than an operator.
+fn is_power_of_ten(token: &str) -> bool {
+    token
+        .strip_prefix("10")
+        .is_some_and(|rest| rest.starts_with('^') || rest.starts_with(|c: char| "⁰¹²³⁴⁵⁶⁷⁸⁹⁻".contains(c)))
+}
+
+/// Writes "1 place" or "5 places".
+fn places(count: u32) -> String {
+    if count == 1 { "1 place".to_string() } else { format!("{} places", count) }
+}
+
+/// The working for converting a number to or from standard form.
+///
+/// A number written in full, such as `320000`, is converted to standard form. A number written
+/// with a power of ten, such as `3.2 × 10⁵` or `32 × 10⁴`, is normalised if need be and then
+/// written out in full.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StandardFormConversion {
+    /// The number as it was written.
+    pub input: StandardForm,
+    /// The number written out in full.
+    pub number: Decimal,
+    pub standard_form: StandardForm,
+}
+
+impl StandardFormConversion {
+    pub fn new(input: StandardForm) -> StandardFormConversion {
+        StandardFormConversion { number: input.to_decimal(), standard_form: input.normalised(), input }
+    }
+
+    /// Returns `true` if the number was written in full and is being put into standard form.
+    pub fn converts_to_standard_form(&self) -> bool {
+        self.input.power == 0
+    }
+
+    /// The answer, e.g. `320000 = 3.2 × 10⁵` or `32 × 10⁴ = 3.2 × 10⁵ = 320000`.
+    pub fn answer(&self) -> String {
+        if self.converts_to_standard_form() {
+            format!("{} = {}", self.number, self.standard_form)
+        } else if self.input.is_normalised() {
+            format!("{} = {}", self.input, self.number)
+        } else {
+            format!("{} = {} = {}", self.input, self.standard_form, self.number)
+        }
+    }
+
+    /// Describes each step of the working, one line per step.
+    pub fn steps(&self) -> Vec<String> {
+        let mut steps = Vec::new();
+        if self.number.is_zero() {
+            steps.push("0 is written as 0 in standard form".to_string());
+            return steps;
+        }
+
+        // Step 1: Move the decimal point so that there is one digit, which is not 0, in front
+        // of it, changing the power to make up for it.
+        if !self.input.is_normalised() || self.converts_to_standard_form() {
+            steps.push(format!(
+                "Place the decimal point after the first significant figure: {}",
+                self.standard_form.mantissa
+            ));
+            let moved = self.standard_form.power - self.input.power;
+            let distance = places(moved.unsigned_abs());
+            steps.push(match (moved.signum(), self.converts_to_standard_form()) {
+                (0, _) => "The decimal point does not move, so the power of 10 is 0".to_string(),
+                (1, true) => format!("The decimal point moves {} to the left, so the power of 10 is {}", distance, moved),
+                (_, true) => format!("The decimal point moves {} to the right, so the power of 10 is {}", distance, moved),
+                (1, false) => format!(
+                    "The decimal point moves {} to the left, so add {} to the power: {} = {}",
+                    distance, moved, self.input, self.standard_form
+                ),
+                (_, false) => format!(
+                    "The decimal point moves {} to the right, so subtract {} from the power: {} = {}",
+                    distance,
+                    -moved,
+                    self.input,
+                    self.standard_form
+                ),
+            });
+        }
+
+        // Step 2: Write a number given in standard form out in full.
+        if !self.converts_to_standard_form() {
+            let power = self.standard_form.power;
+            let direction = if power > 0 { "right" } else { "left" };
+            steps.push(format!(
+                "Multiply by 10{} by moving the decimal point {} to the {}: {}",
+                superscript(power.into()),
+                places(power.unsigned_abs()),
+                direction,
+                self.number
+            ));
+        }
+        steps
+    }
+}
+
+/// The full working for a calculation with numbers in standard form, such as
+/// `3.2 × 10⁵ × 4 × 10³`.
+///
+/// Both numbers are first written properly in standard form. For multiplication and division
+/// the numbers in front are multiplied or divided and the powers added or subtracted; for
+/// addition and subtraction the number with the smaller power is rewritten with the larger power
+/// so that the numbers in front can be combined. The answer is then normalised.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StandardFormCalculation {
+    pub left: StandardForm,
+    pub operation: Operation,
+    pub right: StandardForm,
+    /// The two numbers in the form they are combined in: in standard form for × and ÷, and
+    /// with the same power for + and −.
+    pub combined_operands: [StandardForm; 2],
+    /// The answer before it is normalised, e.g. `12.8 × 10⁸`.
+    pub unnormalised: StandardForm,
+    /// The answer in standard form.
+    pub result: StandardForm,
+    /// `false` if a division recurs and the answer was rounded to [`DIVISION_FIGURES`]
+    /// significant figures.
+    pub exact: bool,
+}
+
+impl StandardFormCalculation {
+    /// Works through `left operation right`, failing when dividing by zero or when the power of
+    /// ten is too large to hold.
+    pub fn new(left: StandardForm, operation: Operation, right: StandardForm) -> Result<StandardFormCalculation, MathError> {
+        let (a, b) = (left.normalised(), right.normalised());
+        let mut exact = true;
+        let (combined_operands, unnormalised) = match operation {
+            Operation::Multiply => {
+                let mantissa = a.mantissa.mul(&b.mantissa).trim();
+                let power = a.power.checked_add(b.power).ok_or(MathError::Overflow)?;
+                let product = StandardForm { mantissa, power };
+                ([a, b], product)
+            }
+            Operation::Divide => {
+                let (mantissa, is_exact) =
+                    a.mantissa.div(&b.mantissa, DIVISION_FIGURES).ok_or(MathError::DivisionByZero)?;
+                exact = is_exact;
+                let power = a.power.checked_sub(b.power).ok_or(MathError::Overflow)?;
+                let quotient = StandardForm { mantissa, power };
+                ([a, b], quotient)
+            }
+            Operation::Add | Operation::Subtract => {
+                // Zero can take any power, so it takes the power of the other number.
+                let power = match (a.mantissa.is_zero(), b.mantissa.is_zero()) {
+                    (true, _) => b.power,
+                    (_, true) => a.power,
+                    _ => a.power.max(b.power),
+                };
+                let rewrite = |n: StandardForm| {
+                    let mantissa = if n.mantissa.is_zero() { Decimal::zero() } else { n.mantissa.shifted(n.power - power) };
+                    StandardForm { mantissa, power }
+                };
+                let (a, b) = (rewrite(a), rewrite(b));
+                let mantissa = if operation == Operation::Add {
+                    a.mantissa.add(&b.mantissa)
+                } else {
+                    a.mantissa.sub(&b.mantissa)
+                };
+                let combined = StandardForm { mantissa: mantissa.trim(), power };
+                ([a, b], combined)
+            }
+        };
+        let result = unnormalised.normalised().trimmed();
+        Ok(StandardFormCalculation { left, operation, right, combined_operands, unnormalised, result, exact })
+    }
+
+    /// Describes each step of the working, one line per step.
+    pub fn steps(&self) -> Vec<String> {
+        let mut steps = Vec::new();
+        let [a, b] = &self.combined_operands;
+
+        // Step 1: Write both numbers properly in standard form.
+        for n in [&self.left, &self.right] {
+            if !n.is_normalised() {
+                steps.push(format!("Write {} in standard form: {}", n, n.normalised()));
+            }
+        }
+
+        // Step 2: Combine the numbers.
+        let power = |p: i32| format!("10{}", superscript(p.into()));
+        match self.operation {
+            Operation::Multiply => {
+                steps.push(format!(
+                    "Multiply the numbers: {} × {} = {}",
+                    a.mantissa, b.mantissa, self.unnormalised.mantissa
+                ));
+                steps.push(format!(
+                    "Add the powers: {} × {} = {}",
+                    power(a.power),
+                    power(b.power),
+                    power(self.unnormalised.power)
+                ));
+            }
+            Operation::Divide => {
+                let equals = if self.exact { "=" } else { "≈" };
+                steps.push(format!(
+                    "Divide the numbers: {} ÷ {} {} {}",
+                    a.mantissa, b.mantissa, equals, self.unnormalised.mantissa
+                ));
+                steps.push(format!(
+                    "Subtract the powers: {} ÷ {} = {}",
+                    power(a.power),
+                    power(b.power),
+                    power(self.unnormalised.power)
+                ));
+            }
+            Operation::Add | Operation::Subtract => {
+                for (original, rewritten) in [(&self.left, a), (&self.right, b)] {
+                    if !rewritten.mantissa.is_zero() && original.normalised().power != rewritten.power {
+                        steps.push(format!(
+                            "Rewrite {} with the power {}: {}",
+                            original.normalised(),
+                            power(rewritten.power),
+                            rewritten
+                        ));
+                    }
+                }
+                steps.push(format!(
+                    "{} the numbers in front of {}: {} {} {} = {}",
+                    if self.operation == Operation::Add { "Add" } else { "Subtract" },
+                    power(self.unnormalised.power),
+                    a.mantissa,
+                    self.operation,
+                    b.mantissa,
+                    self.unnormalised.mantissa
+                ));
+            }
+        }
+
+        // Step 3: Write the answer properly in standard form.
+        if self.result.mantissa.is_zero() {
+            steps.push("The answer is 0".to_string());
+        } else if self.unnormalised.is_normalised() {
+            steps.push(format!("{} is already in standard form", self.result));
+        } else {
+            steps.push(format!("Write the answer in standard form: {} = {}", self.unnormalised, self.result));
+        }
+        steps
+    }
+}
+
+/// Reads a calculation such as `3.2 × 10^5 × 4 × 10^3` or `3.2e5 + 4e3`.
+///
+/// The operator must have a space either side. A `×` followed by a power of ten such as `10^5`
+/// belongs to the number before it, so `4 × 10^3` is a single number. A single number with no
+/// operator gives [`MathError::MissingOperator`].
+pub fn parse_standard_form_calculation(input: &str) -> Result<(StandardForm, Operation, StandardForm), MathError> {
+    let tokens: Vec<&str> = input.split_whitespace().collect();
+    let (symbol, operation) = tokens
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter_map(|(i, token)| Operation::from_symbol(token).map(|operation| (i, *token, operation)))
+        .find(|(i, _, operation)| {
+            *operation != Operation::Multiply || !tokens.get(i + 1).is_some_and(|next| is_power_of_ten(next))
+        })
+        .map(|(_, token, operation)| (token, operation))
+        .ok_or(MathError::MissingOperator)?;
+    let position = Span::of(symbol, input);
+    let left: StandardForm = input[..position.start].parse()?;
+    let right = &input[position.end..];
+    let offset = Span::of(right, input).start;
+    let right: StandardForm = right.parse().map_err(|e: MathError| e.shifted(offset))?;
+    Ok((left, operation, right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sf(s: &str) -> StandardForm {
+        s.parse().unwrap()
+    }
+
+    fn calculate(input: &str) -> StandardFormCalculation {
+        let (left, operation, right) = parse_standard_form_calculation(input).unwrap();
+        StandardFormCalculation::new(left, operation, right).unwrap()
+    }
+
+    #[test]
+    fn reads_every_notation() {
+        for input in ["3.2e5", "3.2E5", "3.2×10^5", "3.2 x 10^5", "3.2 * 10⁵", "3.2 × 10^(+5)"] {
+            assert_eq!(sf(input), StandardForm { mantissa: "3.2".parse().unwrap(), power: 5 }, "{}", input);
+        }
+        assert_eq!(sf("3.2 x 10^-4").power, -4);
+        assert_eq!(sf("3.2 × 10^−4").power, -4);
+        assert_eq!(sf("3.2 × 10⁻⁴").power, -4);
+        assert_eq!(sf("320000").power, 0);
+        assert_eq!(sf("-32 × 10^4").to_string(), "-32 × 10⁴");
+    }
+
+    #[test]
+    fn rejects_invalid_numbers() {
+        assert_eq!("  ".parse::<StandardForm>(), Err(MathError::EmptyInput));
+        assert_eq!(
+            "3.2 × 11^5".parse::<StandardForm>(),
+            Err(MathError::InvalidToken { token: "11^5".to_string(), position: Span::new(7, 11) })
+        );
+        assert_eq!(
+            "3.2a × 10^5".parse::<StandardForm>(),
+            Err(MathError::InvalidToken { token: "3.2a".to_string(), position: Span::new(0, 4) })
+        );
+        assert!("× 10^5".parse::<StandardForm>().is_err());
+        assert!("3.2e".parse::<StandardForm>().is_err());
+        assert_eq!("3.2e2147483647".parse::<StandardForm>(), Err(MathError::Overflow));
+        assert_eq!("3.2 × 10^-1001".parse::<StandardForm>(), Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn converts_to_and_from_standard_form() {
+        let write = |n: &str| StandardForm::from_decimal(&n.parse().unwrap()).to_string();
+        assert_eq!(sf("320000").normalised().to_string(), "3.2 × 10⁵");
+        assert_eq!(write("0.000320"), "3.20 × 10⁻⁴");
+        assert_eq!(write("-0.000972"), "-9.72 × 10⁻⁴");
+        assert_eq!(write("7"), "7 × 10⁰");
+        assert_eq!(write("0"), "0");
+        assert_eq!(write("5.0e4"), "5.0 × 10⁴");
+
+        assert_eq!(sf("3.2 x 10^-4").to_decimal().to_string(), "0.00032");
+        assert_eq!(sf("32 × 10^4").normalised().to_string(), "3.2 × 10⁵");
+        assert!(!sf("0.5e3").is_normalised());
+        assert!(sf("-9.9e-3").is_normalised());
+    }
+
+    #[test]
+    fn shows_the_working_for_a_conversion() {
+        let conversion = StandardFormConversion::new(sf("0.00032"));
+        assert_eq!(conversion.answer(), "0.00032 = 3.2 × 10⁻⁴");
+        assert_eq!(
+            conversion.steps(),
+            vec![
+                "Place the decimal point after the first significant figure: 3.2",
+                "The decimal point moves 4 places to the right, so the power of 10 is -4",
+            ]
+        );
+
+        let conversion = StandardFormConversion::new(sf("32 × 10^4"));
+        assert_eq!(conversion.answer(), "32 × 10⁴ = 3.2 × 10⁵ = 320000");
+        assert_eq!(
+            conversion.steps()[1],
+            "The decimal point moves 1 place to the left, so add 1 to the power: 32 × 10⁴ = 3.2 × 10⁵"
+        );
+        assert_eq!(conversion.steps()[2], "Multiply by 10⁵ by moving the decimal point 5 places to the right: 320000");
+
+        assert_eq!(StandardFormConversion::new(sf("3.2e5")).answer(), "3.2 × 10⁵ = 320000");
+    }
+
+    #[test]
+    fn multiplies_and_normalises() {
+        let calculation = calculate("3.2 × 10^5 × 4 × 10^3");
+        assert_eq!(calculation.result.to_string(), "1.28 × 10⁹");
+        assert_eq!(
+            calculation.steps(),
+            vec![
+                "Multiply the numbers: 3.2 × 4 = 12.8",
+                "Add the powers: 10⁵ × 10³ = 10⁸",
+                "Write the answer in standard form: 12.8 × 10⁸ = 1.28 × 10⁹",
+            ]
+        );
+        assert_eq!(calculate("2.5e3 * 4e-5").result.to_string(), "1 × 10⁻¹");
+
+        let huge = StandardForm { mantissa: "3.2".parse().unwrap(), power: i32::MAX };
+        let calculation = StandardFormCalculation::new(huge.clone(), Operation::Multiply, huge);
+        assert_eq!(calculation, Err(MathError::Overflow));
+    }
+
+    #[test]
+    fn divides_exactly_or_to_ten_figures() {
+        let calculation = calculate("6 × 10^5 ÷ 3 × 10^2");
+        assert_eq!(calculation.result.to_string(), "2 × 10³");
+        assert!(calculation.exact);
+
+        let calculation = calculate("1e3 / 3e-2");
+        assert_eq!(calculation.result.to_string(), "3.333333333 × 10⁴");
+        assert!(!calculation.exact);
+        assert_eq!(calculation.steps()[0], "Divide the numbers: 1 ÷ 3 ≈ 0.3333333333");
+        assert_eq!(calculation.steps()[2], "Write the answer in standard form: 0.3333333333 × 10⁵ = 3.333333333 × 10⁴");
+
+        assert_eq!(
+            StandardFormCalculation::new(sf("1e3"), Operation::Divide, sf("0e5")),
+            Err(MathError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn adds_and_subtracts_with_the_same_power() {
+        let calculation = calculate("3.2 × 10^5 + 4 × 10^3");
+        assert_eq!(calculation.result.to_string(), "3.24 × 10⁵");
+        assert_eq!(
+            calculation.steps(),
+            vec![
+                "Rewrite 4 × 10³ with the power 10⁵: 0.04 × 10⁵",
+                "Add the numbers in front of 10⁵: 3.2 + 0.04 = 3.24",
+                "3.24 × 10⁵ is already in standard form",
+            ]
+        );
+
+        let calculation = calculate("3.2 x 10^-4 - 32 x 10^-5");
+        assert_eq!(calculation.steps()[0], "Write 32 × 10⁻⁵ in standard form: 3.2 × 10⁻⁴");
+        assert_eq!(calculation.result.to_string(), "0");
+        assert_eq!(calculate("1.5e4 - 9e3").result.to_string(), "6 × 10³");
+        assert_eq!(calculate("320000 + 0").result.to_string(), "3.2 × 10⁵");
+    }
+
+    #[test]
+    fn reads_calculations() {
+        assert_eq!(parse_standard_form_calculation("3.2 × 10^5"), Err(MathError::MissingOperator));
+        assert_eq!(parse_standard_form_calculation("-3.2e5"), Err(MathError::MissingOperator));
+        let (left, operation, right) = parse_standard_form_calculation("3.2e5 x 4").unwrap();
+        assert_eq!((left.power, operation, right.to_string()), (5, Operation::Multiply, "4 × 10⁰".to_string()));
+        assert_eq!(
+            parse_standard_form_calculation("3.2e5 + 4 × 1O^3"),
+            Err(MathError::InvalidToken { token: "1O^3".to_string(), position: Span::new(13, 17) })
+        );
+    }
+}